@@ -25,6 +25,8 @@ ByteBuffer validate_analysis(const uint8_t *request_ptr, int32_t request_length)
 
 ByteBuffer release(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBuffer get_remaining_budget(const uint8_t *request_ptr, int32_t request_length);
+
 void whitenoise_destroy_bytebuffer(ByteBuffer buffer);
 
 // direct api
@@ -87,40 +87,44 @@ pub extern "C" fn compute_privacy_usage(
 ) -> ffi_support::ByteBuffer {
     let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
 
-    let response = proto::ResponseComputePrivacyUsage {
-        value: match proto::RequestComputePrivacyUsage::decode(request_buffer) {
-            Ok(request) => {
-                let proto::RequestComputePrivacyUsage {
-                    analysis, release
-                } = request;
-
-
-                let run = || -> Result<proto::PrivacyUsage> {
-                    let proto::Analysis {
-                        privacy_definition, computation_graph
-                    } = analysis
-                        .ok_or_else(|| Error::from("analysis must be defined"))?;
-                    let release = parse_release(release
-                        .ok_or_else(|| Error::from("release must be defined"))?);
-
-                    let privacy_definition = privacy_definition
-                        .ok_or_else(|| Error::from("privacy_definition must be defined"))?;
-                    let computation_graph = computation_graph
-                        .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
-
-                    whitenoise_validator::compute_privacy_usage(privacy_definition, computation_graph, release)
-                };
-
-                match run() {
-                    Ok(x) =>
-                        Some(proto::response_compute_privacy_usage::Value::Data(x)),
-                    Err(err) =>
-                        Some(proto::response_compute_privacy_usage::Value::Error(serialize_error(err))),
-                }
+    let (value, composition) = match proto::RequestComputePrivacyUsage::decode(request_buffer) {
+        Ok(request) => {
+            let proto::RequestComputePrivacyUsage {
+                analysis, release
+            } = request;
+
+
+            let run = || -> Result<(proto::PrivacyUsage, proto::privacy_definition::Composition)> {
+                let proto::Analysis {
+                    privacy_definition, computation_graph
+                } = analysis
+                    .ok_or_else(|| Error::from("analysis must be defined"))?;
+                let release = parse_release(release
+                    .ok_or_else(|| Error::from("release must be defined"))?);
+
+                let privacy_definition = privacy_definition
+                    .ok_or_else(|| Error::from("privacy_definition must be defined"))?;
+                let computation_graph = computation_graph
+                    .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
+
+                whitenoise_validator::compute_privacy_usage(privacy_definition, computation_graph, release)
+            };
+
+            match run() {
+                Ok((x, composition)) =>
+                    (Some(proto::response_compute_privacy_usage::Value::Data(x)), composition),
+                Err(err) =>
+                    (Some(proto::response_compute_privacy_usage::Value::Error(serialize_error(err))),
+                     proto::privacy_definition::Composition::Basic),
             }
-            Err(_) =>
-                Some(proto::response_compute_privacy_usage::Value::Error(serialize_error("unable to parse protobuf".into())))
         }
+        Err(_) =>
+            (Some(proto::response_compute_privacy_usage::Value::Error(serialize_error("unable to parse protobuf".into()))),
+             proto::privacy_definition::Composition::Basic)
+    };
+
+    let response = proto::ResponseComputePrivacyUsage {
+        value, composition: composition as i32
     };
     buffer_to_ptr(response)
 }
@@ -449,4 +453,41 @@ pub extern "C" fn release(
 }
 
 
+/// FFI wrapper for [get_remaining_budget](../whitenoise_runtime/utilities/ledger/fn.get_remaining_budget.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestGetRemainingBudget](proto/struct.RequestGetRemainingBudget.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferRuntime struct](struct.ByteBufferRuntime.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseGetRemainingBudget](proto/struct.ResponseGetRemainingBudget.html)
+#[cfg(feature = "use-runtime")]
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn get_remaining_budget(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseGetRemainingBudget {
+        value: match proto::RequestGetRemainingBudget::decode(request_buffer) {
+            Ok(request) => {
+                let proto::RequestGetRemainingBudget {
+                    ledger_path, dataset_id
+                } = request;
+
+                match whitenoise_runtime::utilities::ledger::get_remaining_budget(&ledger_path, &dataset_id) {
+                    Ok(x) =>
+                        Some(proto::response_get_remaining_budget::Value::Data(x)),
+                    Err(err) =>
+                        Some(proto::response_get_remaining_budget::Value::Error(serialize_error(err))),
+                }
+            }
+            Err(_) =>
+                Some(proto::response_get_remaining_budget::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
 ffi_support::define_bytebuffer_destructor!(whitenoise_destroy_bytebuffer);
@@ -14,13 +14,16 @@ use noisy_float::types::n64;
 
 
 impl Evaluable for proto::Count {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_memory_utilization = privacy_definition.as_ref()
+            .map(|v| v.protect_memory_utilization).unwrap_or(false);
+
         Ok(ReleaseNode::new(if self.distinct {
             match take_argument(&mut arguments, "data")?.array()? {
-                Array::Bool(data) => count_distinct(&data)?.into(),
-                Array::Float(data) => count_distinct(&data.mapv(|v| n64(v as f64)))?.into(),
-                Array::Int(data) => count_distinct(&data)?.into(),
-                Array::Str(data) => count_distinct(&data)?.into()
+                Array::Bool(data) => count_distinct(&data, protect_memory_utilization)?.into(),
+                Array::Float(data) => count_distinct(&data.mapv(|v| n64(v as f64)), protect_memory_utilization)?.into(),
+                Array::Int(data) => count_distinct(&data, protect_memory_utilization)?.into(),
+                Array::Str(data) => count_distinct(&data, protect_memory_utilization)?.into()
             }
         } else {
             match take_argument(&mut arguments, "data")? {
@@ -64,6 +67,7 @@ pub fn count<T>(data: &ArrayD<T>) -> Result<ArrayD<Integer>> {
 ///
 /// # Arguments
 /// * `data` - Data for which you want a distinct count.
+/// * `fixed_capacity` - Whether to allocate space for every record up front, so that memory does not depend on the number of unique values.
 ///
 /// # Return
 /// Number of rows in data.
@@ -73,11 +77,15 @@ pub fn count<T>(data: &ArrayD<T>) -> Result<ArrayD<Integer>> {
 /// use ndarray::{ArrayD, arr1, arr2};
 /// use whitenoise_runtime::components::count::count_distinct;
 /// let data = arr2(&[ [false, false, true], [true, false, true] ]).into_dyn();
-/// let distinct = count_distinct(&data).unwrap();
+/// let distinct = count_distinct(&data, false).unwrap();
 /// assert_eq!(distinct, arr2(&[ [2, 1, 1] ]).into_dyn());
 /// ```
-pub fn count_distinct<T: Eq + Hash>(data: &ArrayD<T>) -> Result<ArrayD<Integer>> {
-    let counts = data.gencolumns().into_iter().map(|column| {
+pub fn count_distinct<T: Eq + Hash>(data: &ArrayD<T>, fixed_capacity: bool) -> Result<ArrayD<Integer>> {
+    let counts = data.gencolumns().into_iter().map(|column| if fixed_capacity {
+        let mut distinct = HashSet::<&T>::with_capacity(column.len());
+        distinct.extend(column.iter());
+        distinct.len() as Integer
+    } else {
         HashSet::<&T>::from_iter(column.iter()).len() as Integer
     }).collect::<Vec<Integer>>();
 
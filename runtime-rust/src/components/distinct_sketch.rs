@@ -0,0 +1,226 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Value, Array, ReleaseNode};
+use crate::components::Evaluable;
+use ndarray::ArrayD;
+use whitenoise_validator::{proto, Float, Integer};
+use whitenoise_validator::utilities::take_argument;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+
+impl Evaluable for proto::DistinctSketch {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        if self.size <= 0 {
+            return Err("size: must be positive".into())
+        }
+        let size = self.size as usize;
+
+        let partitions = match take_argument(&mut arguments, "data")? {
+            Value::Array(array) => vec![array],
+            Value::Partitions(partitions) => partitions.into_iter()
+                .map(|(_, partition)| partition.array())
+                .collect::<Result<Vec<Array>>>()?,
+            _ => return Err("data: must be an array or partitions".into())
+        };
+
+        let shape = partitions.first()
+            .ok_or_else(|| Error::from("data: must have at least one partition"))?.shape();
+
+        // the sketches of each partition are merged
+        let mut sketches: Option<Vec<Vec<bool>>> = None;
+        for partition in partitions {
+            let partition_sketches = match partition {
+                Array::Bool(data) => distinct_sketch(&data, size)?,
+                Array::Float(data) => distinct_sketch(&data.mapv(Float::to_bits), size)?,
+                Array::Int(data) => distinct_sketch(&data, size)?,
+                Array::Str(data) => distinct_sketch(&data, size)?
+            };
+            sketches = Some(match sketches {
+                Some(sketches) => merge_sketches(sketches, partition_sketches)?,
+                None => partition_sketches
+            });
+        }
+
+        let occupied = sketches.unwrap_or_default().iter()
+            .map(|sketch| sketch.iter().filter(|bit| **bit).count() as Integer)
+            .collect::<Vec<Integer>>();
+
+        // ensure counts are of correct dimension
+        Ok(ReleaseNode::new(match shape.len() {
+            1 => ndarray::Array::from_shape_vec(vec![], occupied)?,
+            2 => ndarray::Array::from_shape_vec(vec![1, occupied.len()], occupied)?,
+            _ => return Err("invalid data shape for DistinctSketch".into())
+        }.into()))
+    }
+}
+
+impl Evaluable for proto::DistinctSketchEstimate {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let occupied = match take_argument(&mut arguments, "data")?.array()? {
+            Array::Float(data) => data,
+            Array::Int(data) => data.mapv(|v| v as Float),
+            _ => return Err("data: atomic type must be numeric".into())
+        };
+
+        Ok(ReleaseNode::new(occupied
+            .mapv(|v| distinct_sketch_estimate(v, self.size as Float).round() as Integer)
+            .into()))
+    }
+}
+
+/// Sketches the unique members of each column of the data.
+///
+/// Each member is hashed into one bit of a bitmap with `size` bits.
+/// The memory used is fixed by the size, and does not depend on the number of unique members.
+///
+/// # Arguments
+/// * `data` - Data for which you want a sketch of the unique members.
+/// * `size` - Number of bits in the sketch of each column.
+///
+/// # Return
+/// A bitmap for each column of the data.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use whitenoise_runtime::components::distinct_sketch::distinct_sketch;
+/// let data = arr2(&[ [1, 2], [1, 3], [1, 2] ]).into_dyn();
+/// let sketches = distinct_sketch(&data, 1024).unwrap();
+/// assert_eq!(sketches[0].iter().filter(|bit| **bit).count(), 1);
+/// ```
+pub fn distinct_sketch<T: Hash>(data: &ArrayD<T>, size: usize) -> Result<Vec<Vec<bool>>> {
+    if size == 0 {
+        return Err("size: must be positive".into())
+    }
+
+    Ok(data.gencolumns().into_iter().map(|column| {
+        let mut sketch = vec![false; size];
+        column.iter().for_each(|member| {
+            // the hasher is constructed with fixed keys, so sketches of different partitions agree
+            let mut hasher = DefaultHasher::new();
+            member.hash(&mut hasher);
+            sketch[(hasher.finish() % size as u64) as usize] = true;
+        });
+        sketch
+    }).collect())
+}
+
+/// Merges the sketches of two partitions into the sketch of their union.
+///
+/// # Arguments
+/// * `left` - A bitmap for each column of the first partition.
+/// * `right` - A bitmap for each column of the second partition.
+///
+/// # Return
+/// A bitmap for each column, with the bits occupied in either partition.
+pub fn merge_sketches(left: Vec<Vec<bool>>, right: Vec<Vec<bool>>) -> Result<Vec<Vec<bool>>> {
+    if left.len() != right.len() {
+        return Err("partitions must have the same number of columns".into())
+    }
+
+    left.into_iter().zip(right)
+        .map(|(left, right)| if left.len() == right.len() {
+            Ok(left.iter().zip(right).map(|(l, r)| *l || r).collect())
+        } else {
+            Err("sketches must have the same size".into())
+        })
+        .collect()
+}
+
+/// Linear counting estimate of the number of unique members.
+///
+/// If `n` members are hashed uniformly into `size` bits, the expected fraction of free bits is `(1 - 1/size)^n`,
+/// which is approximately `exp(-n/size)`. Inverting at the observed fraction gives the estimate.
+///
+/// # Arguments
+/// * `occupied` - Number of occupied bits in the sketch. This may be noisy.
+/// * `size` - Number of bits in the sketch.
+///
+/// # Return
+/// Estimated number of unique members.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::distinct_sketch::distinct_sketch_estimate;
+/// assert_eq!(distinct_sketch_estimate(0., 1024.), 0.);
+/// assert!((distinct_sketch_estimate(1., 1024.) - 1.).abs() < 1e-3);
+/// ```
+pub fn distinct_sketch_estimate(occupied: Float, size: Float) -> Float {
+    // a full sketch is estimated as if one bit were free, and noise may push the count out of range
+    let occupied = occupied.max(0.).min(size - 1.);
+    -size * (1. - occupied / size).ln()
+}
+
+#[cfg(test)]
+mod test_distinct_sketch {
+    use crate::components::distinct_sketch::{distinct_sketch, merge_sketches, distinct_sketch_estimate};
+    use crate::components::Evaluable;
+    use whitenoise_validator::base::Value;
+    use whitenoise_validator::{proto, Float, Integer};
+    use ndarray::{Array, arr2};
+
+    #[test]
+    fn test_estimate_accuracy() {
+        let size = 4096;
+        for num_distinct in &[10, 1000, 10000] {
+            let data = Array::from_shape_fn((num_distinct * 3, 1), |(i, _)| (i % num_distinct) as Integer).into_dyn();
+            let occupied = distinct_sketch(&data, size).unwrap()[0].iter().filter(|bit| **bit).count();
+            let estimate = distinct_sketch_estimate(occupied as Float, size as Float);
+            assert!((estimate - *num_distinct as Float).abs() / (*num_distinct as Float) < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_merge_partitions() {
+        let left = arr2(&[["a".to_string()], ["b".to_string()], ["c".to_string()]]).into_dyn();
+        let right = arr2(&[["c".to_string()], ["d".to_string()]]).into_dyn();
+        let both = arr2(&[["a".to_string()], ["b".to_string()], ["c".to_string()], ["d".to_string()]]).into_dyn();
+
+        let merged = merge_sketches(distinct_sketch(&left, 64).unwrap(), distinct_sketch(&right, 64).unwrap()).unwrap();
+        assert_eq!(merged, distinct_sketch(&both, 64).unwrap());
+
+        let release = proto::DistinctSketch { size: 64 }.evaluate(&None, indexmap::indexmap![
+            "data".into() => Value::Partitions(indexmap::indexmap![
+                0.into() => left.into(),
+                1.into() => right.into()
+            ])
+        ]).unwrap();
+        let occupied = release.value.array().unwrap().int().unwrap();
+        assert_eq!(occupied.shape(), &[1, 1]);
+        assert_eq!(occupied[[0, 0]] as usize, merged[0].iter().filter(|bit| **bit).count());
+    }
+
+    #[test]
+    fn test_dp_count_sketch() {
+        use whitenoise_validator::bindings::Analysis;
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((3000, 1), |(i, _)| (i % 1000) as Integer).into_dyn().into())
+            .value_public(true).build();
+        let lower = analysis.literal().value(0.into()).value_public(true).build();
+
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1e6, delta: 0.
+            }))
+        }];
+        let count = analysis.dp_count(data, lower, privacy_usage)
+            .distinct(true).sketch_size(4096).build();
+
+        let (release, _warnings) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let report = whitenoise_validator::generate_report(
+            analysis.privacy_definition, analysis.components, release.clone()).unwrap();
+        assert!(report.contains("sketch_size"));
+
+        let estimate = release.get(&count).unwrap().value.clone().array().unwrap().first_int().unwrap();
+        assert!((estimate - 1000).abs() < 50);
+    }
+}
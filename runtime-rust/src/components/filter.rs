@@ -14,9 +14,18 @@ use crate::utilities::to_nd;
 
 
 impl Evaluable for proto::Filter {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         let mask = take_argument(&mut arguments, "mask")?.array()?.bool()?;
 
+        if privacy_definition.as_ref().map(|v| v.protect_memory_utilization).unwrap_or(false) {
+            return Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
+                Array::Str(data) => filter_fixed_capacity(data, mask)?.into(),
+                Array::Float(data) => filter_fixed_capacity(data, mask)?.into(),
+                Array::Int(data) => filter_fixed_capacity(data, mask)?.into(),
+                Array::Bool(data) => filter_fixed_capacity(data, mask)?.into(),
+            }))
+        }
+
         Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
             Array::Str(data) => filter(data, mask)?.into(),
             Array::Float(data) => filter(data, mask)?.into(),
@@ -54,4 +63,46 @@ pub fn filter<T: Clone + Default>(data: ArrayD<T>, mask: ArrayD<bool>) -> Result
         .map(|(index, _)| index)
         .collect();
     Ok(slow_select(&data, Axis(0), &mask_indices))
-}
\ No newline at end of file
+}
+
+/// Filters data down into only the desired rows, in a buffer with capacity for every row of the input.
+///
+/// The memory allocated does not depend on the number of rows retained.
+///
+/// # Arguments
+/// * `data` - Data to be filtered.
+/// * `mask` - Boolean mask giving whether or not each row should be kept.
+///
+/// # Return
+/// Data with only the desired rows.
+///
+/// # Example
+/// ```
+/// use ndarray::{ArrayD, arr1, arr2};
+/// use whitenoise_runtime::components::filter::filter_fixed_capacity;
+///
+/// let data = arr2(&[ [1, 2, 3], [4, 5, 6], [7, 8, 9], [10, 11, 12] ]).into_dyn();
+/// let mask = arr1(&[true, false, true, false]).into_dyn();
+/// let filtered = filter_fixed_capacity(data, mask).unwrap();
+/// assert_eq!(filtered, arr2(&[ [1, 2, 3], [7, 8, 9] ]).into_dyn());
+/// ```
+pub fn filter_fixed_capacity<T: Clone>(data: ArrayD<T>, mask: ArrayD<bool>) -> Result<ArrayD<T>> {
+
+    let columnar_mask: Array1<bool> = to_nd(mask, 1)?.into_dimensionality::<Ix1>()?;
+    if data.ndim() == 0 || columnar_mask.len() != data.len_of(Axis(0)) {
+        return Err("mask must have the same number of rows as the data".into())
+    }
+
+    let mut buffer = Vec::with_capacity(data.len());
+    let mut num_rows = 0;
+    data.outer_iter().zip(columnar_mask.iter())
+        .filter(|(_, keep)| **keep)
+        .for_each(|(row, _)| {
+            buffer.extend(row.iter().cloned());
+            num_rows += 1;
+        });
+
+    let mut shape = data.shape().to_vec();
+    shape[0] = num_rows;
+    Ok(ArrayD::from_shape_vec(shape, buffer)?)
+}
@@ -62,6 +62,9 @@ impl Evaluable for proto::Index {
 
             // if the value is an array, we'll be selecting columns
             Value::Array(array) => {
+                if array.shape().len() != 2 {
+                    return Err("data: columns may only be indexed on 2-dimensional arrays".into())
+                }
                 let indices = if let Ok(indices) = take_argument(&mut arguments, "indices") {
                     let indices = indices.array()?.int()?;
                     dimensionality = indices.shape().len() + 1;
@@ -186,3 +189,21 @@ fn column_stack(
         }
     }
 }
+
+#[cfg(test)]
+mod test_index {
+    use ndarray::{arr1, arr2};
+    use whitenoise_validator::base::Value;
+    use whitenoise_validator::proto;
+    use crate::components::Evaluable;
+
+    #[test]
+    fn test_index_dimensionality() {
+        let index = |data: Value| proto::Index {}.evaluate(&None, indexmap::indexmap![
+            "data".into() => data,
+            "indices".into() => arr1(&[0i64]).into_dyn().into()]);
+
+        assert!(index(arr2(&[[1.], [2.]]).into_dyn().into()).is_ok());
+        assert!(index(arr1(&[1., 2.]).into_dyn().into()).is_err());
+    }
+}
@@ -3,10 +3,25 @@ use whitenoise_validator::components::Named;
 
 use crate::NodeArguments;
 use whitenoise_validator::base::{Value, ReleaseNode, IndexKey};
+#[cfg(any(feature = "use-columnar", feature = "use-sqlite"))]
+use whitenoise_validator::base::{Array, DataType};
 use indexmap::IndexMap;
 use crate::components::Evaluable;
 
-use whitenoise_validator::{proto};
+use whitenoise_validator::proto;
+#[cfg(any(feature = "use-columnar", feature = "use-sqlite"))]
+use whitenoise_validator::Float;
+
+#[cfg(feature = "use-columnar")]
+use arrow_array::{RecordBatch, ArrayRef};
+#[cfg(feature = "use-columnar")]
+use arrow_array::cast::AsArray;
+#[cfg(feature = "use-columnar")]
+use arrow_array::types::{Float64Type, Int64Type};
+#[cfg(feature = "use-columnar")]
+use arrow_cast::cast::{cast_with_options, CastOptions};
+#[cfg(feature = "use-columnar")]
+use arrow_schema::DataType as ArrowDataType;
 
 impl Evaluable for proto::Materialize {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, arguments: NodeArguments) -> Result<ReleaseNode> {
@@ -15,22 +30,102 @@ impl Evaluable for proto::Materialize {
             arguments.iter().map(|(k, v)| (k.clone(), v)).collect(),
             IndexMap::new(), None)?;
 
-        // num columns is sufficient shared information to build the dataframes
-        let num_columns = column_names.len();
+        let column_types = self.get_column_types(
+            &arguments.iter().map(|(k, v)| (k.clone(), v)).collect())?;
 
-        let mut response = (0..num_columns)
-            .map(|_| Vec::new())
-            .collect::<Vec<Vec<String>>>();
+        // read one record past the bound, to detect data sources that exceed it
+        let limit = match self.max_records {
+            0 => None,
+            max_records => Some(max_records as usize + 1)
+        };
 
-        let mut reader = match csv::ReaderBuilder::new()
-            .has_headers(self.skip_row)
-            .from_path(self.file_path.clone()) {
-            Ok(reader) => reader,
-            Err(_) => return Err("the provided file path could not be found".into())
+        let data = match column_types {
+            #[cfg_attr(not(any(feature = "use-columnar", feature = "use-sqlite")), allow(unused_variables))]
+            Some(column_types) => match self.file_format.to_lowercase().as_str() {
+                #[cfg(feature = "use-columnar")]
+                "parquet" => record_batches_to_dataframe(&read_parquet(&self.file_path)?, &column_names, &column_types)?,
+                #[cfg(feature = "use-columnar")]
+                "arrow" => record_batches_to_dataframe(&read_arrow(&self.file_path)?, &column_names, &column_types)?,
+                #[cfg(not(feature = "use-columnar"))]
+                "parquet" | "arrow" => return Err("runtime has been compiled without use-columnar, so parquet and arrow files may not be read".into()),
+                #[cfg(feature = "use-sqlite")]
+                "sqlite" => read_sqlite(&self.file_path, &self.table, &column_names, &column_types)?,
+                #[cfg(not(feature = "use-sqlite"))]
+                "sqlite" => return Err("runtime has been compiled without use-sqlite, so sqlite databases may not be read".into()),
+                _ => return Err("file_format: must be one of \"csv\", \"parquet\", \"arrow\" or \"sqlite\"".into())
+            },
+            None => read_records(&mut open_csv(self)?, &column_names, limit)?.0
         };
 
-        // parse from csv into response
-        reader.deserialize().try_for_each(|result: std::result::Result<Vec<String>, _>| {
+        let num_records = match &data {
+            Value::Dataframe(data) => data.values().next()
+                .map(|column| column.ref_array()?.num_records()).transpose()?.unwrap_or(0),
+            _ => return Err("data source must be read into a dataframe".into())
+        };
+        check_max_records(self, num_records)?;
+
+        Ok(ReleaseNode::new(data))
+    }
+}
+
+/// Reject a data source with more records than the public bound set on its Materialize component.
+///
+/// # Arguments
+/// * `materialize` - Materialize component, containing the bound on the number of records.
+/// * `num_records` - Number of records read from the data source.
+pub fn check_max_records(materialize: &proto::Materialize, num_records: usize) -> Result<()> {
+    if materialize.max_records > 0 && num_records as u64 > materialize.max_records {
+        return Err("data source has more records than max_records".into())
+    }
+    Ok(())
+}
+
+/// Open a csv reader over the file referenced by a Materialize component.
+///
+/// # Arguments
+/// * `materialize` - Materialize component, containing the file path and whether to skip the header.
+///
+/// # Return
+/// A csv reader positioned at the first record.
+pub fn open_csv(materialize: &proto::Materialize) -> Result<csv::Reader<std::fs::File>> {
+    match csv::ReaderBuilder::new()
+        .has_headers(materialize.skip_row)
+        .from_path(materialize.file_path.clone()) {
+        Ok(reader) => Ok(reader),
+        Err(_) => Err("the provided file path could not be found".into())
+    }
+}
+
+/// Read records from a csv reader into a dataframe.
+///
+/// Successive calls on the same reader continue from the last record read,
+/// so a file may be consumed in chunks of bounded size.
+///
+/// # Arguments
+/// * `reader` - Csv reader to consume records from.
+/// * `column_names` - Names of the columns in the dataframe.
+/// * `limit` - Maximum number of records to read. When None, all remaining records are read.
+///
+/// # Return
+/// The dataframe, and the number of records read.
+pub fn read_records<R: std::io::Read>(
+    reader: &mut csv::Reader<R>,
+    column_names: &[IndexKey],
+    limit: Option<usize>
+) -> Result<(Value, usize)> {
+
+    // num columns is sufficient shared information to build the dataframes
+    let num_columns = column_names.len();
+
+    let mut response = (0..num_columns)
+        .map(|_| Vec::new())
+        .collect::<Vec<Vec<String>>>();
+
+    let mut num_records = 0;
+
+    // parse from csv into response
+    reader.deserialize().take(limit.unwrap_or(usize::MAX))
+        .try_for_each(|result: std::result::Result<Vec<String>, _>| {
 
             // parse each record into the whitenoise internal format
             match result {
@@ -39,21 +134,398 @@ impl Evaluable for proto::Materialize {
                     .for_each(|(idx, value)| response[idx].push(value)),
                 Err(e) => return Err(format!("{:?}", e).into())
             };
+            num_records += 1;
             Ok::<_, Error>(())
         })?;
 
-        let num_nonempty_columns = response.iter()
-            .filter(|col| !col.is_empty()).count();
+    let num_nonempty_columns = response.iter()
+        .filter(|col| !col.is_empty()).count();
+
+    if 0 < num_nonempty_columns && num_nonempty_columns < num_columns {
+        (num_nonempty_columns..num_columns).for_each(|idx|
+            response[idx] = (0..response[0].len()).map(|_| "".to_string()).collect::<Vec<String>>())
+    }
 
-        if 0 < num_nonempty_columns && num_nonempty_columns < num_columns {
-            (num_nonempty_columns..num_columns).for_each(|idx|
-                response[idx] = (0..response[0].len()).map(|_| "".to_string()).collect::<Vec<String>>())
+    Ok((Value::Dataframe(column_names.iter().cloned()
+        .zip(response.into_iter())
+        .map(|(key, value): (IndexKey, Vec<String>)|
+            (key, ndarray::Array::from(value).into_dyn().into()))
+        .collect::<IndexMap<IndexKey, Value>>()), num_records))
+}
+
+/// Read all record batches from a Parquet file.
+///
+/// # Arguments
+/// * `file_path` - Path to the Parquet file.
+///
+/// # Return
+/// Record batches in the order they are stored in the file.
+/// Column chunks may be uncompressed, or compressed with snappy, gzip or zstd.
+#[cfg(feature = "use-columnar")]
+pub fn read_parquet(file_path: &str) -> Result<Vec<RecordBatch>> {
+    let file = std::fs::File::open(file_path)
+        .map_err(|_| Error::from("the provided file path could not be found"))?;
+
+    parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+        .and_then(|builder| builder.build())
+        .map_err(|e| Error::from(format!("parquet: {}", e)))?
+        .map(|batch| batch.map_err(|e| Error::from(format!("parquet: {}", e))))
+        .collect()
+}
+
+/// Read all record batches from an Arrow IPC file.
+///
+/// # Arguments
+/// * `file_path` - Path to the Arrow IPC file.
+///
+/// # Return
+/// Record batches in the order they are stored in the file.
+#[cfg(feature = "use-columnar")]
+pub fn read_arrow(file_path: &str) -> Result<Vec<RecordBatch>> {
+    let file = std::fs::File::open(file_path)
+        .map_err(|_| Error::from("the provided file path could not be found"))?;
+
+    arrow_ipc::reader::FileReader::try_new(file, None)
+        .map_err(|e| Error::from(format!("arrow: {}", e)))?
+        .map(|batch| batch.map_err(|e| Error::from(format!("arrow: {}", e))))
+        .collect()
+}
+
+/// Convert record batches into a dataframe of typed columns.
+///
+/// String column names are matched against the names in the schema, and integer column names are positions in the schema.
+/// Nulls are read as NaN in float columns and empty strings in string columns.
+/// Integer and boolean columns have no representation for nulls, so they may only be read from fields that are not nullable in the schema.
+/// Nullable fields are read as float or string columns instead, and then imputed or cast, like the columns of a csv.
+/// As the schema is checked rather than the data, whether the read succeeds does not depend on the data.
+///
+/// # Arguments
+/// * `batches` - Record batches read from a typed file format.
+/// * `column_names` - Names of the columns to retrieve.
+/// * `column_types` - Atomic type of each column. Columns are cast to these types.
+///
+/// # Return
+/// A dataframe containing one array per column.
+#[cfg(feature = "use-columnar")]
+pub fn record_batches_to_dataframe(
+    batches: &[RecordBatch],
+    column_names: &[IndexKey],
+    column_types: &[DataType]
+) -> Result<Value> {
+
+    // values that cannot be cast are an error, instead of null
+    let options = CastOptions { safe: false, ..Default::default() };
+
+    column_names.iter().zip(column_types.iter())
+        .map(|(name, data_type)| {
+            let (columns, nullable): (Vec<ArrayRef>, Vec<bool>) = batches.iter()
+                .map(|batch| {
+                    let schema = batch.schema();
+                    match name {
+                        IndexKey::Str(column_name) => schema.index_of(column_name).ok(),
+                        IndexKey::Int(index) => Some(*index as usize).filter(|index| *index < schema.fields().len()),
+                        _ => None
+                    }
+                        .map(|position| (batch.column(position).clone(), schema.field(position).is_nullable()))
+                        .ok_or_else(|| Error::from(format!("column {} could not be found in the file", name.to_string())))
+                })
+                .collect::<Result<Vec<(ArrayRef, bool)>>>()?.into_iter().unzip();
+
+            if nullable.into_iter().any(|nullable| nullable) && (data_type == &DataType::Int || data_type == &DataType::Bool) {
+                return Err(format!(
+                    "column {}: nullable fields may not be read as {:?}. Read the column as float or string, and impute it",
+                    name.to_string(), data_type).into())
+            }
+
+            let cast = |arrow_type: &ArrowDataType| columns.iter()
+                .map(|column| cast_with_options(column, arrow_type, &options)
+                    .map_err(|e| Error::from(format!("column {}: {}", name.to_string(), e))))
+                .collect::<Result<Vec<ArrayRef>>>();
+
+            let array = match data_type {
+                DataType::Float => Array::Float(ndarray::Array::from(cast(&ArrowDataType::Float64)?.iter()
+                    .flat_map(|column| column.as_primitive::<Float64Type>().iter()
+                        .map(|v| v.unwrap_or(Float::NAN)).collect::<Vec<Float>>())
+                    .collect::<Vec<Float>>()).into_dyn()),
+                // the fields are not nullable, so every value is valid
+                DataType::Int => Array::Int(ndarray::Array::from(cast(&ArrowDataType::Int64)?.iter()
+                    .flat_map(|column| column.as_primitive::<Int64Type>().values().to_vec())
+                    .collect::<Vec<_>>()).into_dyn()),
+                DataType::Bool => Array::Bool(ndarray::Array::from(cast(&ArrowDataType::Boolean)?.iter()
+                    .flat_map(|column| column.as_boolean().values().iter().collect::<Vec<bool>>())
+                    .collect::<Vec<bool>>()).into_dyn()),
+                DataType::Str => Array::Str(ndarray::Array::from(cast(&ArrowDataType::Utf8)?.iter()
+                    .flat_map(|column| column.as_string::<i32>().iter()
+                        .map(|v| v.unwrap_or("").to_string()).collect::<Vec<String>>())
+                    .collect::<Vec<String>>()).into_dyn()),
+                DataType::Unknown => return Err("column types must be known".into())
+            };
+            Ok((name.clone(), Value::Array(array)))
+        })
+        .collect::<Result<IndexMap<IndexKey, Value>>>()
+        .map(Value::Dataframe)
+}
+
+/// Read a table from a SQLite database as a dataframe of typed columns.
+///
+/// The database is opened read-only, and the query is built here rather than supplied by the caller.
+/// Each record of the table is read exactly once.
+/// Joins, unions, grouping and deduplication could repeat, merge or drop records,
+/// so that one individual could influence more than one record, and the c-stability of the data would be understated.
+/// String column names are matched against the names of the columns in the table, and integer column names are positions in the table.
+/// Nulls are read as NaN in float columns and empty strings in string columns.
+/// Integer and boolean columns have no representation for nulls, so nulls in these columns are an error.
+///
+/// # Arguments
+/// * `file_path` - Path to the SQLite database.
+/// * `table` - Name of the table to read.
+/// * `column_names` - Names of the columns to retrieve from the table.
+/// * `column_types` - Atomic type of each column. Values are converted to these types.
+///
+/// # Return
+/// A dataframe containing one array per column.
+#[cfg(feature = "use-sqlite")]
+pub fn read_sqlite(
+    file_path: &str,
+    table: &str,
+    column_names: &[IndexKey],
+    column_types: &[DataType]
+) -> Result<Value> {
+    let sqlite_error = |e: rusqlite::Error| Error::from(format!("sqlite: {}", e));
+
+    if !std::path::Path::new(file_path).exists() {
+        return Err("the provided file path could not be found".into())
+    }
+    let connection = rusqlite::Connection::open_with_flags(
+        file_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(sqlite_error)?;
+
+    // the table name is quoted as an identifier, so that it cannot alter the query
+    let query = format!("SELECT * FROM \"{}\"", table.replace('"', "\"\""));
+    let mut statement = connection.prepare(&query).map_err(sqlite_error)?;
+
+    // position of each column in the table
+    let result_names = statement.column_names().into_iter()
+        .map(String::from).collect::<Vec<String>>();
+    let positions = column_names.iter()
+        .map(|name| match name {
+            IndexKey::Str(column_name) => result_names.iter().position(|v| v == column_name),
+            IndexKey::Int(index) => Some(*index as usize).filter(|v| *v < result_names.len()),
+            _ => None
+        }.ok_or_else(|| Error::from(format!("column {} could not be found in the table", name.to_string()))))
+        .collect::<Result<Vec<usize>>>()?;
+
+    let mut columns = positions.iter()
+        .map(|_| Vec::new()).collect::<Vec<Vec<rusqlite::types::Value>>>();
+
+    let mut rows = statement.query([]).map_err(sqlite_error)?;
+    while let Some(row) = rows.next().map_err(sqlite_error)? {
+        for (column, position) in columns.iter_mut().zip(positions.iter()) {
+            column.push(row.get::<_, rusqlite::types::Value>(*position).map_err(sqlite_error)?);
         }
+    }
+
+    column_names.iter().zip(column_types.iter()).zip(columns)
+        .map(|((name, data_type), column)| {
+            use rusqlite::types::Value as SqlValue;
+            let mismatch = |value: &SqlValue| Error::from(format!(
+                "column {}: {:?} cannot be read as {:?}", name.to_string(), value, data_type));
+
+            let array = match data_type {
+                DataType::Float => Array::Float(ndarray::Array::from(column.iter()
+                    .map(|value| Ok(match value {
+                        SqlValue::Null => Float::NAN,
+                        SqlValue::Integer(value) => *value as Float,
+                        SqlValue::Real(value) => *value,
+                        _ => return Err(mismatch(value))
+                    })).collect::<Result<Vec<Float>>>()?).into_dyn()),
+                DataType::Int => Array::Int(ndarray::Array::from(column.iter()
+                    .map(|value| match value {
+                        SqlValue::Integer(value) => Ok(*value),
+                        _ => Err(mismatch(value))
+                    }).collect::<Result<Vec<_>>>()?).into_dyn()),
+                // sqlite stores booleans as integers
+                DataType::Bool => Array::Bool(ndarray::Array::from(column.iter()
+                    .map(|value| match value {
+                        SqlValue::Integer(value) => Ok(*value != 0),
+                        _ => Err(mismatch(value))
+                    }).collect::<Result<Vec<bool>>>()?).into_dyn()),
+                DataType::Str => Array::Str(ndarray::Array::from(column.iter()
+                    .map(|value| Ok(match value {
+                        SqlValue::Null => "".to_string(),
+                        SqlValue::Integer(value) => value.to_string(),
+                        SqlValue::Real(value) => value.to_string(),
+                        SqlValue::Text(value) => value.clone(),
+                        SqlValue::Blob(_) => return Err(mismatch(value))
+                    })).collect::<Result<Vec<String>>>()?).into_dyn()),
+                DataType::Unknown => return Err("column types must be known".into())
+            };
+            Ok((name.clone(), Value::Array(array)))
+        })
+        .collect::<Result<IndexMap<IndexKey, Value>>>()
+        .map(Value::Dataframe)
+}
+
+
+#[cfg(test)]
+mod test_materialize {
+    #[cfg(feature = "use-sqlite")]
+    use crate::components::materialize::read_sqlite;
+    #[cfg(feature = "use-columnar")]
+    use crate::components::materialize::{read_parquet, read_arrow, record_batches_to_dataframe};
+    use crate::components::Evaluable;
+    use whitenoise_validator::proto;
+    use ndarray::arr1;
+    #[cfg(any(feature = "use-columnar", feature = "use-sqlite"))]
+    use whitenoise_validator::base::{IndexKey, DataType};
+    #[cfg(feature = "use-columnar")]
+    use arrow_array::{RecordBatch, Int64Array, Float64Array, StringArray, BooleanArray, ArrayRef};
+    #[cfg(feature = "use-columnar")]
+    use std::sync::Arc;
+
+    #[cfg(feature = "use-columnar")]
+    fn batch() -> RecordBatch {
+        RecordBatch::try_from_iter_with_nullable(vec![
+            ("int", Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef, false),
+            ("float", Arc::new(Float64Array::from(vec![Some(1.5), None, Some(3.5)])) as ArrayRef, true),
+            ("bool", Arc::new(BooleanArray::from(vec![true, false, true])) as ArrayRef, false),
+            ("str", Arc::new(StringArray::from(vec![Some("a"), Some("b"), None])) as ArrayRef, true),
+            ("nullable_int", Arc::new(Int64Array::from(vec![4, 5, 6])) as ArrayRef, true),
+        ]).unwrap()
+    }
+
+    #[cfg(feature = "use-columnar")]
+    fn check(batches: Vec<RecordBatch>) {
+        let column_names = vec![
+            IndexKey::from("int"), "float".into(), "bool".into(), "str".into(), 0.into()];
+        let column_types = vec![
+            DataType::Int, DataType::Float, DataType::Bool, DataType::Str, DataType::Float];
+
+        let dataframe = record_batches_to_dataframe(&batches, &column_names, &column_types).unwrap()
+            .dataframe().unwrap();
+
+        assert_eq!(dataframe.get(&IndexKey::from("int")).unwrap().ref_array().unwrap()
+            .ref_int().unwrap().iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+        let floats = dataframe.get(&IndexKey::from("float")).unwrap().ref_array().unwrap()
+            .ref_float().unwrap().clone();
+        assert_eq!(floats[0], 1.5);
+        assert!(floats[1].is_nan());
+        assert_eq!(dataframe.get(&IndexKey::from("bool")).unwrap().ref_array().unwrap()
+            .ref_bool().unwrap().iter().cloned().collect::<Vec<_>>(), vec![true, false, true]);
+        assert_eq!(dataframe.get(&IndexKey::from("str")).unwrap().ref_array().unwrap()
+            .ref_string().unwrap().iter().cloned().collect::<Vec<_>>(), vec!["a", "b", ""]);
+        // integer names are positions in the schema
+        assert_eq!(dataframe.get(&IndexKey::from(0)).unwrap().ref_array().unwrap()
+            .ref_float().unwrap().iter().cloned().collect::<Vec<_>>(), vec![1., 2., 3.]);
+
+        // nullable fields may not be read into integer or boolean columns, even when they contain no nulls
+        assert!(record_batches_to_dataframe(&batches, &["float".into()], &[DataType::Int]).is_err());
+        assert!(record_batches_to_dataframe(&batches, &["nullable_int".into()], &[DataType::Int]).is_err());
+        assert!(record_batches_to_dataframe(&batches, &["str".into()], &[DataType::Bool]).is_err());
+        // but may be read as floats, to be imputed
+        let nullable_int = record_batches_to_dataframe(&batches, &["nullable_int".into()], &[DataType::Float]).unwrap()
+            .dataframe().unwrap();
+        assert_eq!(nullable_int.get(&IndexKey::from("nullable_int")).unwrap().ref_array().unwrap()
+            .ref_float().unwrap().iter().cloned().collect::<Vec<_>>(), vec![4., 5., 6.]);
+    }
+
+    #[test]
+    #[cfg(feature = "use-columnar")]
+    fn test_parquet() {
+        use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+
+        let file_path = std::env::temp_dir().join(format!("whitenoise_materialize_{}.parquet", std::process::id()));
+        let batch = batch();
+        let compressions = [
+            Compression::UNCOMPRESSED,
+            Compression::SNAPPY,
+            Compression::GZIP(GzipLevel::default()),
+            Compression::ZSTD(ZstdLevel::default())];
+
+        compressions.iter().for_each(|compression| {
+            let properties = parquet::file::properties::WriterProperties::builder()
+                .set_compression(*compression).build();
+            let mut writer = parquet::arrow::ArrowWriter::try_new(
+                std::fs::File::create(&file_path).unwrap(), batch.schema(), Some(properties)).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+
+            check(read_parquet(file_path.to_str().unwrap()).unwrap());
+        });
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "use-columnar")]
+    fn test_arrow() {
+        let file_path = std::env::temp_dir().join(format!("whitenoise_materialize_{}.arrow", std::process::id()));
+        let batch = batch();
+        let mut writer = arrow_ipc::writer::FileWriter::try_new(
+            std::fs::File::create(&file_path).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        check(read_arrow(file_path.to_str().unwrap()).unwrap());
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_max_records() {
+        let file_path = std::env::temp_dir().join(format!("whitenoise_materialize_{}.csv", std::process::id()));
+        std::fs::write(&file_path, "a\n1\n2\n3\n").unwrap();
+
+        let materialize = |max_records: u64| proto::Materialize {
+            skip_row: true,
+            file_format: "csv".to_string(),
+            file_path: file_path.to_str().unwrap().to_string(),
+            max_records,
+            ..Default::default()
+        }.evaluate(&None, indexmap::indexmap!["column_names".into() => arr1(&["a".to_string()]).into_dyn().into()]);
+
+        assert!(materialize(0).is_ok());
+        assert!(materialize(3).is_ok());
+        assert!(materialize(2).is_err());
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "use-sqlite")]
+    fn test_sqlite() {
+        let file_path = std::env::temp_dir().join(format!("whitenoise_materialize_{}.sqlite", std::process::id()));
+        let connection = rusqlite::Connection::open(&file_path).unwrap();
+        connection.execute_batch("
+            CREATE TABLE people (age INTEGER, income REAL, married INTEGER, name TEXT);
+            INSERT INTO people VALUES (20, 10.5, 1, 'a'), (30, NULL, 0, 'b'), (40, 30.5, 1, NULL);
+        ").unwrap();
+        let file_path = file_path.to_str().unwrap();
+
+        let dataframe = read_sqlite(
+            file_path, "people",
+            &["income".into(), "age".into(), "married".into(), "name".into()],
+            &[DataType::Float, DataType::Int, DataType::Bool, DataType::Str]).unwrap()
+            .dataframe().unwrap();
+
+        let incomes = dataframe.get(&IndexKey::from("income")).unwrap().ref_array().unwrap()
+            .ref_float().unwrap().clone();
+        assert_eq!(incomes[0], 10.5);
+        assert!(incomes[1].is_nan());
+        assert_eq!(dataframe.get(&IndexKey::from("age")).unwrap().ref_array().unwrap()
+            .ref_int().unwrap().iter().cloned().collect::<Vec<_>>(), vec![20, 30, 40]);
+        assert_eq!(dataframe.get(&IndexKey::from("married")).unwrap().ref_array().unwrap()
+            .ref_bool().unwrap().iter().cloned().collect::<Vec<_>>(), vec![true, false, true]);
+        assert_eq!(dataframe.get(&IndexKey::from("name")).unwrap().ref_array().unwrap()
+            .ref_string().unwrap().iter().cloned().collect::<Vec<_>>(), vec!["a", "b", ""]);
+
+        // columns may also be retrieved by their position in the table
+        assert!(read_sqlite(file_path, "people", &[IndexKey::Int(1)], &[DataType::Float]).is_ok());
+
+        // nulls may not be read into integer columns, and columns must be in the table
+        assert!(read_sqlite(file_path, "people", &["income".into()], &[DataType::Int]).is_err());
+        assert!(read_sqlite(file_path, "people", &["wage".into()], &[DataType::Int]).is_err());
+
+        // the table name is an identifier, not a query
+        assert!(read_sqlite(file_path, "people UNION ALL SELECT * FROM people", &["age".into()], &[DataType::Int]).is_err());
+        assert!(read_sqlite(file_path, "people\" UNION ALL SELECT * FROM \"people", &["age".into()], &[DataType::Int]).is_err());
 
-        Ok(ReleaseNode::new(Value::Dataframe(column_names.into_iter()
-            .zip(response.into_iter())
-            .map(|(key, value): (IndexKey, Vec<String>)|
-                (key, ndarray::Array::from(value).into_dyn().into()))
-            .collect::<IndexMap<IndexKey, Value>>())))
+        std::fs::remove_file(file_path).unwrap();
     }
 }
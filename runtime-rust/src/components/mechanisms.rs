@@ -1,15 +1,17 @@
 use whitenoise_validator::errors::*;
 
 use crate::NodeArguments;
-use whitenoise_validator::base::{ReleaseNode, Value, Jagged, Array};
+use whitenoise_validator::base::{ReleaseNode, Value, Jagged, Array, IndexKey};
 use whitenoise_validator::utilities::{
     take_argument, array::broadcast_ndarray,
-    privacy::{get_epsilon, get_delta, spread_privacy_usage}};
+    privacy::{get_epsilon, get_delta, get_rho, spread_privacy_usage, approximate_to_concentrated_for_delta}};
 use crate::components::Evaluable;
 use crate::utilities;
 use whitenoise_validator::{proto, Float, Integer};
 use ndarray::{Axis, arr1};
-use crate::utilities::mechanisms::exponential_mechanism;
+use crate::utilities::mechanisms::{
+    exponential_mechanism, report_noisy_max, permute_and_flip, randomized_response, unary_randomized_response,
+    smooth_quantile_mechanism};
 
 impl Evaluable for proto::LaplaceMechanism {
     fn evaluate(
@@ -74,17 +76,24 @@ impl Evaluable for proto::GaussianMechanism {
 
         let usages = spread_privacy_usage(&self.privacy_usage, num_columns)?;
 
-        let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
-        let delta = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
-
         data.gencolumns_mut().into_iter()
             .zip(sensitivity.gencolumns().into_iter())
-            .zip(epsilon.into_iter().zip(delta.into_iter()))
-            .try_for_each(|((mut data_column, sensitivity), (epsilon, delta))| data_column.iter_mut()
+            .zip(usages.iter())
+            .try_for_each(|((mut data_column, sensitivity), usage)| data_column.iter_mut()
                 .zip(sensitivity.iter())
                 .try_for_each(|(v, sens)| {
-                    *v += utilities::mechanisms::gaussian_mechanism(
-                        epsilon, delta, *sens as f64, enforce_constant_time)? as Float;
+                    *v += match usage.distance.as_ref().ok_or("distance must be defined")? {
+                        proto::privacy_usage::Distance::Approximate(approximate) => if self.analytic {
+                            utilities::mechanisms::analytic_gaussian_mechanism(
+                                approximate.epsilon, approximate.delta, *sens, enforce_constant_time)?
+                        } else {
+                            utilities::mechanisms::gaussian_mechanism(
+                                approximate.epsilon, approximate.delta, *sens as f64, enforce_constant_time)?
+                        },
+                        proto::privacy_usage::Distance::Concentrated(concentrated) =>
+                            utilities::mechanisms::gaussian_mechanism_concentrated(
+                                concentrated.rho, *sens as f64, enforce_constant_time)?
+                    } as Float;
                     Ok::<_, Error>(())
                 }))?;
 
@@ -139,6 +148,122 @@ impl Evaluable for proto::SimpleGeometricMechanism {
     }
 }
 
+impl Evaluable for proto::DiscreteGaussianMechanism {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let data = take_argument(&mut arguments, "data")?.array()?;
+        let num_columns = data.num_columns()?;
+        let mut data = data.int()?.to_owned();
+
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.float()?;
+
+        let usages = spread_privacy_usage(&self.privacy_usage, num_columns)?;
+        let rhos = usages.iter()
+            .map(|usage| match usage.distance.as_ref().ok_or("distance must be defined")? {
+                proto::privacy_usage::Distance::Approximate(_) => get_rho(&approximate_to_concentrated_for_delta(usage)?),
+                proto::privacy_usage::Distance::Concentrated(concentrated) => Ok(concentrated.rho)
+            })
+            .collect::<Result<Vec<f64>>>()?;
+
+        data.gencolumns_mut().into_iter()
+            .zip(sensitivity.gencolumns().into_iter().zip(rhos))
+            .try_for_each(|(mut data_column, (sensitivity, rho))| data_column.iter_mut()
+                .zip(sensitivity.iter())
+                .try_for_each(|(v, sens)| {
+                    *v = v.saturating_add(utilities::mechanisms::discrete_gaussian_mechanism(rho, *sens)?);
+                    Ok::<_, Error>(())
+                }))?;
+
+        Ok(ReleaseNode {
+            value: data.into(),
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
+impl Evaluable for proto::SnappingMechanism {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let data = take_argument(&mut arguments, "data")?.array()?;
+        let num_columns = data.num_columns()?;
+        let mut data = match data {
+            Array::Float(data) => data,
+            Array::Int(data) => data.mapv(|v| v as Float),
+            _ => return Err("data must be numeric".into())
+        };
+
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.float()?;
+
+        let usages = spread_privacy_usage(&self.privacy_usage, num_columns)?;
+        let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
+
+        let lower = broadcast_ndarray(
+            take_argument(&mut arguments, "lower")?.array()?.float()?, data.shape())?;
+
+        let upper = broadcast_ndarray(
+            take_argument(&mut arguments, "upper")?.array()?.float()?, data.shape())?;
+
+        data.gencolumns_mut().into_iter()
+            .zip(sensitivity.gencolumns().into_iter().zip(epsilon))
+            .zip(lower.gencolumns().into_iter().zip(upper.gencolumns()))
+            .try_for_each(|((mut data_column, (sensitivity, epsilon)), (lower, upper))| data_column.iter_mut()
+                .zip(sensitivity.iter())
+                .zip(lower.iter().zip(upper.iter()))
+                .try_for_each(|((v, sens), (c_min, c_max))| {
+                    *v = utilities::mechanisms::snapping_mechanism(
+                        *v, epsilon, *sens, *c_min, *c_max)?;
+                    Ok::<_, Error>(())
+                }))?;
+
+        Ok(ReleaseNode {
+            value: data.into(),
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
+impl Evaluable for proto::SmoothQuantileMechanism {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        let data = match take_argument(&mut arguments, "data")?.array()? {
+            Array::Float(data) => data,
+            Array::Int(data) => data.mapv(|v| v as Float),
+            _ => return Err("data must be numeric".into())
+        };
+
+        let lower = take_argument(&mut arguments, "lower")?.array()?.float()?;
+        let upper = take_argument(&mut arguments, "upper")?.array()?.float()?;
+
+        let usages = spread_privacy_usage(&self.privacy_usage, lower.len())?;
+
+        let releases = data.gencolumns().into_iter()
+            .zip(usages.iter())
+            .zip(lower.iter().zip(upper.iter()))
+            .map(|((column, usage), (lower, upper))| smooth_quantile_mechanism(
+                column.to_vec(), self.alpha, get_epsilon(usage)?,
+                *lower, *upper, enforce_constant_time))
+            .collect::<Result<Vec<Float>>>()?;
+
+        // the quantile reduces the row axis to a single record
+        let value = match data.ndim() {
+            1 => ndarray::Array::from_shape_vec(vec![], releases)?.into_dyn(),
+            2 => ndarray::Array::from_shape_vec(vec![1, releases.len()], releases)?.into_dyn(),
+            _ => return Err("data must be one or two-dimensional".into())
+        };
+
+        Ok(ReleaseNode {
+            value: value.into(),
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
 impl Evaluable for proto::ExponentialMechanism {
     fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
 
@@ -225,3 +350,213 @@ impl Evaluable for proto::ExponentialMechanism {
         })
     }
 }
+
+impl Evaluable for proto::AboveThreshold {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        let answers = match take_argument(&mut arguments, "data")?.array()? {
+            Array::Float(data) => data,
+            Array::Int(data) => data.mapv(|v| v as Float),
+            _ => return Err("data must be numeric".into())
+        };
+
+        let threshold = match take_argument(&mut arguments, "threshold")?.array()? {
+            Array::Float(threshold) => threshold.first().cloned(),
+            Array::Int(threshold) => threshold.first().map(|v| *v as Float),
+            _ => return Err("threshold must be numeric".into())
+        }.ok_or("threshold must not be empty")?;
+
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.first_float()?;
+
+        let usage = self.privacy_usage.first()
+            .ok_or("privacy_usage must be defined")?.clone();
+        let epsilon = get_epsilon(&usage)?;
+
+        let indices = utilities::mechanisms::above_threshold(
+            epsilon, sensitivity,
+            &answers.iter().cloned().collect::<Vec<Float>>(), threshold,
+            self.cutoff, enforce_constant_time)?;
+
+        Ok(ReleaseNode {
+            value: arr1(&indices).into_dyn().into(),
+            privacy_usages: Some(vec![usage]),
+            public: true,
+        })
+    }
+}
+
+impl Evaluable for proto::DpKeySelection {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        let data = take_argument(&mut arguments, "data")?.array()?;
+        let usages = spread_privacy_usage(&self.privacy_usage, data.num_columns()? as usize)?;
+
+        macro_rules! select_keys {
+            ($data:expr, $variant:ident) => {{
+                let (categories, counts): (Vec<_>, Vec<_>) = $data.gencolumns().into_iter()
+                    .zip(usages.iter())
+                    .map(|(column, usage)| {
+                        utilities::mechanisms::key_selection(
+                            column.to_vec(), get_epsilon(usage)?, get_delta(usage)?,
+                            &self.mechanism, enforce_constant_time)
+                    })
+                    .collect::<Result<Vec<_>>>()?.into_iter().unzip();
+                (Jagged::$variant(categories), Jagged::Float(counts))
+            }}
+        }
+
+        let (categories, counts) = match data {
+            Array::Int(data) => select_keys!(data, Int),
+            Array::Str(data) => select_keys!(data, Str),
+            Array::Bool(data) => select_keys!(data, Bool),
+            Array::Float(_) => return Err("categories may not be floats".into())
+        };
+
+        Ok(ReleaseNode {
+            value: Value::Dataframe(vec![
+                (IndexKey::from("categories"), Value::Jagged(categories)),
+                (IndexKey::from("counts"), Value::Jagged(counts))
+            ].into_iter().collect()),
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
+/// Apply a selection mechanism to each column of jagged candidates, releasing one candidate per column.
+macro_rules! release_selection {
+    ($candidates:expr, $utilities:expr, $sensitivity:expr, $epsilon:expr, $select:expr) => {
+        match $candidates {
+            Jagged::Float(candidates) => release_selection!(@column candidates, $utilities, $sensitivity, $epsilon, $select, Float),
+            Jagged::Int(candidates) => release_selection!(@column candidates, $utilities, $sensitivity, $epsilon, $select, Integer),
+            Jagged::Str(candidates) => release_selection!(@column candidates, $utilities, $sensitivity, $epsilon, $select, String),
+            Jagged::Bool(candidates) => release_selection!(@column candidates, $utilities, $sensitivity, $epsilon, $select, bool),
+        }
+    };
+    (@column $candidates:expr, $utilities:expr, $sensitivity:expr, $epsilon:expr, $select:expr, $type:ty) => {{
+        let release_vec = $candidates.iter().zip($utilities)
+            .zip($sensitivity.iter().zip($epsilon.iter()))
+            .map(|((cands, utils), (sens, eps))| $select(*eps, *sens, cands, utils))
+            .collect::<Result<Vec<$type>>>()?;
+
+        let mut release_array = arr1(&release_vec).into_dyn();
+        release_array.insert_axis_inplace(Axis(0));
+
+        Value::from(release_array)
+    }};
+}
+
+impl Evaluable for proto::ReportNoisyMaxMechanism {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        let candidates = take_argument(&mut arguments, "candidates")?.jagged()?;
+
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.float()?
+            .iter().cloned().collect::<Vec<Float>>();
+
+        let usages = spread_privacy_usage(&self.privacy_usage, sensitivity.len())?;
+        let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
+
+        let utilities = take_argument(&mut arguments, "utilities")?.jagged()?.float()?;
+
+        let value = release_selection!(candidates, utilities, sensitivity, epsilon,
+            |eps, sens, cands, utils| report_noisy_max(
+                eps, sens, cands, utils, &self.noise, enforce_constant_time));
+
+        Ok(ReleaseNode {
+            value,
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
+impl Evaluable for proto::PermuteAndFlipMechanism {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        let candidates = take_argument(&mut arguments, "candidates")?.jagged()?;
+
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.float()?
+            .iter().cloned().collect::<Vec<Float>>();
+
+        let usages = spread_privacy_usage(&self.privacy_usage, sensitivity.len())?;
+        let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
+
+        let utilities = take_argument(&mut arguments, "utilities")?.jagged()?.float()?;
+
+        let value = release_selection!(candidates, utilities, sensitivity, epsilon,
+            |eps, sens, cands, utils| permute_and_flip(
+                eps, sens, cands, utils, enforce_constant_time));
+
+        Ok(ReleaseNode {
+            value,
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
+impl Evaluable for proto::RandomizedResponse {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        let usages = spread_privacy_usage(&self.privacy_usage, 1)?;
+        let epsilon = get_epsilon(&usages[0])?;
+
+        let encoding = self.encoding.to_lowercase();
+        let data = take_argument(&mut arguments, "data")?.array()?;
+        let categories = match encoding.as_str() {
+            "binary" => Jagged::Bool(vec![vec![false, true]]),
+            _ => take_argument(&mut arguments, "categories")?.jagged()?
+        };
+
+        // privatize each record independently
+        macro_rules! respond {
+            ($data:expr, $categories:expr) => {{
+                let categories = $categories.into_iter().next()
+                    .ok_or("categories: must contain one column")?;
+
+                if encoding == "unary" {
+                    let reports = $data.iter()
+                        .map(|value| unary_randomized_response(value, &categories, epsilon, enforce_constant_time))
+                        .collect::<Result<Vec<Vec<bool>>>>()?;
+                    let shape = (reports.len(), categories.len());
+                    Value::from(ndarray::Array::from_shape_vec(
+                        shape, reports.into_iter().flatten().collect())?.into_dyn())
+                } else {
+                    let reports = $data.iter()
+                        .map(|value| randomized_response(value, &categories, epsilon, enforce_constant_time))
+                        .collect::<Result<Vec<_>>>()?;
+                    Value::from(ndarray::Array::from_shape_vec($data.shape(), reports)?)
+                }
+            }};
+        }
+
+        let value = match (data, categories) {
+            (Array::Bool(data), Jagged::Bool(categories)) => respond!(data, categories),
+            (Array::Int(data), Jagged::Int(categories)) => respond!(data, categories),
+            (Array::Str(data), Jagged::Str(categories)) => respond!(data, categories),
+            _ => return Err("data and categories must be homogeneously typed, and may not be floats".into())
+        };
+
+        Ok(ReleaseNode {
+            value,
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
@@ -21,6 +21,7 @@ pub mod count;
 pub mod covariance;
 pub mod column_bind;
 pub mod digitize;
+pub mod distinct_sketch;
 pub mod filter;
 pub mod histogram;
 pub mod impute;
@@ -30,9 +31,11 @@ pub mod mean;
 pub mod mechanisms;
 pub mod partition;
 pub mod quantile;
+pub mod randomized_response_histogram;
 pub mod raw_moment;
 pub mod reshape;
 pub mod resize;
+pub mod solve_linear_regression;
 pub mod sum;
 pub mod transforms;
 pub mod union;
@@ -81,11 +84,12 @@ impl Evaluable for proto::component::Variant {
 
         evaluate!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, ColumnBind, Count, Covariance, Digitize, Filter, Histogram, Impute, Index,
+            Cast, Clamp, ColumnBind, Count, Covariance, Digitize, DistinctSketch, DistinctSketchEstimate, Filter, Histogram, Impute, Index,
             Materialize, Mean, Partition,
-            Quantile, RawMoment, Reshape, Resize, Sum, Union, Variance,
+            Quantile, RandomizedResponseHistogram, RawMoment, Reshape, Resize, SolveLinearRegression,
+            Sum, Union, Variance,
 
-            ExponentialMechanism, GaussianMechanism, LaplaceMechanism, SimpleGeometricMechanism,
+            AboveThreshold, DiscreteGaussianMechanism, DpKeySelection, ExponentialMechanism, GaussianMechanism, LaplaceMechanism, PermuteAndFlipMechanism, RandomizedResponse, ReportNoisyMaxMechanism, SimpleGeometricMechanism, SmoothQuantileMechanism, SnappingMechanism,
 
             Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
             Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract
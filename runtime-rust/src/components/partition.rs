@@ -14,7 +14,10 @@ use indexmap::map::IndexMap;
 
 
 impl Evaluable for proto::Partition {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_memory_utilization = privacy_definition.as_ref()
+            .map(|v| v.protect_memory_utilization).unwrap_or(false);
+
         let data = take_argument(&mut arguments, "data")?;
         Ok(ReleaseNode::new(match arguments.remove::<IndexKey>(&"by".into()) {
             Some(by) => {
@@ -24,11 +27,14 @@ impl Evaluable for proto::Partition {
 
                 match by.array()? {
                     Array::Int(by) =>
-                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions)?),
+                        Value::Partitions(partition_by(
+                            &data, by.mapv(IndexKey::from), partitions, protect_memory_utilization)?),
                     Array::Bool(by) =>
-                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions)?),
+                        Value::Partitions(partition_by(
+                            &data, by.mapv(IndexKey::from), partitions, protect_memory_utilization)?),
                     Array::Str(by) =>
-                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions)?),
+                        Value::Partitions(partition_by(
+                            &data, by.mapv(IndexKey::from), partitions, protect_memory_utilization)?),
                     _ => return Err("by and categories must share the same type".into())
                 }
             },
@@ -132,23 +138,41 @@ pub fn partition_evenly(data: &Value, num_partitions: i64) -> Result<IndexMap<In
 
 }
 
+/// Partitions data by the keys in `by`
+///
+/// Rows whose key is not among the partition keys are dropped, as a partition for them would depend on the data.
+///
+/// # Arguments
+/// * `data` - Data to be partitioned.
+/// * `by` - Partition key for each row of the data.
+/// * `partition_keys` - Keys of the partitions returned.
+/// * `fixed_capacity` - Whether to allocate space for every record in each partition up front,
+///   so that memory does not depend on the size of each partition.
+///
+/// # Return
+/// Indexmap with data splits.
 pub fn partition_by(
-    data: &Value, by: ArrayD<IndexKey>, partition_keys: Vec<IndexKey>
+    data: &Value, by: ArrayD<IndexKey>, partition_keys: Vec<IndexKey>, fixed_capacity: bool
 ) -> Result<IndexMap<IndexKey, Value>> {
 
-    let mut indices = partition_keys.into_iter()
-        .map(|key| (key, vec![]))
-        .collect::<IndexMap<IndexKey, Vec<usize>>>();
-
-    match by.ndim() {
+    let row_keys: Vec<IndexKey> = match by.ndim() {
         0 => return Err("by: invalid dimensionality".into()),
-        1 => by.into_dimensionality::<ndarray::Ix1>()?,
+        1 => by.iter().cloned().collect(),
         _ => by.genrows().into_iter().map(|row| IndexKey::Tuple(row.to_vec())).collect()
+    };
+
+    if fixed_capacity {
+        return partition_value_fixed_capacity(data, &row_keys, &partition_keys)
     }
-        .into_iter().enumerate()
-        .for_each(|(idx, cat)| indices.entry(cat.clone())
-            .or_insert_with(Vec::new).push(idx));
 
+    let mut indices = partition_keys.into_iter()
+        .map(|key| (key, vec![]))
+        .collect::<IndexMap<IndexKey, Vec<usize>>>();
+
+    row_keys.into_iter().enumerate()
+        .for_each(|(idx, cat)| if let Some(indices) = indices.get_mut(&cat) {
+            indices.push(idx)
+        });
     // partition either an array or a dataframe
     fn value_partitioner(data: &Value, indices: &IndexMap<IndexKey, Vec<usize>>) -> Result<IndexMap<IndexKey, Value>> {
         Ok(match data {
@@ -186,4 +210,111 @@ pub fn partition_by(
     };
 
     value_partitioner(data, &indices)
-}
\ No newline at end of file
+}
+
+/// Partitions an array or dataframe by the keys of its rows, into buffers with capacity for every row of the data.
+fn partition_value_fixed_capacity(
+    data: &Value, row_keys: &[IndexKey], partition_keys: &[IndexKey]
+) -> Result<IndexMap<IndexKey, Value>> {
+    fn into_values<T>(partitions: IndexMap<IndexKey, ArrayD<T>>) -> IndexMap<IndexKey, Value>
+        where ArrayD<T>: Into<Value> {
+        partitions.into_iter().map(|(key, data)| (key, data.into())).collect()
+    }
+
+    Ok(match data {
+        Value::Array(data) => match data {
+            Array::Int(data) => into_values(partition_ndarray_fixed_capacity(data, row_keys, partition_keys)?),
+            Array::Float(data) => into_values(partition_ndarray_fixed_capacity(data, row_keys, partition_keys)?),
+            Array::Bool(data) => into_values(partition_ndarray_fixed_capacity(data, row_keys, partition_keys)?),
+            Array::Str(data) => into_values(partition_ndarray_fixed_capacity(data, row_keys, partition_keys)?),
+        },
+        Value::Dataframe(data) => {
+            let mut columnar_partitions = data.iter().map(|(k, v)|
+                Ok((k.clone(), partition_value_fixed_capacity(v, row_keys, partition_keys)?)))
+                .collect::<Result<IndexMap<ColName, IndexMap<IndexKey, Value>>>>()?;
+
+            partition_keys.iter()
+                .map(|cat| (
+                    cat.clone(),
+                    Value::Dataframe(columnar_partitions.iter_mut().map(|(colname, partitions)|
+                        (colname.clone(), partitions.swap_remove(cat).unwrap())
+                    ).collect::<IndexMap<ColName, Value>>())
+                ))
+                .collect::<IndexMap<IndexKey, Value>>()
+        },
+        _ => return Err("data: must be a dataframe or array".into())
+    })
+}
+
+/// Partitions an ndarray by the keys of its rows, into buffers with capacity for every row of the data.
+///
+/// Every partition allocates the same amount of memory, regardless of how many rows it retains.
+/// Rows whose key is not among the partition keys are dropped.
+///
+/// # Arguments
+/// * `data` - Data to be partitioned.
+/// * `row_keys` - Partition key for each row of the data.
+/// * `partition_keys` - Keys of the partitions returned.
+///
+/// # Return
+/// Indexmap with data splits.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use whitenoise_runtime::components::partition::partition_ndarray_fixed_capacity;
+/// use whitenoise_validator::base::IndexKey;
+///
+/// let data = arr2(&[ [1, 2], [4, 5], [7, 8] ]).into_dyn();
+/// let row_keys: Vec<IndexKey> = vec![true.into(), false.into(), true.into()];
+/// let partitioned = partition_ndarray_fixed_capacity(&data, &row_keys, &[false.into(), true.into()]).unwrap();
+/// assert_eq!(partitioned.get::<IndexKey>(&false.into()).unwrap().clone(), arr2(&[ [4, 5] ]).into_dyn());
+/// assert_eq!(partitioned.get::<IndexKey>(&true.into()).unwrap().clone(), arr2(&[ [1, 2], [7, 8] ]).into_dyn());
+/// ```
+pub fn partition_ndarray_fixed_capacity<T: Clone>(
+    data: &ArrayD<T>, row_keys: &[IndexKey], partition_keys: &[IndexKey]
+) -> Result<IndexMap<IndexKey, ArrayD<T>>> {
+    if data.ndim() == 0 || data.len_of(Axis(0)) != row_keys.len() {
+        return Err("by must have the same number of rows as the data".into())
+    }
+
+    let mut buffers = partition_keys.iter()
+        .map(|key| (key.clone(), (Vec::with_capacity(data.len()), 0)))
+        .collect::<IndexMap<IndexKey, (Vec<T>, usize)>>();
+
+    data.outer_iter().zip(row_keys.iter())
+        .for_each(|(row, key)| if let Some((buffer, num_rows)) = buffers.get_mut(key) {
+            buffer.extend(row.iter().cloned());
+            *num_rows += 1;
+        });
+
+    buffers.into_iter()
+        .map(|(key, (buffer, num_rows))| {
+            let mut shape = data.shape().to_vec();
+            shape[0] = num_rows;
+            Ok((key, ArrayD::from_shape_vec(shape, buffer)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_partition {
+    use ndarray::arr1;
+    use whitenoise_validator::base::{Value, IndexKey};
+    use crate::components::partition::partition_by;
+
+    #[test]
+    fn test_unknown_keys() {
+        let data: Value = arr1(&[1, 2, 3, 4]).into_dyn().into();
+        let by = arr1(&["a", "b", "c", "a"]).into_dyn().mapv(IndexKey::from);
+        let partition_keys = vec![IndexKey::from("a"), "b".into()];
+
+        // both modes drop the row whose key is not a partition key
+        [false, true].iter().for_each(|fixed_capacity| {
+            let partitions = partition_by(&data, by.clone(), partition_keys.clone(), *fixed_capacity).unwrap();
+            assert_eq!(partitions.keys().cloned().collect::<Vec<IndexKey>>(), partition_keys);
+            assert_eq!(partitions.get(&IndexKey::from("a")).unwrap().ref_array().unwrap().ref_int().unwrap().clone(),
+                       arr1(&[1i64, 4]).into_dyn());
+        });
+    }
+}
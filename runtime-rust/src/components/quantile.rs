@@ -119,4 +119,50 @@ pub fn quantile_utilities<T: Ord + Clone + Copy>(
             candidates.into_iter().map(|(idx, _)| utilities[idx]).collect()
         })
         .collect::<Vec<Vec<Float>>>())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_quantile {
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::base::{Value, Jagged};
+    use whitenoise_validator::proto;
+    use whitenoise_validator::Float;
+    use ndarray::Array;
+
+    #[test]
+    fn test_dp_median_exponential_report() {
+        let x = Array::from_shape_fn((100, 1), |(i, _)| (i % 10) as Float).into_dyn();
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal().value(x.into()).value_public(true).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let candidates = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![(0..11).map(|v| v as Float).collect()])))
+            .value_public(true).build();
+
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }];
+        analysis.dp_median(data, privacy_usage)
+            .candidates(candidates)
+            .mechanism("exponential".to_string())
+            .build();
+
+        let (release, _warnings) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let report = whitenoise_validator::generate_report(
+            analysis.privacy_definition, analysis.components, release).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        // under add/remove neighboring, the median rank has sensitivity 1/2, and there are 11 candidates
+        let accuracy = report[0]["accuracy"]["accuracyValue"].as_f64().unwrap();
+        assert!((accuracy - (11. / 0.05 as Float).ln()).abs() < 1e-10);
+    }
+}
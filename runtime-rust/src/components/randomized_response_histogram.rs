@@ -0,0 +1,74 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::{Array, Jagged, ReleaseNode};
+use crate::components::Evaluable;
+use crate::components::histogram::histogram;
+use ndarray::{ArrayD, arr1, Axis};
+
+use whitenoise_validator::{proto, Float};
+use whitenoise_validator::utilities::take_argument;
+use whitenoise_validator::utilities::privacy::{get_epsilon, spread_privacy_usage, randomized_response_probabilities};
+
+
+impl Evaluable for proto::RandomizedResponseHistogram {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let epsilon = get_epsilon(&spread_privacy_usage(&self.privacy_usage, 1)?[0])?;
+
+        let data = take_argument(&mut arguments, "data")?.array()?;
+        let num_reports = data.num_records()? as Float;
+
+        // the number of reports that indicate each category
+        let tallies = match self.encoding.to_lowercase().as_str() {
+            "binary" => histogram(&data.bool()?, &arr1(&[false, true]).into_dyn())?,
+            "generalized" => match (data, take_argument(&mut arguments, "categories")?.jagged()?) {
+                (Array::Bool(data), Jagged::Bool(categories)) => histogram(&data, &first_column(categories)?)?,
+                (Array::Int(data), Jagged::Int(categories)) => histogram(&data, &first_column(categories)?)?,
+                (Array::Str(data), Jagged::Str(categories)) => histogram(&data, &first_column(categories)?)?,
+                _ => return Err("data and categories must be homogeneously typed, and may not be floats".into())
+            },
+            "unary" => data.bool()?.mapv(|bit| bit as i64).sum_axis(Axis(0)),
+            _ => return Err("encoding: must be one of [Binary, Generalized, Unary]".into())
+        };
+
+        let (prob_truth, prob_other) = randomized_response_probabilities(
+            &self.encoding, epsilon, tallies.len())?;
+
+        Ok(ReleaseNode::new(randomized_response_histogram(
+            tallies.iter().map(|tally| *tally as Float).collect(),
+            num_reports, prob_truth, prob_other).into()))
+    }
+}
+
+/// Unbiased estimates of the number of records in each category, from randomized response tallies.
+///
+/// Each report indicates the true category with probability `prob_truth`, and any other category with probability `prob_other`.
+/// The expected tally of a category with `count` records is therefore `count * prob_truth + (num_reports - count) * prob_other`.
+///
+/// # Arguments
+/// * `tallies` - The number of reports that indicate each category.
+/// * `num_reports` - The number of reports.
+/// * `prob_truth` - Probability that a report indicates the true category.
+/// * `prob_other` - Probability that a report indicates a category other than the true category.
+///
+/// # Return
+/// Estimated number of records in each category.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::randomized_response_histogram::randomized_response_histogram;
+/// let estimates = randomized_response_histogram(vec![6., 4.], 10., 0.75, 0.25);
+/// assert_eq!(estimates, ndarray::arr1(&[7., 3.]).into_dyn());
+/// ```
+pub fn randomized_response_histogram(
+    tallies: Vec<Float>, num_reports: Float, prob_truth: Float, prob_other: Float
+) -> ArrayD<Float> {
+    arr1(&tallies).into_dyn()
+        .mapv(|tally| (tally - num_reports * prob_other) / (prob_truth - prob_other))
+}
+
+fn first_column<T: Clone>(categories: Vec<Vec<T>>) -> Result<ArrayD<T>> {
+    categories.first()
+        .map(|column| arr1(column).into_dyn())
+        .ok_or_else(|| "categories: must contain one column".into())
+}
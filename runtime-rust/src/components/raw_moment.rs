@@ -5,15 +5,22 @@ use whitenoise_validator::base::ReleaseNode;
 use whitenoise_validator::utilities::take_argument;
 use crate::components::Evaluable;
 use whitenoise_validator::{proto, Float};
-use ndarray::ArrayD;
+use ndarray::{ArrayD, Array, Axis};
+use crate::utilities::{get_num_columns, is_overflow_protected};
 use crate::components::mean::mean;
 
 use std::convert::TryFrom;
 
 impl Evaluable for proto::RawMoment {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         let data = take_argument(&mut arguments, "data")?.array()?.float()?;
-        Ok(ReleaseNode::new(raw_moment(&data, self.order)?.into()))
+        Ok(ReleaseNode::new(if protect_overflow {
+            scaled_raw_moment(&data, self.order)?
+        } else {
+            raw_moment(&data, self.order)?
+        }.into()))
     }
 }
 
@@ -50,4 +57,47 @@ pub fn raw_moment(data: &ArrayD<Float>, order: u32) -> Result<ArrayD<Float>> {
             .for_each(|v| *v = v.powi(k)));
 
     mean(&data)
-}
\ No newline at end of file
+}
+
+/// Accepts data and returns sample estimate of kth raw moment for each column,
+/// accumulating each power divided by the number of records.
+///
+/// The accumulator never exceeds the largest power of any record,
+/// so the estimate cannot overflow whenever the powers of the records are finite.
+///
+/// # Arguments
+/// * `data` - Data for which you would like the kth raw moments.
+/// * `order` - Number representing the kth moment you want.
+///
+/// # Return
+/// kth sample moment for each column.
+///
+/// # Example
+/// ```
+/// use ndarray::{ArrayD, arr2};
+/// use whitenoise_runtime::components::raw_moment::scaled_raw_moment;
+/// use whitenoise_validator::Float;
+/// let data: ArrayD<Float> = arr2(&[ [1e300], [1e300] ]).into_dyn();
+/// let moments = scaled_raw_moment(&data, 1).unwrap();
+/// assert_eq!(moments, arr2(&[[1e300]]).into_dyn());
+/// ```
+pub fn scaled_raw_moment(data: &ArrayD<Float>, order: u32) -> Result<ArrayD<Float>> {
+    let k = i32::try_from(order)
+        .map_err(|_| Error::from("order: invalid size"))?;
+
+    let num_records = data.len_of(Axis(0));
+    if num_records == 0 {
+        return Err("attempted raw moment of an empty column".into())
+    }
+
+    // iterate over the generalized columns
+    let moments = data.gencolumns().into_iter()
+        .map(|column| column.fold(0., |moment, v| moment + v.powi(k) / num_records as Float))
+        .collect::<Vec<Float>>();
+
+    match data.ndim() {
+        1 => Array::from_shape_vec(vec![], moments),
+        2 => Array::from_shape_vec(vec![1, get_num_columns(data)? as usize], moments),
+        _ => return Err("invalid data shape for RawMoment".into())
+    }.map_err(|_| "unable to package RawMoment result into an array".into())
+}
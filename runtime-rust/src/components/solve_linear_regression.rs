@@ -0,0 +1,173 @@
+use whitenoise_validator::errors::*;
+
+use crate::NodeArguments;
+use whitenoise_validator::base::ReleaseNode;
+use whitenoise_validator::utilities::take_argument;
+use crate::components::Evaluable;
+use ndarray::arr1;
+use whitenoise_validator::{proto, Float};
+
+impl Evaluable for proto::SolveLinearRegression {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let covariance = take_argument(&mut arguments, "covariance")?.array()?.float()?;
+        let cross_covariance = take_argument(&mut arguments, "cross_covariance")?.array()?.float()?;
+        let data_mean = take_argument(&mut arguments, "data_mean")?.array()?.float()?;
+        let target_mean = take_argument(&mut arguments, "target_mean")?.array()?.float()?;
+
+        let target_mean = *target_mean.iter().next()
+            .ok_or("target_mean: must contain one element")?;
+
+        Ok(ReleaseNode::new(arr1(&solve_linear_regression(
+            &covariance.iter().cloned().collect::<Vec<Float>>(),
+            &cross_covariance.iter().cloned().collect::<Vec<Float>>(),
+            &data_mean.iter().cloned().collect::<Vec<Float>>(),
+            target_mean,
+            self.ridge as Float
+        )?).into_dyn().into()))
+    }
+}
+
+/// Solves the least squares coefficients of a linear model from the first and second moments of the data.
+///
+/// The coefficients solve `(covariance + ridge * I) beta = cross_covariance`,
+/// and the intercept is chosen such that the model passes through the means.
+///
+/// # Arguments
+/// * `covariance` - Covariance matrix of the features, in row-major order.
+/// * `cross_covariance` - Covariance between each feature and the target.
+/// * `data_mean` - Mean of each feature.
+/// * `target_mean` - Mean of the target.
+/// * `ridge` - Penalty added to the diagonal of the covariance matrix.
+///
+/// # Return
+/// The intercept, followed by one coefficient for each feature.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::components::solve_linear_regression::solve_linear_regression;
+/// // y = 2 x + 1, where x has mean 2 and variance 4
+/// let parameters = solve_linear_regression(&[4.], &[8.], &[2.], 5., 0.).unwrap();
+/// assert_eq!(parameters, vec![1., 2.]);
+/// ```
+pub fn solve_linear_regression(
+    covariance: &[Float], cross_covariance: &[Float],
+    data_mean: &[Float], target_mean: Float, ridge: Float,
+) -> Result<Vec<Float>> {
+    let num_features = data_mean.len();
+    if covariance.len() != num_features * num_features || cross_covariance.len() != num_features {
+        return Err("covariance and cross_covariance must have one row per feature".into())
+    }
+
+    // augmented matrix [covariance + ridge * I | cross_covariance]
+    let mut system = (0..num_features)
+        .map(|i| (0..num_features)
+            .map(|j| covariance[i * num_features + j] + if i == j { ridge } else { 0. })
+            .chain(std::iter::once(cross_covariance[i]))
+            .collect::<Vec<Float>>())
+        .collect::<Vec<Vec<Float>>>();
+
+    // gaussian elimination with partial pivoting
+    for pivot in 0..num_features {
+        let largest = (pivot..num_features)
+            .max_by(|l, r| system[*l][pivot].abs().partial_cmp(&system[*r][pivot].abs())
+                .unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or(pivot);
+        if system[largest][pivot].abs() <= Float::EPSILON || system[largest][pivot].is_nan() {
+            return Err("covariance matrix is singular. Consider adding a ridge penalty".into())
+        }
+        system.swap(pivot, largest);
+
+        let (upper, lower) = system.split_at_mut(pivot + 1);
+        lower.iter_mut().for_each(|row| {
+            let factor = row[pivot] / upper[pivot][pivot];
+            row.iter_mut().zip(upper[pivot].iter()).skip(pivot)
+                .for_each(|(value, pivot_value)| *value -= factor * pivot_value);
+        });
+    }
+
+    // back substitution
+    let mut coefficients: Vec<Float> = vec![0.; num_features];
+    for row in (0..num_features).rev() {
+        let remainder = (row + 1..num_features)
+            .fold(system[row][num_features], |total, column| total - system[row][column] * coefficients[column]);
+        coefficients[row] = remainder / system[row][row];
+    }
+
+    let intercept = target_mean - data_mean.iter().zip(coefficients.iter())
+        .map(|(mean, coefficient)| mean * coefficient)
+        .sum::<Float>();
+
+    Ok(std::iter::once(intercept).chain(coefficients).collect())
+}
+
+#[cfg(test)]
+mod test_solve_linear_regression {
+    use crate::components::solve_linear_regression::solve_linear_regression;
+    use whitenoise_validator::Float;
+
+    #[test]
+    fn test_solve_two_features() {
+        // y = 1 + 2 x_1 - 3 x_2, with correlated features
+        let covariance = [2., 1., 1., 3.];
+        let cross_covariance = [2. * 2. - 3. * 1., 2. * 1. - 3. * 3.];
+        let parameters = solve_linear_regression(
+            &covariance, &cross_covariance, &[1., -1.], 1. + 2. + 3., 0.).unwrap();
+
+        [1., 2., -3.].iter().zip(parameters.iter())
+            .for_each(|(expected, actual): (&Float, &Float)| assert!((expected - actual).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_solve_singular() {
+        assert!(solve_linear_regression(&[1., 1., 1., 1.], &[1., 1.], &[0., 0.], 0., 0.).is_err());
+        assert!(solve_linear_regression(&[1., 1., 1., 1.], &[1., 1.], &[0., 0.], 0., 1.).is_ok());
+    }
+
+    #[test]
+    fn test_dp_linear_regression() {
+        use whitenoise_validator::bindings::Analysis;
+        use whitenoise_validator::proto;
+        use ndarray::Array;
+
+        // y = 2 x + 1
+        let x = Array::from_shape_fn((100, 1), |(i, _)| (i % 10) as Float).into_dyn();
+        let y = x.mapv(|v| 2. * v + 1.);
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal().value(x.into()).value_public(true).build();
+        let target = analysis.literal().value(y.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(100.into()).value_public(true).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper_data = analysis.literal().value(10.0.into()).value_public(true).build();
+        let upper_target = analysis.literal().value(21.0.into()).value_public(true).build();
+
+        let data = analysis.clamp(data).lower(lower).upper(upper_data).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).lower(lower).upper(upper_data).build();
+        let target = analysis.clamp(target).lower(lower).upper(upper_target).build();
+        let target = analysis.resize(target)
+            .number_rows(number_rows).lower(lower).upper(upper_target).build();
+
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1e6, delta: 0.
+            }))
+        }];
+        let regression = analysis.dp_linear_regression(data, target, privacy_usage).build();
+
+        let (release, _warnings) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let report = whitenoise_validator::generate_report(
+            analysis.privacy_definition, analysis.components, release.clone()).unwrap();
+        assert!(report.contains("DPLinearRegression"));
+
+        let parameters = release.get(&regression).unwrap().value.clone().array().unwrap().float().unwrap();
+        assert_eq!(parameters.len(), 2);
+        assert!((parameters[0] - 1.).abs() < 0.1);
+        assert!((parameters[1] - 2.).abs() < 0.1);
+    }
+}
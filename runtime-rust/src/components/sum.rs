@@ -4,17 +4,23 @@ use crate::NodeArguments;
 use whitenoise_validator::base::{Array, ReleaseNode};
 use whitenoise_validator::utilities::{take_argument};
 use crate::components::Evaluable;
-use whitenoise_validator::proto;
+use whitenoise_validator::{proto, Integer};
 use ndarray::{ArrayD};
 use std::ops::Add;
-use crate::utilities::get_num_columns;
+use crate::utilities::{get_num_columns, is_overflow_protected};
 use num::Zero;
 
 impl Evaluable for proto::Sum {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         match take_argument(&mut arguments, "data")?.array()? {
             Array::Float(data) => Ok(sum(&data)?.into()),
-            Array::Int(data) => Ok(sum(&data)?.into()),
+            Array::Int(data) => Ok(if protect_overflow {
+                saturating_sum(&data)?
+            } else {
+                sum(&data)?
+            }.into()),
             _ => return Err("data must be either f64 or i64".into())
         }.map(ReleaseNode::new)
     }
@@ -37,11 +43,38 @@ impl Evaluable for proto::Sum {
 /// assert!(sums == arr2(&[[6., 60.]]).into_dyn());
 /// ```
 pub fn sum<T: Add<T, Output=T> + Zero + Copy>(data: &ArrayD<T>) -> Result<ArrayD<T>> {
+    fold_columns(data, &|sum, i| sum + i)
+}
+
+/// Calculates sum for each column of the data, saturating at the numeric bounds of the integer type.
+///
+/// The validator rejects sums that may overflow when protecting against overflow,
+/// so saturation only guards the sensitivity analysis against inaccurate bounds.
+///
+/// # Arguments
+/// * `data` - Data for which you would like the sum of each column.
+///
+/// # Return
+/// Sum of each column of the data.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use whitenoise_runtime::components::sum::saturating_sum;
+/// let data = arr2(&[ [i64::MAX, 10], [2, 20] ]).into_dyn();
+/// let sums = saturating_sum(&data).unwrap();
+/// assert!(sums == arr2(&[[i64::MAX, 30]]).into_dyn());
+/// ```
+pub fn saturating_sum(data: &ArrayD<Integer>) -> Result<ArrayD<Integer>> {
+    fold_columns(data, &|sum: Integer, i| sum.saturating_add(i))
+}
+
+fn fold_columns<T: Zero + Copy>(data: &ArrayD<T>, operator: &dyn Fn(T, T) -> T) -> Result<ArrayD<T>> {
     let data = data.clone();
 
     // iterate over the generalized columns
     let means = data.gencolumns().into_iter()
-        .map(|column| column.fold(T::zero(), |sum, i| sum + *i)).collect::<Vec<T>>();
+        .map(|column| column.fold(T::zero(), |sum, i| operator(sum, *i))).collect::<Vec<T>>();
 
     let array = match data.ndim() {
         1 => ndarray::Array::from_shape_vec(vec![], means),
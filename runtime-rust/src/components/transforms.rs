@@ -5,17 +5,22 @@ use crate::NodeArguments;
 use whitenoise_validator::base::{Value, Array, ReleaseNode};
 use whitenoise_validator::utilities::take_argument;
 use whitenoise_validator::{proto, Integer, Float};
-use crate::utilities::broadcast_map;
+use crate::utilities::{broadcast_map, is_overflow_protected};
 
 
 impl Evaluable for proto::Abs {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         match take_argument(&mut arguments, "data")? {
             Value::Array(data) => match data {
                 Array::Float(data) =>
                     Ok(data.mapv(|v| v.abs()).into()),
-                Array::Int(data) =>
-                    Ok(data.mapv(|v| v.abs()).into()),
+                Array::Int(data) => Ok(if protect_overflow {
+                    data.mapv(|v| v.saturating_abs())
+                } else {
+                    data.mapv(|v| v.abs())
+                }.into()),
                 _ => Err("Abs: The atomic type must be numeric".into())
             },
             _ => Err("Abs: The argument type must be an array".into())
@@ -24,13 +29,18 @@ impl Evaluable for proto::Abs {
 }
 
 impl Evaluable for proto::Add {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
             (Value::Array(left), Value::Array(right)) => match (left, right) {
                 (Array::Float(x), Array::Float(y)) =>
                     Ok(broadcast_map(x, y, &|l, r| l + r)?.into()),
-                (Array::Int(x), Array::Int(y)) =>
-                    Ok(broadcast_map(x, y, &|l, r| l + r)?.into()),
+                (Array::Int(x), Array::Int(y)) => Ok(if protect_overflow {
+                    broadcast_map(x, y, &|l: &Integer, r: &Integer| l.saturating_add(*r))?
+                } else {
+                    broadcast_map(x, y, &|l, r| l + r)?
+                }.into()),
                 (Array::Str(x), Array::Str(y)) =>
                     Ok(broadcast_map(x, y, &|l, r| format!("{}{}", l, r))?.into()),
                 _ => Err("Add: Either the argument types are mismatched or boolean.".into())
@@ -144,13 +154,18 @@ impl Evaluable for proto::Modulo {
 }
 
 impl Evaluable for proto::Multiply {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
             (Value::Array(left), Value::Array(right)) => match (left, right) {
                 (Array::Float(x), Array::Float(y)) =>
                     Ok(broadcast_map(x, y, &|l, r| l * r)?.into()),
-                (Array::Int(x), Array::Int(y)) =>
-                    Ok(broadcast_map(x, y, &|l, r| l * r)?.into()),
+                (Array::Int(x), Array::Int(y)) => Ok(if protect_overflow {
+                    broadcast_map(x, y, &|l: &Integer, r: &Integer| l.saturating_mul(*r))?
+                } else {
+                    broadcast_map(x, y, &|l, r| l * r)?
+                }.into()),
                 _ => Err("Multiply: Either the argument types are mismatched or non-numeric.".into())
             },
             _ => Err("Multiply: Both arguments must be arrays.".into())
@@ -172,11 +187,17 @@ impl Evaluable for proto::Negate {
 }
 
 impl Evaluable for proto::Negative {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         match take_argument(&mut arguments, "data")? {
             Value::Array(data) => match data {
                 Array::Float(x) => Ok((-x).into()),
-                Array::Int(x) => Ok((-x).into()),
+                Array::Int(x) => Ok(if protect_overflow {
+                    x.mapv(|v| v.saturating_neg())
+                } else {
+                    -x
+                }.into()),
                 _ => Err("Negative: Argument must be numeric.".into())
             },
             _ => Err("Negative: Argument must be an array.".into())
@@ -198,13 +219,18 @@ impl Evaluable for proto::Or {
 }
 
 impl Evaluable for proto::Power {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         match (take_argument(&mut arguments, "data")?, take_argument(&mut arguments, "radical")?) {
             (Value::Array(left), Value::Array(right)) => match (left, right) {
                 (Array::Float(x), Array::Float(y)) =>
                     Ok(broadcast_map(x, y, &|l, r| l.powf(*r))?.into()),
-                (Array::Int(x), Array::Int(y)) =>
-                    Ok(broadcast_map(x, y, &|l, r| l.pow(*r as u32))?.into()),
+                (Array::Int(x), Array::Int(y)) => Ok(if protect_overflow {
+                    broadcast_map(x, y, &|l: &Integer, r: &Integer| l.saturating_pow(*r as u32))?
+                } else {
+                    broadcast_map(x, y, &|l, r| l.pow(*r as u32))?
+                }.into()),
                 _ => Err("Power: Either the argument types are mismatched or non-numeric.".into())
             },
             _ => Err("Power: Both arguments must be arrays.".into())
@@ -234,7 +260,7 @@ impl Evaluable for proto::RowMin {
                 (Array::Float(x), Array::Float(y)) =>
                     Ok(broadcast_map(x, y, &|l: &Float, r: &Float| l.min(*r))?.into()),
                 (Array::Int(x), Array::Int(y)) =>
-                    Ok(broadcast_map(x, y, &|l: &Integer, r: &Integer| *l.max(r))?.into()),
+                    Ok(broadcast_map(x, y, &|l: &Integer, r: &Integer| *l.min(r))?.into()),
                 _ => Err("RowMin: Either the argument types are mismatched or non-numeric.".into())
             },
             _ => Err("RowMin: Both arguments must be arrays.".into())
@@ -243,16 +269,37 @@ impl Evaluable for proto::RowMin {
 }
 
 impl Evaluable for proto::Subtract {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let protect_overflow = is_overflow_protected(privacy_definition);
+
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
             (Value::Array(left), Value::Array(right)) => match (left, right) {
                 (Array::Float(x), Array::Float(y)) =>
                     Ok(broadcast_map(x, y, &|l, r| l - r)?.into()),
-                (Array::Int(x), Array::Int(y)) =>
-                    Ok(broadcast_map(x, y, &|l, r| l - r)?.into()),
+                (Array::Int(x), Array::Int(y)) => Ok(if protect_overflow {
+                    broadcast_map(x, y, &|l: &Integer, r: &Integer| l.saturating_sub(*r))?
+                } else {
+                    broadcast_map(x, y, &|l, r| l - r)?
+                }.into()),
                 _ => Err("Subtract: Either the argument types are mismatched or non-numeric.".into())
             },
             _ => Err("Subtract: Both arguments must be arrays.".into())
         }.map(ReleaseNode::new)
     }
 }
+
+#[cfg(test)]
+mod test_transforms {
+    use ndarray::arr1;
+    use whitenoise_validator::proto;
+    use crate::components::Evaluable;
+
+    #[test]
+    fn test_row_min_int() {
+        let release = proto::RowMin {}.evaluate(&None, indexmap::indexmap![
+            "left".into() => arr1(&[1i64, 5]).into_dyn().into(),
+            "right".into() => arr1(&[3i64, 2]).into_dyn().into()]).unwrap();
+
+        assert_eq!(release.value.array().unwrap().int().unwrap(), arr1(&[1i64, 2]).into_dyn());
+    }
+}
@@ -23,8 +23,10 @@ use whitenoise_validator::base::{Value, ReleaseNode, Release, IndexKey, Componen
 use whitenoise_validator::utilities::{get_sinks, get_input_properties, get_dependents};
 
 use crate::components::Evaluable;
+use crate::utilities::streaming::Streaming;
 
 use std::iter::FromIterator;
+use std::time::Instant;
 use indexmap::map::IndexMap;
 
 
@@ -48,6 +50,32 @@ pub type NodeArguments = IndexMap<IndexKey, Value>;
 /// # Return
 /// a collection of computed values for components in the graph
 pub fn release(
+    privacy_definition: Option<proto::PrivacyDefinition>,
+    computation_graph: HashMap<u32, proto::Component>,
+    release: Release,
+    filter_level: proto::FilterLevel
+) -> Result<(Release, Vec<Error>)> {
+    if !utilities::entropy::is_cryptographically_secure() {
+        return Err("the entropy source is not cryptographically secure, so the release would not be differentially private".into())
+    }
+    evaluate_graph(privacy_definition, computation_graph, release, filter_level)
+}
+
+/// Execute the computation as in [release](fn.release.html), from any entropy source.
+///
+/// Only available in tests, for reproducible releases from a seeded entropy source.
+/// The release is not differentially private unless the entropy source is cryptographically secure.
+#[cfg(any(test, feature = "test-seeded-entropy"))]
+pub fn release_with_insecure_entropy(
+    privacy_definition: Option<proto::PrivacyDefinition>,
+    computation_graph: HashMap<u32, proto::Component>,
+    release: Release,
+    filter_level: proto::FilterLevel
+) -> Result<(Release, Vec<Error>)> {
+    evaluate_graph(privacy_definition, computation_graph, release, filter_level)
+}
+
+fn evaluate_graph(
     privacy_definition: Option<proto::PrivacyDefinition>,
     mut computation_graph: HashMap<u32, proto::Component>,
     mut release: Release,
@@ -90,6 +118,35 @@ pub fn release(
             continue;
         }
 
+        // aggregate data sources in chunks, when the aggregation decomposes over records
+        let graph_size = computation_graph.len();
+        let streaming = utilities::streaming::stream_aggregation(
+            &privacy_definition, &mut computation_graph, &mut properties,
+            &mut release, &mut maximum_id, component_id)?;
+
+        // streamed nodes may have been expanded
+        if computation_graph.len() != graph_size {
+            parents = get_dependents(&computation_graph);
+        }
+
+        match streaming {
+            Streaming::Evaluated(mut evaluation) => {
+                traversal.pop();
+                evaluation.public = properties.get(&component_id)
+                    .map(ValueProperties::is_public)
+                    .unwrap_or(false);
+                verify_evaluation(
+                    &privacy_definition, &computation_graph, &properties, component_id, &evaluation.value)?;
+                release.insert(component_id, *evaluation);
+                continue
+            }
+            Streaming::Dependencies(dependencies) => {
+                traversal.extend(dependencies);
+                continue
+            }
+            Streaming::Unavailable => ()
+        }
+
         let component: &proto::Component = computation_graph.get(&component_id)
             .ok_or_else(|| Error::from("attempted to retrieve a non-existent component id"))?;
 
@@ -173,6 +230,13 @@ pub fn release(
         // the expansion may have overwritten the current component
         let component = computation_graph.get(&component_id).unwrap();
 
+        // spend from the privacy budget ledgers of upstream datasets before any mechanism is evaluated
+        if let Some(privacy_definition) = &privacy_definition {
+            utilities::ledger::debit_mechanism(
+                privacy_definition, &computation_graph, component_id,
+                &get_input_properties(&component, &properties)?)?;
+        }
+
         // println!("node id:    {:?}", component_id);
         // println!("component:  {:?}", component.variant);
         // println!("arguments:  {:?}", node_arguments);
@@ -218,10 +282,22 @@ pub fn release(
                 .map(|v| node_arguments.insert(name, v));
         }
 
+        let start = Instant::now();
+
         // evaluate the component using the Evaluable trait, which is implemented on the proto::component::Variant enum
-        let mut evaluation = component.variant.as_ref()
-            .ok_or_else(|| Error::from("variant of component must be known"))?
-            .evaluate(&privacy_definition, node_arguments)?;
+        let evaluation = component.variant.as_ref()
+            .ok_or_else(|| Error::from("variant of component must be known"))
+            .and_then(|variant| variant.evaluate(&privacy_definition, node_arguments));
+
+        // pad the evaluation, including failures, to a bound derived from public sizes
+        if let Some(privacy_definition) = &privacy_definition {
+            if privacy_definition.protect_elapsed_time {
+                utilities::pad_elapsed_time(
+                    privacy_definition, &get_input_properties(component, &properties)?, start)?;
+            }
+        }
+
+        let mut evaluation = evaluation?;
 
         // println!("evaluation: {:?}", evaluation);
 
@@ -229,6 +305,9 @@ pub fn release(
             .map(ValueProperties::is_public)
             .unwrap_or(false);
 
+        verify_evaluation(
+            &privacy_definition, &computation_graph, &properties, component_id, &evaluation.value)?;
+
         // store the evaluated `Value` enum in the release
         release.insert(component_id, evaluation);
     }
@@ -254,3 +333,22 @@ pub fn release(
 
     Ok((release, warnings))
 }
+
+/// When enabled by the privacy definition, check the evaluation of a node against its derived properties.
+fn verify_evaluation(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    computation_graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    component_id: u32,
+    value: &Value
+) -> Result<()> {
+    let enabled = privacy_definition.as_ref()
+        .map(|privacy_definition| privacy_definition.verify_properties)
+        .unwrap_or(false);
+
+    match properties.get(&component_id) {
+        Some(property) if enabled => utilities::verification::verify_evaluation(
+            component_id, computation_graph.get(&component_id), property, value),
+        _ => Ok(())
+    }
+}
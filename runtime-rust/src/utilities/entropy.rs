@@ -0,0 +1,206 @@
+//! Sources of entropy for the samplers in the noise module.
+//!
+//! Every random draw in the runtime reads bytes through [fill_bytes](fn.fill_bytes.html),
+//! which delegates to the entropy source of the current thread.
+//! The default source is the OpenSSL CSPRNG.
+//! A source that cannot provide entropy returns an error, and the error is propagated out of the sampler,
+//! so that a release is never computed from predictable noise.
+//! Other sources may only be installed in tests, and `release` refuses to run unless the installed source is cryptographically secure.
+
+use whitenoise_validator::errors::*;
+
+use openssl::rand::rand_bytes;
+use std::cell::RefCell;
+
+
+/// A source of uniformly random bytes.
+pub trait EntropySource {
+    /// Fill the buffer with uniformly random bytes.
+    ///
+    /// If entropy is unavailable, an error must be returned. The contents of the buffer are then unspecified.
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<()>;
+
+    /// True if the bytes are unpredictable, so that releases computed from them are differentially private.
+    fn is_cryptographically_secure(&self) -> bool {
+        false
+    }
+}
+
+/// Entropy from the OpenSSL CSPRNG, which is seeded by the operating system.
+#[derive(Debug, Default)]
+pub struct OpenSSLEntropy;
+
+impl EntropySource for OpenSSLEntropy {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<()> {
+        rand_bytes(buffer)
+            .map_err(|err| format!("failed to read entropy from OpenSSL: {}", err).into())
+    }
+
+    fn is_cryptographically_secure(&self) -> bool {
+        true
+    }
+}
+
+/// Deterministic bytes from a seed, for reproducible tests.
+///
+/// The bytes are generated by SplitMix64, which is not cryptographically secure.
+/// Releases computed from this source are not differentially private.
+#[cfg(any(test, feature = "test-seeded-entropy"))]
+#[derive(Debug)]
+pub struct SeededEntropy {
+    state: u64
+}
+
+#[cfg(any(test, feature = "test-seeded-entropy"))]
+impl SeededEntropy {
+    pub fn new(seed: u64) -> Self {
+        SeededEntropy { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(any(test, feature = "test-seeded-entropy"))]
+impl EntropySource for SeededEntropy {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<()> {
+        buffer.chunks_mut(8).for_each(|chunk| {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        });
+        Ok(())
+    }
+}
+
+thread_local! {
+    static ENTROPY_SOURCE: RefCell<Box<dyn EntropySource>> = RefCell::new(Box::new(OpenSSLEntropy));
+}
+
+/// Fill the buffer with bytes from the entropy source of the current thread.
+///
+/// # Arguments
+/// * `buffer` - Buffer to fill with uniformly random bytes.
+///
+/// # Return
+/// An error if the entropy source failed. The buffer must not be used in this case.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::entropy::fill_bytes;
+/// let mut buffer = [0u8; 16];
+/// fill_bytes(&mut buffer).unwrap();
+/// ```
+pub fn fill_bytes(buffer: &mut [u8]) -> Result<()> {
+    ENTROPY_SOURCE.with(|source| source.borrow_mut().fill_bytes(buffer))
+}
+
+/// True if the entropy source of the current thread is cryptographically secure.
+pub fn is_cryptographically_secure() -> bool {
+    ENTROPY_SOURCE.with(|source| source.borrow().is_cryptographically_secure())
+}
+
+/// Replace the entropy source of the current thread.
+///
+/// Only available in tests. `release` refuses to run unless the source is cryptographically secure.
+///
+/// # Arguments
+/// * `source` - The new entropy source.
+///
+/// # Return
+/// The previous entropy source.
+#[cfg(any(test, feature = "test-seeded-entropy"))]
+pub fn set_entropy_source(source: Box<dyn EntropySource>) -> Box<dyn EntropySource> {
+    ENTROPY_SOURCE.with(|current| current.replace(source))
+}
+
+#[cfg(test)]
+mod test_entropy {
+    use whitenoise_validator::errors::*;
+    use crate::utilities::entropy::{EntropySource, OpenSSLEntropy, set_entropy_source};
+    use crate::utilities::noise;
+    use whitenoise_validator::proto;
+    use std::collections::HashMap;
+
+    struct FailingEntropy;
+
+    impl EntropySource for FailingEntropy {
+        fn fill_bytes(&mut self, _buffer: &mut [u8]) -> Result<()> {
+            Err("entropy unavailable".into())
+        }
+    }
+
+    #[test]
+    fn test_fail_closed() {
+        set_entropy_source(Box::new(FailingEntropy));
+
+        assert!(noise::sample_bit().is_err());
+        assert!(noise::sample_uniform(0., 1., true).is_err());
+        assert!(noise::sample_laplace(0., 1., false).is_err());
+        assert!(noise::sample_gaussian(0., 1., false).is_err());
+        assert!(noise::sample_simple_geometric_mechanism(1., 0, 10, false).is_err());
+
+        set_entropy_source(Box::new(OpenSSLEntropy));
+        assert!(noise::sample_laplace(0., 1., false).is_ok());
+    }
+
+    #[test]
+    fn test_refuse_insecure_release() {
+        let release = || crate::release(None, HashMap::new(), HashMap::new(), proto::FilterLevel::All);
+
+        // sources are insecure unless marked otherwise
+        set_entropy_source(Box::new(FailingEntropy));
+        assert!(release().is_err());
+
+        set_entropy_source(Box::new(OpenSSLEntropy));
+        assert!(release().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "test-seeded-entropy")]
+    fn test_reproducible_release() {
+        use crate::utilities::entropy::SeededEntropy;
+        use whitenoise_validator::bindings::Analysis;
+        use ndarray::arr2;
+
+        let release_mean = || {
+            let mut analysis = Analysis::new();
+            let data = analysis.literal()
+                .value(arr2(&[[1.], [2.], [3.], [4.]]).into_dyn().into())
+                .value_public(true).build();
+            let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+            let upper = analysis.literal().value(5.0.into()).value_public(true).build();
+            let number_rows = analysis.literal().value(4.into()).value_public(true).build();
+            let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+            let resized = analysis.resize(clamped)
+                .number_rows(number_rows).lower(lower).upper(upper).build();
+
+            let mean = analysis.dp_mean(resized, vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon: 1., delta: 0.
+                }))
+            }]).mechanism("Laplace".to_string()).build();
+
+            let (release, _warnings) = crate::release_with_insecure_entropy(
+                Some(analysis.privacy_definition),
+                analysis.components,
+                analysis.release,
+                proto::FilterLevel::All).unwrap();
+            release.get(&mean).unwrap().value.clone().array().unwrap().float().unwrap()
+        };
+
+        set_entropy_source(Box::new(SeededEntropy::new(42)));
+        let first = release_mean();
+        set_entropy_source(Box::new(SeededEntropy::new(42)));
+        let second = release_mean();
+        set_entropy_source(Box::new(SeededEntropy::new(43)));
+        let third = release_mean();
+
+        assert_eq!(first, second);
+        assert_ne!(first, third);
+    }
+}
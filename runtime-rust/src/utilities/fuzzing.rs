@@ -0,0 +1,504 @@
+//! Property-based fuzzing of the property propagation rules of the validator.
+//!
+//! Random small datasets are passed through random chains of Clamp, Impute, Resize, Cast, the arithmetic transforms,
+//! Index and Filter. Each step is propagated by the validator and evaluated by the runtime, on the dataset and on a neighboring dataset.
+//! The derived natures, nullity and sizes must hold for both evaluations,
+//! and the derived c-stability must bound the number of output records that differ between them.
+//! A failure is reported with the seed that generated the chain, so that the chain can be replayed.
+
+use whitenoise_validator::errors::*;
+
+use whitenoise_validator::{proto, Float, Integer};
+use whitenoise_validator::base::{Value, Array, Jagged, ValueProperties, ArrayProperties, IndexKey, Nature, NatureContinuous, NatureCategorical, Vector1DNull, DataType, Release, ReleaseNode};
+use whitenoise_validator::utilities::propagate_properties;
+use proto::component::Variant;
+
+use crate::components::Evaluable;
+use crate::utilities::verification::verify_properties;
+use crate::utilities::entropy::SeededEntropy;
+
+use indexmap::map::IndexMap;
+use ndarray::{ArrayD, Array2, arr1};
+use std::collections::HashMap;
+
+
+/// Members of generated string data.
+const CATEGORIES: [&str; 4] = ["a", "b", "c", "d"];
+
+/// Deterministic generator, so that a failing chain can be replayed from its seed.
+pub struct FuzzRng(SeededEntropy);
+
+impl FuzzRng {
+    pub fn new(seed: u64) -> Self {
+        FuzzRng(SeededEntropy::new(seed))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    /// Uniform index in [0, upper).
+    pub fn below(&mut self, upper: usize) -> usize {
+        (self.next_u64() % upper as u64) as usize
+    }
+
+    /// Uniform float in [lower, upper).
+    pub fn float(&mut self, lower: Float, upper: Float) -> Float {
+        lower + (upper - lower) * (self.next_u64() >> 11) as Float / (1u64 << 53) as Float
+    }
+
+    /// Uniform integer in [lower, upper].
+    pub fn int(&mut self, lower: Integer, upper: Integer) -> Integer {
+        lower + self.below((upper - lower + 1) as usize) as Integer
+    }
+
+    pub fn bernoulli(&mut self, prob: f64) -> bool {
+        self.float(0., 1.) < prob
+    }
+
+    pub fn choose<'a, T>(&mut self, options: &'a [T]) -> &'a T {
+        &options[self.below(options.len())]
+    }
+}
+
+/// A private value in a chain, evaluated on a dataset and on a neighboring dataset.
+#[derive(Clone, Debug)]
+pub struct Node {
+    /// Properties derived by the validator.
+    pub property: ValueProperties,
+    /// Evaluation on the dataset.
+    pub value: Value,
+    /// Evaluation on the neighboring dataset.
+    pub neighbor: Value,
+    /// True if no step of the chain is randomized, so that the two evaluations are coupled.
+    pub deterministic: bool,
+}
+
+/// An argument to a component in a chain.
+pub enum Argument<'a> {
+    Private(&'a Node),
+    Public(Value),
+}
+
+/// Generate a random dataset, together with a neighboring dataset that satisfies the same declared properties.
+///
+/// # Return
+/// The dataset, and the number of records that differ between the dataset and its neighbor.
+/// When the number of records is declared, the neighbor substitutes a record. Otherwise the neighbor adds a record.
+pub fn random_dataset(rng: &mut FuzzRng) -> (Node, usize) {
+    let num_rows = rng.int(1, 8) as usize;
+    let num_columns = rng.int(1, 3) as usize;
+    let substitute = rng.bernoulli(0.5);
+    let declare_nature = rng.bernoulli(0.5);
+
+    let (value, neighbor, data_type, nullity, nature): (Value, Value, DataType, bool, Option<Nature>) = match rng.below(4) {
+        0 => {
+            let lower = (0..num_columns).map(|_| rng.float(-10., 0.)).collect::<Vec<Float>>();
+            let upper = lower.iter().map(|lower| rng.float(lower + 0.5, 10.)).collect::<Vec<Float>>();
+            let nullity = rng.bernoulli(0.5);
+            let (value, neighbor) = dataset_pair(rng, num_rows, num_columns, substitute, |rng, column|
+                if nullity && rng.bernoulli(0.2) { Float::NAN } else { rng.float(lower[column], upper[column]) });
+            let nature = Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Float(lower.into_iter().map(Some).collect()),
+                upper: Vector1DNull::Float(upper.into_iter().map(Some).collect()),
+            });
+            (value.into(), neighbor.into(), DataType::Float, nullity, Some(nature))
+        },
+        1 => {
+            let lower = (0..num_columns).map(|_| rng.int(-10, 0)).collect::<Vec<Integer>>();
+            let upper = lower.iter().map(|lower| rng.int(lower + 1, 10)).collect::<Vec<Integer>>();
+            let (value, neighbor) = dataset_pair(rng, num_rows, num_columns, substitute, |rng, column|
+                rng.int(lower[column], upper[column]));
+            let nature = Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Int(lower.into_iter().map(Some).collect()),
+                upper: Vector1DNull::Int(upper.into_iter().map(Some).collect()),
+            });
+            (value.into(), neighbor.into(), DataType::Int, false, Some(nature))
+        },
+        2 => {
+            let num_categories = rng.int(2, CATEGORIES.len() as Integer) as usize;
+            let (value, neighbor) = dataset_pair(rng, num_rows, num_columns, substitute, |rng, _|
+                rng.choose(&CATEGORIES[..num_categories]).to_string());
+            let nature = Nature::Categorical(NatureCategorical {
+                categories: Jagged::Str(vec![CATEGORIES[..num_categories].iter().map(|v| v.to_string()).collect(); num_columns])
+            });
+            (value.into(), neighbor.into(), DataType::Str, false, Some(nature))
+        },
+        _ => {
+            let (value, neighbor) = dataset_pair(rng, num_rows, num_columns, substitute, |rng, _| rng.bernoulli(0.5));
+            let nature = Nature::Categorical(NatureCategorical {
+                categories: Jagged::Bool(vec![vec![false, true]; num_columns])
+            });
+            (value.into(), neighbor.into(), DataType::Bool, false, Some(nature))
+        }
+    };
+
+    let property = ArrayProperties {
+        num_records: if substitute { Some(num_rows as i64) } else { None },
+        num_columns: Some(num_columns as i64),
+        nullity,
+        releasable: false,
+        c_stability: vec![1.; num_columns],
+        aggregator: None,
+        nature: nature.filter(|_| declare_nature),
+        data_type,
+        dataset_id: Some(0),
+        is_not_empty: true,
+        dimensionality: Some(2),
+        group_id: vec![],
+        naturally_ordered: true,
+        randomized_response: None
+    };
+
+    let node = Node { property: property.into(), value, neighbor, deterministic: true };
+    (node, if substitute { 2 } else { 1 })
+}
+
+/// Generate a dataset, and a neighbor that differs by one substituted or added record.
+fn dataset_pair<T: Clone>(
+    rng: &mut FuzzRng, num_rows: usize, num_columns: usize, substitute: bool,
+    mut sample: impl FnMut(&mut FuzzRng, usize) -> T
+) -> (ArrayD<T>, ArrayD<T>) {
+    let mut rows = (0..num_rows)
+        .map(|_| (0..num_columns).map(|column| sample(rng, column)).collect())
+        .collect::<Vec<Vec<T>>>();
+    let dataset = to_array(&rows);
+
+    let record = (0..num_columns).map(|column| sample(rng, column)).collect();
+    if substitute {
+        let index = rng.below(num_rows);
+        rows[index] = record;
+    } else {
+        rows.push(record);
+    }
+    (dataset, to_array(&rows))
+}
+
+fn to_array<T: Clone>(rows: &[Vec<T>]) -> ArrayD<T> {
+    Array2::from_shape_fn((rows.len(), rows[0].len()), |(i, j)| rows[i][j].clone()).into_dyn()
+}
+
+/// Propagate properties through a component, and evaluate it on the dataset and on the neighboring dataset.
+///
+/// # Return
+/// None if the validator rejects the arguments.
+/// Otherwise the output node, or an error if the component fails to evaluate or the derived properties do not hold.
+pub fn apply(variant: Variant, arguments: Vec<(&str, Argument)>, deterministic: bool) -> Result<Option<Node>> {
+    let privacy_definition = Some(proto::PrivacyDefinition { group_size: 1, ..Default::default() });
+
+    // the component is the only node in the graph. Private arguments are given by their properties,
+    // and public arguments by their releases
+    let mut component = proto::Component {
+        arguments: None,
+        variant: Some(variant.clone()),
+        omit: false,
+        submission: 0
+    };
+    let mut release = Release::new();
+    let mut properties = HashMap::<u32, ValueProperties>::new();
+    for (argument_id, (name, argument)) in arguments.iter().enumerate() {
+        let argument_id = argument_id as u32 + 1;
+        component.insert_argument(&(*name).into(), argument_id);
+        match argument {
+            Argument::Private(node) => {
+                properties.insert(argument_id, node.property.clone());
+            },
+            Argument::Public(value) => {
+                release.insert(argument_id, ReleaseNode { value: value.clone(), privacy_usages: None, public: true });
+            }
+        }
+    }
+    let mut computation_graph = HashMap::new();
+    computation_graph.insert(0, component);
+
+    let property = match propagate_properties(
+        &privacy_definition, &mut computation_graph, &mut release, Some(properties), false) {
+        Ok((mut properties, _warnings)) => properties.remove(&0)
+            .ok_or_else(|| Error::from("the validator did not derive properties for the component"))?,
+        Err(_) => return Ok(None)
+    };
+
+    let evaluate = |neighbor: bool| -> Result<Value> {
+        let node_arguments = arguments.iter()
+            .map(|(name, argument)| ((*name).into(), match argument {
+                Argument::Private(node) => if neighbor { node.neighbor.clone() } else { node.value.clone() },
+                Argument::Public(value) => value.clone()
+            }))
+            .collect::<IndexMap<IndexKey, Value>>();
+        variant.evaluate(&privacy_definition, node_arguments)
+            .map(|release| release.value)
+            .chain_err(|| format!("the validator accepted {:?}, but evaluation failed", variant))
+    };
+
+    let value = evaluate(false)?;
+    verify_properties(&property, &value)
+        .chain_err(|| format!("derived properties of {:?} do not hold on the dataset", variant))?;
+    let neighbor = evaluate(true)?;
+    verify_properties(&property, &neighbor)
+        .chain_err(|| format!("derived properties of {:?} do not hold on the neighboring dataset", variant))?;
+
+    let deterministic = deterministic && arguments.iter().all(|(_, argument)| match argument {
+        Argument::Private(node) => node.deterministic,
+        Argument::Public(_) => true
+    });
+    Ok(Some(Node { property, value, neighbor, deterministic }))
+}
+
+/// Check that the number of records that differ between the evaluations on neighboring datasets is bounded by the c-stability.
+///
+/// # Arguments
+/// * `node` - The output of a chain.
+/// * `input_distance` - The number of records that differ between the neighboring datasets.
+pub fn verify_stability(node: &Node, input_distance: usize) -> Result<()> {
+    if !node.deterministic {
+        return Ok(())
+    }
+    let c_stability = node.property.array()?.c_stability.iter().copied().fold(0., Float::max);
+    let distance = record_distance(&node.value, &node.neighbor)?;
+    if distance as Float > c_stability * input_distance as Float {
+        return Err(format!(
+            "c_stability: derived {}, but {} records differ when {} input records differ",
+            c_stability, distance, input_distance).into())
+    }
+    Ok(())
+}
+
+/// The size of the symmetric difference between the records of two arrays, as multisets.
+fn record_distance(left: &Value, right: &Value) -> Result<usize> {
+    let mut counts = HashMap::<String, i64>::new();
+    records(left)?.into_iter().for_each(|record| *counts.entry(record).or_insert(0) += 1);
+    records(right)?.into_iter().for_each(|record| *counts.entry(record).or_insert(0) -= 1);
+    Ok(counts.values().map(|count| count.unsigned_abs() as usize).sum())
+}
+
+/// Each record of an array, formatted as a string. Members of vectors are records.
+fn records(value: &Value) -> Result<Vec<String>> {
+    fn format_records<T>(data: &ArrayD<T>, format: impl Fn(&T) -> String) -> Vec<String> {
+        match data.ndim() {
+            0 | 1 => data.iter().map(format).collect(),
+            _ => data.genrows().into_iter()
+                .map(|row| row.iter().map(&format).collect::<Vec<String>>().join(","))
+                .collect()
+        }
+    }
+    Ok(match value.ref_array()? {
+        Array::Float(data) => format_records(data, |v| if v.is_nan() { "NaN".to_string() } else { v.to_string() }),
+        Array::Int(data) => format_records(data, Integer::to_string),
+        Array::Bool(data) => format_records(data, bool::to_string),
+        Array::Str(data) => format_records(data, String::clone),
+    })
+}
+
+/// A random public scalar of the given atomic type.
+fn random_scalar(rng: &mut FuzzRng, data_type: &DataType) -> Value {
+    match data_type {
+        DataType::Float => rng.float(-10., 10.).into(),
+        DataType::Int => rng.int(-10, 10).into(),
+        DataType::Bool => rng.bernoulli(0.5).into(),
+        _ => rng.choose(&CATEGORIES).to_string().into()
+    }
+}
+
+/// A random pair of public scalars of the given numeric type, where the first is less than the second.
+fn random_bounds(rng: &mut FuzzRng, data_type: &DataType) -> (Value, Value) {
+    match data_type {
+        DataType::Int => {
+            let lower = rng.int(-10, 9);
+            (lower.into(), rng.int(lower + 1, 10).into())
+        },
+        _ => {
+            let lower = rng.float(-10., 9.);
+            (lower.into(), rng.float(lower + 0.5, 10.).into())
+        }
+    }
+}
+
+/// Apply a random component to the node.
+///
+/// # Arguments
+/// * `rng` - Source of randomness for the choice of component and its public arguments.
+/// * `node` - The private argument.
+/// * `log` - A description of the applied component is appended to the log.
+///
+/// # Return
+/// None if the validator rejected the component.
+pub fn random_step(rng: &mut FuzzRng, node: &Node, log: &mut Vec<String>) -> Result<Option<Node>> {
+    let data_property = node.property.array()?;
+    let data_type = data_property.data_type.clone();
+    let num_columns = data_property.num_columns.unwrap_or(1) as usize;
+    let is_numeric = data_type == DataType::Float || data_type == DataType::Int;
+
+    let step = rng.below(11);
+    log.push(format!("step {} on {:?}", step, data_type));
+
+    let private = || Argument::Private(node);
+    match step {
+        // clamp to bounds, or to categories
+        0 => if is_numeric {
+            let (lower, upper) = random_bounds(rng, &data_type);
+            apply(Variant::Clamp(proto::Clamp {}), vec![
+                ("data", private()), ("lower", Argument::Public(lower)), ("upper", Argument::Public(upper))], true)
+        } else {
+            let categories = random_categories(rng, &data_type);
+            let null_value = random_scalar(rng, &data_type);
+            apply(Variant::Clamp(proto::Clamp {}), vec![
+                ("data", private()),
+                ("categories", Argument::Public(categories)),
+                ("null_value", Argument::Public(null_value))], true)
+        },
+        // impute from bounds, or from categories
+        1 => if is_numeric {
+            let (lower, upper) = random_bounds(rng, &data_type);
+            apply(Variant::Impute(proto::Impute {}), vec![
+                ("data", private()), ("lower", Argument::Public(lower)), ("upper", Argument::Public(upper))], false)
+        } else {
+            let categories = random_categories(rng, &data_type);
+            let null_values = random_categories(rng, &data_type);
+            apply(Variant::Impute(proto::Impute {}), vec![
+                ("data", private()),
+                ("categories", Argument::Public(categories)),
+                ("null_values", Argument::Public(null_values))], false)
+        },
+        // resize, imputing from bounds, or from categories
+        2 => {
+            let number_rows = Argument::Public(rng.int(1, 10).into());
+            if is_numeric {
+                let (lower, upper) = random_bounds(rng, &data_type);
+                apply(Variant::Resize(proto::Resize {}), vec![
+                    ("data", private()), ("number_rows", number_rows),
+                    ("lower", Argument::Public(lower)), ("upper", Argument::Public(upper))], false)
+            } else {
+                let categories = random_categories(rng, &data_type);
+                apply(Variant::Resize(proto::Resize {}), vec![
+                    ("data", private()), ("number_rows", number_rows),
+                    ("categories", Argument::Public(categories))], false)
+            }
+        },
+        // cast to a random atomic type
+        3 => {
+            let atomic_type = rng.choose(&["float", "int", "bool", "string"]).to_string();
+            let mut arguments = vec![("data", private())];
+            match atomic_type.as_str() {
+                "int" => {
+                    let (lower, upper) = random_bounds(rng, &DataType::Int);
+                    arguments.push(("lower", Argument::Public(lower)));
+                    arguments.push(("upper", Argument::Public(upper)));
+                },
+                "bool" => arguments.push(("true_label", Argument::Public(random_scalar(rng, &data_type)))),
+                _ => ()
+            };
+            // casting to int imputes unparseable members at random
+            let deterministic = atomic_type != "int";
+            apply(Variant::Cast(proto::Cast { atomic_type }), arguments, deterministic)
+        },
+        // binary transform with a public scalar, or with itself
+        4 | 5 => {
+            let variant = if is_numeric {
+                rng.choose(&[
+                    Variant::Add(proto::Add {}), Variant::Subtract(proto::Subtract {}),
+                    Variant::Multiply(proto::Multiply {}), Variant::Divide(proto::Divide {}),
+                    Variant::Modulo(proto::Modulo {}), Variant::RowMax(proto::RowMax {}),
+                    Variant::RowMin(proto::RowMin {}), Variant::GreaterThan(proto::GreaterThan {}),
+                    Variant::LessThan(proto::LessThan {}), Variant::Equal(proto::Equal {})]).clone()
+            } else if data_type == DataType::Bool {
+                rng.choose(&[
+                    Variant::LogicalAnd(proto::And {}), Variant::LogicalOr(proto::Or {}),
+                    Variant::Equal(proto::Equal {})]).clone()
+            } else {
+                Variant::Equal(proto::Equal {})
+            };
+            let right = if step == 4 { Argument::Public(random_scalar(rng, &data_type)) } else { private() };
+            apply(variant, vec![("left", private()), ("right", right)], true)
+        },
+        // power or logarithm with a public scalar
+        6 => if rng.bernoulli(0.5) {
+            let radical = Argument::Public(rng.choose(&[0.5, 2., 3.]).to_owned().into());
+            apply(Variant::Power(proto::Power {}), vec![("data", private()), ("radical", radical)], true)
+        } else {
+            let base = Argument::Public(rng.choose(&[2., std::f64::consts::E, 10.]).to_owned().into());
+            apply(Variant::Log(proto::Log {}), vec![("data", private()), ("base", base)], true)
+        },
+        // unary transform
+        7 => {
+            let variant = if data_type == DataType::Bool {
+                Variant::Negate(proto::Negate {})
+            } else {
+                rng.choose(&[Variant::Abs(proto::Abs {}), Variant::Negative(proto::Negative {})]).clone()
+            };
+            apply(variant, vec![("data", private())], true)
+        },
+        // index a subset of the columns, or a single column
+        8 => {
+            let indices: Value = if rng.bernoulli(0.5) {
+                (rng.below(num_columns) as Integer).into()
+            } else {
+                let indices = (0..num_columns as Integer).filter(|_| rng.bernoulli(0.7)).collect::<Vec<Integer>>();
+                arr1(&if indices.is_empty() { vec![0] } else { indices }).into_dyn().into()
+            };
+            apply(Variant::Index(proto::Index {}), vec![("data", private()), ("indices", Argument::Public(indices))], true)
+        },
+        // filter by a mask derived from the first column
+        _ => {
+            let column = match apply(Variant::Index(proto::Index {}), vec![
+                ("data", private()), ("indices", Argument::Public((0 as Integer).into()))], true)? {
+                Some(column) => column,
+                None => return Ok(None)
+            };
+            let mask = if data_type == DataType::Bool {
+                column
+            } else {
+                let variant = if is_numeric { Variant::GreaterThan(proto::GreaterThan {}) } else { Variant::Equal(proto::Equal {}) };
+                match apply(variant, vec![
+                    ("left", Argument::Private(&column)),
+                    ("right", Argument::Public(random_scalar(rng, &data_type)))], true)? {
+                    Some(mask) => mask,
+                    None => return Ok(None)
+                }
+            };
+            apply(Variant::Filter(proto::Filter {}), vec![("data", private()), ("mask", Argument::Private(&mask))], true)
+        }
+    }
+}
+
+/// A random set of categories, as public jagged data of the given atomic type.
+fn random_categories(rng: &mut FuzzRng, data_type: &DataType) -> Value {
+    let num_categories = rng.int(1, 3) as usize;
+    Value::Jagged(match data_type {
+        DataType::Bool => Jagged::Bool(vec![(0..num_categories).map(|_| rng.bernoulli(0.5)).collect()]),
+        DataType::Int => Jagged::Int(vec![(0..num_categories).map(|_| rng.int(-10, 10)).collect()]),
+        DataType::Float => Jagged::Float(vec![(0..num_categories).map(|_| rng.float(-10., 10.)).collect()]),
+        _ => Jagged::Str(vec![(0..num_categories).map(|_| rng.choose(&CATEGORIES).to_string()).collect()])
+    })
+}
+
+/// Generate a random dataset and a random chain of components from the seed, and check every step.
+///
+/// # Arguments
+/// * `seed` - Seed of the chain.
+/// * `max_steps` - Maximum length of the chain.
+pub fn fuzz_chain(seed: u64, max_steps: usize) -> Result<()> {
+    let mut rng = FuzzRng::new(seed);
+    let (mut node, input_distance) = random_dataset(&mut rng);
+    let mut log = vec![format!("dataset {:?}", node.property)];
+
+    for _ in 0..rng.int(1, max_steps as Integer) {
+        let step = random_step(&mut rng, &node, &mut log)
+            .chain_err(|| format!("seed {}, chain {:?}", seed, log))?;
+        if let Some(step) = step {
+            node = step;
+            verify_stability(&node, input_distance)
+                .chain_err(|| format!("seed {}, chain {:?}", seed, log))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_fuzzing {
+    use crate::utilities::fuzzing::fuzz_chain;
+
+    #[test]
+    fn test_propagation_soundness() {
+        (0..2000).for_each(|seed| fuzz_chain(seed, 8).unwrap())
+    }
+}
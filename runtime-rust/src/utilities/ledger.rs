@@ -0,0 +1,589 @@
+//! A persistent privacy budget ledger.
+//!
+//! The ledger is a json file that records, for each dataset id, the total privacy budget and the budget spent so far:
+//! ```json
+//! {"datasets": {"my_dataset": {
+//!     "total": {"name": "approximate", "epsilon": 1.0, "delta": 1e-6},
+//!     "spent": {"name": "approximate", "epsilon": 0.25, "delta": 0.0}}}}
+//! ```
+//! Releases are debited with basic composition, and a release is refused if it would exceed the total.
+//! Every read-modify-write of the ledger holds an advisory lock on a lock file beside the ledger,
+//! and the ledger is replaced atomically by renaming a temporary file.
+//!
+//! The ledger, and the dataset id of each data source, are configured by the operator of the runtime
+//! with [set_ledger_config](fn.set_ledger_config.html). They are never read from the analysis.
+
+use whitenoise_validator::errors::*;
+
+use whitenoise_validator::proto;
+use whitenoise_validator::base::NodeProperties;
+use whitenoise_validator::components::Mechanism;
+use whitenoise_validator::utilities::json::privacy_usage_to_json;
+use whitenoise_validator::utilities::privacy::{concentrated_to_approximate, pure_to_concentrated};
+
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// How long to wait for another process to release the ledger lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Operator configuration of the privacy budget ledger.
+#[derive(Clone, Debug, Default)]
+pub struct LedgerConfig {
+    /// path to the ledger file
+    pub ledger_path: String,
+    /// identity of the dataset in the ledger, keyed by the path of each registered data source
+    pub datasets: HashMap<String, String>,
+}
+
+/// The ledger configuration, with the paths of data sources resolved
+struct Registry {
+    ledger_path: String,
+    datasets: HashMap<PathBuf, String>,
+}
+
+static REGISTRY: RwLock<Option<Registry>> = RwLock::new(None);
+
+/// Configure the ledger that releases are debited from, for every thread of the process.
+///
+/// Every release that reads from a registered data source debits the budget of its dataset,
+/// and is refused if the ledger has no budget for the dataset.
+/// Data sources are matched by their resolved paths, so a data source cannot be read through another path to avoid the ledger.
+///
+/// # Arguments
+/// * `config` - the ledger and registered data sources, or None to stop debiting releases
+pub fn set_ledger_config(config: Option<LedgerConfig>) -> Result<()> {
+    let registry = config.map(|config| Ok::<_, Error>(Registry {
+        datasets: config.datasets.into_iter()
+            .map(|(source_path, dataset_id)| Ok((resolve_path(&source_path)?, dataset_id)))
+            .collect::<Result<HashMap<PathBuf, String>>>()?,
+        ledger_path: config.ledger_path,
+    })).transpose()?;
+
+    *REGISTRY.write().map_err(|_| Error::from("ledger configuration is poisoned"))? = registry;
+    Ok(())
+}
+
+fn resolve_path(path: &str) -> Result<PathBuf> {
+    fs::canonicalize(path)
+        .map_err(|err| format!("unable to resolve the path of a data source: {:?}", err).into())
+}
+
+/// Exclusive advisory lock on a ledger, held for as long as the struct lives.
+///
+/// The operating system releases the lock when the process exits, so a crash never leaves the ledger locked.
+/// The lock file itself is left in place.
+struct LedgerLock(fs::File);
+
+impl LedgerLock {
+    fn acquire(ledger_path: &Path) -> Result<Self> {
+        let lock_path = with_extension(ledger_path, "lock");
+        let file = fs::OpenOptions::new().write(true).create(true).truncate(false).open(&lock_path)
+            .map_err(|err| Error::from(format!("unable to lock ledger: {:?}", err)))?;
+
+        let start = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(LedgerLock(file)),
+                Err(fs::TryLockError::WouldBlock) => {
+                    if start.elapsed() > LOCK_TIMEOUT {
+                        return Err(format!("ledger is locked: {:?}", lock_path).into())
+                    }
+                    sleep(Duration::from_millis(10))
+                }
+                Err(fs::TryLockError::Error(err)) => return Err(format!("unable to lock ledger: {:?}", err).into())
+            }
+        }
+    }
+}
+
+impl Drop for LedgerLock {
+    fn drop(&mut self) {
+        self.0.unlock().ok();
+    }
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+fn read_ledger(ledger_path: &Path) -> Result<serde_json::Value> {
+    match fs::read_to_string(ledger_path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|err| format!("ledger is not valid json: {:?}", err).into()),
+        Err(err) if err.kind() == ErrorKind::NotFound =>
+            Ok(serde_json::json!({"datasets": {}})),
+        Err(err) => Err(format!("unable to read ledger: {:?}", err).into())
+    }
+}
+
+/// Replace the ledger atomically, so that a crash never leaves a partially written ledger.
+fn write_ledger(ledger_path: &Path, ledger: &serde_json::Value) -> Result<()> {
+    let temp_path = with_extension(ledger_path, "tmp");
+    fs::write(&temp_path, ledger.to_string())
+        .map_err(|err| Error::from(format!("unable to write ledger: {:?}", err)))?;
+    fs::rename(&temp_path, ledger_path)
+        .map_err(|err| format!("unable to write ledger: {:?}", err).into())
+}
+
+fn json_to_privacy_usage(value: &serde_json::Value) -> Result<proto::PrivacyUsage> {
+    let get = |key: &str| value.get(key).and_then(|v| v.as_f64())
+        .ok_or_else(|| Error::from(format!("ledger privacy usage is missing {}", key)));
+
+    Ok(proto::PrivacyUsage {
+        distance: Some(match value.get("name").and_then(|v| v.as_str()) {
+            Some("approximate") => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: get("epsilon")?,
+                delta: get("delta")?,
+            }),
+            Some("concentrated") => proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho: get("rho")?
+            }),
+            _ => return Err("ledger privacy usage must be either approximate or concentrated".into())
+        })
+    })
+}
+
+/// Subtract the spent budget from the total budget.
+///
+/// The spent budget is converted to the distance of the total by the usage it implies:
+/// a concentrated spend against an approximate total is converted with the delta of the total,
+/// and a pure spend against a concentrated total is converted to rho = epsilon^2 / 2.
+/// An approximate spend with a nonzero delta implies no concentrated usage, so it may not be subtracted from a concentrated total.
+fn remaining(total: &proto::PrivacyUsage, spent: &proto::PrivacyUsage) -> Result<proto::PrivacyUsage> {
+    use proto::privacy_usage::{Distance, DistanceApproximate, DistanceConcentrated};
+
+    Ok(proto::PrivacyUsage {
+        distance: Some(match (total.distance.as_ref(), spent.distance.as_ref()) {
+            (Some(Distance::Approximate(total)), Some(Distance::Approximate(spent))) =>
+                Distance::Approximate(DistanceApproximate {
+                    epsilon: total.epsilon - spent.epsilon,
+                    delta: total.delta - spent.delta,
+                }),
+            (Some(Distance::Concentrated(total)), Some(Distance::Concentrated(spent))) =>
+                Distance::Concentrated(DistanceConcentrated {
+                    rho: total.rho - spent.rho
+                }),
+            (Some(Distance::Approximate(approximate)), Some(Distance::Concentrated(_))) =>
+                return remaining(total, &concentrated_to_approximate(spent, approximate.delta)?),
+            (Some(Distance::Concentrated(_)), Some(Distance::Approximate(_))) =>
+                return remaining(total, &pure_to_concentrated(spent)?),
+            _ => return Err("distance must be defined".into())
+        })
+    })
+}
+
+fn is_exhausted(remaining: &proto::PrivacyUsage) -> bool {
+    match remaining.distance.as_ref() {
+        Some(proto::privacy_usage::Distance::Approximate(remaining)) =>
+            remaining.epsilon < 0. || remaining.delta < 0.,
+        Some(proto::privacy_usage::Distance::Concentrated(remaining)) =>
+            remaining.rho < 0.,
+        None => true
+    }
+}
+
+/// Set the total privacy budget of a dataset in a ledger.
+///
+/// The ledger is created if it does not exist. Any budget already spent on the dataset is retained.
+///
+/// # Arguments
+/// * `ledger_path` - path to the ledger file
+/// * `dataset_id` - identity of the dataset in the ledger
+/// * `total` - total privacy budget that may be spent on the dataset
+pub fn set_budget(ledger_path: &str, dataset_id: &str, total: &proto::PrivacyUsage) -> Result<()> {
+    let ledger_path = Path::new(ledger_path);
+    let _lock = LedgerLock::acquire(ledger_path)?;
+    let mut ledger = read_ledger(ledger_path)?;
+
+    let datasets = ledger.get_mut("datasets").and_then(|v| v.as_object_mut())
+        .ok_or_else(|| Error::from("ledger must contain datasets"))?;
+
+    let spent = match datasets.get(dataset_id).and_then(|entry| entry.get("spent")) {
+        Some(spent) => json_to_privacy_usage(spent)?,
+        // a new dataset has not spent any budget
+        None => (total.clone() * 0.)?
+    };
+
+    datasets.insert(dataset_id.to_string(), serde_json::json!({
+        "total": privacy_usage_to_json(total),
+        "spent": privacy_usage_to_json(&spent)
+    }));
+    write_ledger(ledger_path, &ledger)
+}
+
+/// Retrieve the remaining privacy budget of a dataset in a ledger.
+///
+/// # Arguments
+/// * `ledger_path` - path to the ledger file
+/// * `dataset_id` - identity of the dataset in the ledger
+///
+/// # Return
+/// The total privacy budget of the dataset, less the privacy budget spent.
+pub fn get_remaining_budget(ledger_path: &str, dataset_id: &str) -> Result<proto::PrivacyUsage> {
+    let ledger_path = Path::new(ledger_path);
+    let _lock = LedgerLock::acquire(ledger_path)?;
+    let ledger = read_ledger(ledger_path)?;
+
+    let entry = ledger.get("datasets").and_then(|v| v.get(dataset_id))
+        .ok_or_else(|| Error::from(format!("no privacy budget has been set for dataset {}", dataset_id)))?;
+
+    remaining(
+        &json_to_privacy_usage(entry.get("total").ok_or("ledger entry is missing the total budget")?)?,
+        &json_to_privacy_usage(entry.get("spent").ok_or("ledger entry is missing the spent budget")?)?)
+}
+
+/// Debit a privacy usage from the budget of each dataset in a ledger.
+///
+/// Either every dataset is debited, or none are.
+/// The debit is refused if any dataset has no budget set, or if the debit would exceed its total budget.
+///
+/// # Arguments
+/// * `ledger_path` - path to the ledger file
+/// * `dataset_ids` - identities of the datasets in the ledger
+/// * `usage` - privacy usage of the release
+pub fn debit(ledger_path: &str, dataset_ids: &[String], usage: &proto::PrivacyUsage) -> Result<()> {
+    let ledger_path = Path::new(ledger_path);
+    let _lock = LedgerLock::acquire(ledger_path)?;
+    let mut ledger = read_ledger(ledger_path)?;
+    debit_datasets(&mut ledger, dataset_ids, usage)?;
+    write_ledger(ledger_path, &ledger)
+}
+
+/// Debit a privacy usage from the budget of each dataset in a ledger, in memory.
+fn debit_datasets(ledger: &mut serde_json::Value, dataset_ids: &[String], usage: &proto::PrivacyUsage) -> Result<()> {
+    let datasets = ledger.get_mut("datasets").and_then(|v| v.as_object_mut())
+        .ok_or_else(|| Error::from("ledger must contain datasets"))?;
+
+    dataset_ids.iter().try_for_each(|dataset_id| {
+        let entry = datasets.get_mut(dataset_id)
+            .ok_or_else(|| Error::from(format!("no privacy budget has been set for dataset {}", dataset_id)))?;
+
+        let total = json_to_privacy_usage(entry.get("total").ok_or("ledger entry is missing the total budget")?)?;
+        let spent = (json_to_privacy_usage(entry.get("spent").ok_or("ledger entry is missing the spent budget")?)?
+            + usage.clone())?;
+
+        if is_exhausted(&remaining(&total, &spent)?) {
+            return Err(format!("the release would exceed the privacy budget of dataset {}", dataset_id).into())
+        }
+        entry["spent"] = privacy_usage_to_json(&spent);
+        Ok(())
+    })
+}
+
+/// Debit the privacy usage of a mechanism from the budget of every registered dataset it reads from.
+///
+/// Datasets are identified by the paths of upstream Materialize components, as registered in the ledger configuration.
+/// The privacy usage is debited before the mechanism is evaluated, so a refused debit never releases.
+/// When no ledger is configured, nothing is debited.
+///
+/// # Arguments
+/// * `privacy_definition` - the definition of privacy under which the mechanism is evaluated
+/// * `computation_graph` - the analysis graph containing the mechanism
+/// * `component_id` - node id of the mechanism
+/// * `properties` - properties of the arguments to the mechanism
+pub fn debit_mechanism(
+    privacy_definition: &proto::PrivacyDefinition,
+    computation_graph: &HashMap<u32, proto::Component>,
+    component_id: u32,
+    properties: &NodeProperties,
+) -> Result<()> {
+    let registry = REGISTRY.read().map_err(|_| Error::from("ledger configuration is poisoned"))?;
+    let registry = match registry.as_ref() {
+        Some(registry) => registry,
+        None => return Ok(())
+    };
+
+    let component = computation_graph.get(&component_id)
+        .ok_or("attempted to retrieve a non-existent component id")?;
+
+    let usage = match component.get_privacy_usage(privacy_definition, None, properties)? {
+        Some(usages) => match usages.into_iter().map(Ok).fold1(|l, r| l? + r?) {
+            Some(usage) => usage?,
+            None => return Ok(())
+        },
+        None => return Ok(())
+    };
+
+    // collect the registered datasets upstream of the mechanism
+    let mut dataset_ids = BTreeSet::new();
+    let mut traversal = vec![component_id];
+    let mut visited = HashSet::new();
+    while let Some(node_id) = traversal.pop() {
+        if !visited.insert(node_id) {
+            continue
+        }
+        let component = match computation_graph.get(&node_id) {
+            Some(component) => component,
+            None => continue
+        };
+        if let Some(proto::component::Variant::Materialize(materialize)) = &component.variant {
+            if let Some(dataset_id) = registry.datasets.get(&resolve_path(&materialize.file_path)?) {
+                dataset_ids.insert(dataset_id.clone());
+            }
+        }
+        traversal.extend(component.arguments().values());
+    }
+
+    if dataset_ids.is_empty() {
+        return Ok(())
+    }
+    debit(&registry.ledger_path, &dataset_ids.into_iter().collect::<Vec<String>>(), &usage)
+}
+
+#[cfg(test)]
+mod test_ledger {
+    use crate::utilities::ledger::{set_budget, debit, get_remaining_budget, with_extension, set_ledger_config, LedgerConfig};
+    use whitenoise_validator::errors::*;
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::proto;
+    use whitenoise_validator::utilities::privacy::{get_epsilon, get_rho};
+    use ndarray::arr1;
+    use std::path::Path;
+
+    fn usage(epsilon: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon, delta: 0.
+            }))
+        }
+    }
+
+    /// A ledger path unique to each test, as tests run concurrently
+    fn ledger_path(name: &str) -> String {
+        std::env::temp_dir().join(format!("whitenoise_ledger_{}_{}.json", name, std::process::id()))
+            .to_str().unwrap().to_string()
+    }
+
+    fn remove_ledger(ledger_path: &str) {
+        std::fs::remove_file(ledger_path).unwrap();
+        std::fs::remove_file(with_extension(Path::new(ledger_path), "lock")).ok();
+    }
+
+    fn remaining_epsilon(ledger_path: &str, dataset_id: &str) -> f64 {
+        get_epsilon(&get_remaining_budget(ledger_path, dataset_id).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_debit() {
+        let ledger_path = ledger_path("debit");
+        let ledger_path = ledger_path.as_str();
+        let datasets = vec!["a".to_string()];
+
+        assert!(debit(ledger_path, &datasets, &usage(0.1)).is_err());
+
+        set_budget(ledger_path, "a", &usage(1.)).unwrap();
+        debit(ledger_path, &datasets, &usage(0.75)).unwrap();
+        assert!((remaining_epsilon(ledger_path, "a") - 0.25).abs() < 1e-12);
+
+        assert!(debit(ledger_path, &datasets, &usage(0.5)).is_err());
+        assert!((remaining_epsilon(ledger_path, "a") - 0.25).abs() < 1e-12);
+
+        remove_ledger(ledger_path);
+    }
+
+    #[test]
+    fn test_exhaustion() {
+        let ledger_path = ledger_path("exhaustion");
+        let ledger_path = ledger_path.as_str();
+        let datasets = vec!["a".to_string()];
+
+        set_budget(ledger_path, "a", &usage(1.)).unwrap();
+        (0..4).for_each(|_| debit(ledger_path, &datasets, &usage(0.25)).unwrap());
+        assert!(remaining_epsilon(ledger_path, "a").abs() < 1e-12);
+
+        // once the budget is spent, every further debit is refused
+        assert!(debit(ledger_path, &datasets, &usage(1e-6)).is_err());
+        assert!(remaining_epsilon(ledger_path, "a").abs() < 1e-12);
+
+        remove_ledger(ledger_path);
+    }
+
+    #[test]
+    fn test_set_budget() {
+        let ledger_path = ledger_path("set_budget");
+        let ledger_path = ledger_path.as_str();
+        let datasets = vec!["a".to_string()];
+
+        set_budget(ledger_path, "a", &usage(1.)).unwrap();
+        set_budget(ledger_path, "b", &usage(2.)).unwrap();
+        debit(ledger_path, &datasets, &usage(0.5)).unwrap();
+
+        // raising the total retains the budget already spent
+        set_budget(ledger_path, "a", &usage(3.)).unwrap();
+        assert!((remaining_epsilon(ledger_path, "a") - 2.5).abs() < 1e-12);
+        assert!((remaining_epsilon(ledger_path, "b") - 2.).abs() < 1e-12);
+
+        // lowering the total below the budget spent refuses further debits
+        set_budget(ledger_path, "a", &usage(0.25)).unwrap();
+        assert!(debit(ledger_path, &datasets, &usage(1e-6)).is_err());
+
+        remove_ledger(ledger_path);
+    }
+
+    #[test]
+    fn test_concurrent_debits() {
+        let ledger_path = ledger_path("concurrent");
+        set_budget(&ledger_path, "a", &usage(1.)).unwrap();
+
+        // only four of the debits fit within the budget
+        let successes = (0..16)
+            .map(|_| {
+                let ledger_path = ledger_path.clone();
+                std::thread::spawn(move || debit(&ledger_path, &["a".to_string()], &usage(0.25)).is_ok())
+            })
+            .collect::<Vec<_>>().into_iter()
+            .map(|handle| handle.join().unwrap())
+            .filter(|success| *success)
+            .count();
+
+        assert_eq!(successes, 4);
+        assert!(remaining_epsilon(&ledger_path, "a").abs() < 1e-12);
+
+        remove_ledger(&ledger_path);
+    }
+
+    #[test]
+    fn test_stale_lock() {
+        let ledger_path = ledger_path("stale_lock");
+        let ledger_path = ledger_path.as_str();
+        set_budget(ledger_path, "a", &usage(1.)).unwrap();
+
+        // a lock file left behind by a crashed process does not hold the lock
+        std::fs::write(with_extension(Path::new(ledger_path), "lock"), "").unwrap();
+        debit(ledger_path, &["a".to_string()], &usage(0.5)).unwrap();
+
+        remove_ledger(ledger_path);
+    }
+
+    fn concentrated(rho: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho
+            }))
+        }
+    }
+
+    /// Release a Laplace count of the records in a csv
+    fn release_count(file_path: &str, epsilon: f64) -> Result<()> {
+        let mut analysis = Analysis::new();
+        let column_names = analysis.literal()
+            .value(arr1(&["a".to_string()]).into_dyn().into())
+            .value_public(true).build();
+        let data = analysis.materialize(column_names, file_path.to_string()).build();
+
+        let name = analysis.literal().value("a".to_string().into()).value_public(true).build();
+        analysis.component_count += 1;
+        let indexed = analysis.component_count;
+        analysis.components.insert(indexed, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap::indexmap![
+                "data".into() => data, "names".into() => name])),
+            variant: Some(proto::component::Variant::Index(proto::Index {})),
+            omit: false,
+            submission: 0,
+        });
+
+        let count = analysis.count(indexed).build();
+        analysis.laplace_mechanism(count, vec![usage(epsilon)]).build();
+
+        crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::Public).map(|_| ())
+    }
+
+    #[test]
+    fn test_debit_mechanism() {
+        let ledger_path = ledger_path("debit_mechanism");
+        let directory = std::env::temp_dir().join(format!("whitenoise_ledger_data_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let registered = directory.join("registered.csv");
+        let unregistered = directory.join("unregistered.csv");
+        [&registered, &unregistered].iter()
+            .for_each(|path| std::fs::write(path, "a\n1\n2\n").unwrap());
+        let registered = registered.to_str().unwrap();
+
+        set_ledger_config(Some(LedgerConfig {
+            ledger_path: ledger_path.clone(),
+            datasets: vec![(registered.to_string(), "a".to_string())].into_iter().collect(),
+        })).unwrap();
+
+        // a registered dataset without a budget in the ledger is never released
+        assert!(release_count(registered, 0.5).is_err());
+
+        set_budget(&ledger_path, "a", &usage(1.)).unwrap();
+        release_count(registered, 0.5).unwrap();
+        assert!((remaining_epsilon(&ledger_path, "a") - 0.5).abs() < 1e-12);
+
+        // the same data source, read through another path, is debited from the same budget
+        let alias = directory.join(".").join("registered.csv");
+        release_count(alias.to_str().unwrap(), 0.5).unwrap();
+        assert!(remaining_epsilon(&ledger_path, "a").abs() < 1e-12);
+        assert!(release_count(registered, 0.5).is_err());
+
+        // data sources that are not registered are not debited
+        release_count(unregistered.to_str().unwrap(), 0.5).unwrap();
+
+        set_ledger_config(None).unwrap();
+        std::fs::remove_dir_all(directory).unwrap();
+        remove_ledger(&ledger_path);
+    }
+
+    #[test]
+    fn test_mixed_distances() {
+        let ledger_path = ledger_path("mixed_distances");
+        let ledger_path = ledger_path.as_str();
+        let datasets = vec!["a".to_string()];
+
+        // pure usages are debited from a concentrated budget as rho = epsilon^2 / 2
+        set_budget(ledger_path, "a", &concentrated(1.)).unwrap();
+        debit(ledger_path, &datasets, &usage(1.)).unwrap();
+        debit(ledger_path, &datasets, &concentrated(0.25)).unwrap();
+        let remaining = get_rho(&get_remaining_budget(ledger_path, "a").unwrap()).unwrap();
+        assert!((remaining - 0.25).abs() < 1e-12);
+
+        // approximate usages with a nonzero delta imply no concentrated usage
+        let approximate = proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 0.1, delta: 1e-6
+            }))
+        };
+        assert!(debit(ledger_path, &datasets, &approximate).is_err());
+
+        // concentrated usages are debited from an approximate budget with the delta of the budget
+        set_budget(ledger_path, "b", &approximate).unwrap();
+        debit(ledger_path, &["b".to_string()], &concentrated(1e-5)).unwrap();
+        let remaining = get_remaining_budget(ledger_path, "b").unwrap();
+        assert!(get_epsilon(&remaining).unwrap() > 0. && get_epsilon(&remaining).unwrap() < 0.1);
+
+        // a pure spend is retained as rho = epsilon^2 / 2 when the total becomes concentrated
+        set_budget(ledger_path, "c", &usage(2.)).unwrap();
+        debit(ledger_path, &["c".to_string()], &usage(1.)).unwrap();
+        set_budget(ledger_path, "c", &concentrated(1.)).unwrap();
+        let remaining = get_rho(&get_remaining_budget(ledger_path, "c").unwrap()).unwrap();
+        assert!((remaining - 0.5).abs() < 1e-12);
+
+        // but an approximate spend with a nonzero delta has no remaining concentrated budget
+        set_budget(ledger_path, "d", &proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 1e-6
+            }))
+        }).unwrap();
+        debit(ledger_path, &["d".to_string()], &approximate).unwrap();
+        set_budget(ledger_path, "d", &concentrated(1.)).unwrap();
+        assert!(get_remaining_budget(ledger_path, "d").is_err());
+        assert!(debit(ledger_path, &["d".to_string()], &concentrated(1e-3)).is_err());
+
+        remove_ledger(ledger_path);
+    }
+}
@@ -2,7 +2,10 @@ use whitenoise_validator::errors::*;
 
 use crate::utilities::noise;
 use crate::utilities;
-use whitenoise_validator::Float;
+use whitenoise_validator::{Float, Integer};
+use whitenoise_validator::utilities::privacy::{analytic_gaussian_scale, randomized_response_probabilities, cauchy_smooth_sensitivity_parameters};
+use probability::distribution::{Gaussian, Inverse};
+use std::collections::BTreeMap;
 
 /// Returns noise drawn according to the Laplace mechanism
 ///
@@ -32,11 +35,79 @@ pub fn laplace_mechanism(epsilon: f64, sensitivity: f64, enforce_constant_time:
         return Err(format!("epsilon ({}) and sensitivity ({}) must be positive", epsilon, sensitivity).into());
     }
     let scale: f64 = sensitivity / epsilon;
-    let noise: f64 = noise::sample_laplace(0., scale, enforce_constant_time);
+    let noise: f64 = noise::sample_laplace(0., scale, enforce_constant_time)?;
 
     Ok(noise)
 }
 
+/// Returns a release of `value` according to the Snapping mechanism.
+///
+/// The Snapping mechanism is a variant of the Laplace mechanism that is robust to floating-point attacks.
+/// The value is clamped to [-B, B], perturbed with Laplace noise derived from a correctly-rounded
+/// logarithm of a uniform draw, rounded to the nearest multiple of the smallest power of two
+/// at least as large as the noise scale, and clamped to [-B, B] once more.
+/// For more information, see Section 5.2 of
+/// [Mironov (2012)](http://citeseerx.ist.psu.edu/viewdoc/download?doi=10.1.1.366.5957&rep=rep1&type=pdf).
+///
+/// The noise scale is chosen such that the release satisfies epsilon-DP,
+/// inflating the sensitivity by 2^-49 * B to account for the rounding error.
+/// The privacy analysis requires the noise scale to be less than B, and B to be less than 2^46 times the noise scale.
+/// Otherwise an error is returned, rather than releasing with a different bound than the one requested.
+///
+/// # Arguments
+///
+/// * `value` - Statistic to be privatized.
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `sensitivity` - Upper bound on the L1 sensitivity of the function you want to privatize.
+/// * `lower` - Lower bound on the statistic.
+/// * `upper` - Upper bound on the statistic.
+///
+/// # Return
+/// The privatized statistic, a multiple of a power of two within [-B, B], where B = max(|lower|, |upper|).
+///
+/// # Examples
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::snapping_mechanism;
+/// let release = snapping_mechanism(4.0, 1.0, 1.0, 0.0, 10.0).unwrap();
+/// ```
+#[cfg(feature = "use-mpfr")]
+pub fn snapping_mechanism(value: f64, epsilon: f64, sensitivity: f64, lower: f64, upper: f64) -> Result<f64> {
+    if epsilon <= 0. || sensitivity <= 0. {
+        return Err(format!("epsilon ({}) and sensitivity ({}) must both be positive", epsilon, sensitivity).into());
+    }
+    if !lower.is_finite() || !upper.is_finite() || lower > upper {
+        return Err(format!("lower ({}) and upper ({}) must be finite, and lower must not exceed upper", lower, upper).into());
+    }
+
+    let get_scale = |bound: f64| (sensitivity + 2f64.powi(-49) * bound) / epsilon;
+
+    // the privacy analysis requires scale < B < 2^46 * scale
+    let bound = lower.abs().max(upper.abs());
+    let scale = get_scale(bound);
+    if bound <= scale || bound >= 2f64.powi(46) * scale {
+        return Err(format!("the snapping mechanism is not defined for a clamping bound of {} and a noise scale of {}. The bound must exceed the scale, and be less than 2^46 times the scale", bound, scale).into());
+    }
+
+    // smallest power of two at least as large as the scale
+    let granularity = 2f64.powi(scale.log2().ceil() as i32);
+
+    // draw from (0, 1]
+    let mut uniform = noise::sample_uniform_mpfr(0., 1.)?;
+    while uniform.is_zero() {
+        uniform = noise::sample_uniform_mpfr(0., 1.)?;
+    }
+    let log_uniform = uniform.ln().to_f64();
+    let sign = if noise::sample_bit()? { 1. } else { -1. };
+
+    let release = value.max(-bound).min(bound) + sign * scale * log_uniform;
+    Ok(((release / granularity).round() * granularity).max(-bound).min(bound))
+}
+
+#[cfg(not(feature = "use-mpfr"))]
+pub fn snapping_mechanism(_value: f64, _epsilon: f64, _sensitivity: f64, _lower: f64, _upper: f64) -> Result<f64> {
+    Err("the snapping mechanism requires correctly-rounded logarithms from MPFR. Enable the use-mpfr feature".into())
+}
+
 /// Returns noise drawn according to the Gaussian mechanism.
 ///
 /// Let c = sqrt(2*ln(1.25/delta)). Noise is drawn from a Gaussian distribution with scale
@@ -69,7 +140,7 @@ pub fn gaussian_mechanism(epsilon: f64, delta: f64, sensitivity: f64, _enforce_c
         return Err(format!("epsilon ({}), delta ({}) and sensitivity ({}) must all be positive", epsilon, delta, sensitivity).into());
     }
     let scale: f64 = sensitivity * (2. * (1.25 / delta).ln()).sqrt() / epsilon;
-    Ok(noise::sample_gaussian_mpfr(0., scale).to_f64())
+    Ok(noise::sample_gaussian_mpfr(0., scale)?.to_f64())
 }
 
 #[cfg(not(feature = "use-mpfr"))]
@@ -78,10 +149,130 @@ pub fn gaussian_mechanism(epsilon: f64, delta: f64, sensitivity: f64, enforce_co
         return Err(format!("epsilon ({}), delta ({}) and sensitivity ({}) must all be positive", epsilon, delta, sensitivity).into());
     }
     let scale: f64 = sensitivity * (2. * (1.25 / delta).ln()).sqrt() / epsilon;
-    let noise: f64 = noise::sample_gaussian(0., scale, enforce_constant_time);
+    let noise: f64 = noise::sample_gaussian(0., scale, enforce_constant_time)?;
     Ok(noise)
 }
 
+/// Returns noise drawn according to the analytic Gaussian mechanism.
+///
+/// Noise is drawn from a Gaussian distribution centered about 0, with the smallest scale
+/// at which the Gaussian mechanism satisfies (epsilon, delta)-DP. This calibration is valid for all epsilon > 0.
+///
+/// For more information, see Algorithm 1 in
+/// [Balle & Wang (2018)](https://arxiv.org/abs/1805.06530).
+///
+/// # Arguments
+///
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `sensitivity` - Upper bound on the L2 sensitivity of the function you want to privatize.
+///
+/// # Return
+/// A draw from Gaussian distribution with scale defined as above.
+///
+/// # Examples
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::analytic_gaussian_mechanism;
+/// let n = analytic_gaussian_mechanism(2.0, 0.0001, 2.0, false);
+/// ```
+#[cfg(feature = "use-mpfr")]
+pub fn analytic_gaussian_mechanism(epsilon: f64, delta: f64, sensitivity: f64, _enforce_constant_time: bool) -> Result<f64> {
+    if sensitivity <= 0. {
+        return Err(format!("sensitivity ({}) must be positive", sensitivity).into());
+    }
+    let scale: f64 = analytic_gaussian_scale(epsilon, delta, sensitivity)?;
+    Ok(noise::sample_gaussian_mpfr(0., scale)?.to_f64())
+}
+
+#[cfg(not(feature = "use-mpfr"))]
+pub fn analytic_gaussian_mechanism(epsilon: f64, delta: f64, sensitivity: f64, enforce_constant_time: bool) -> Result<f64> {
+    if sensitivity <= 0. {
+        return Err(format!("sensitivity ({}) must be positive", sensitivity).into());
+    }
+    let scale: f64 = analytic_gaussian_scale(epsilon, delta, sensitivity)?;
+    let noise: f64 = noise::sample_gaussian(0., scale, enforce_constant_time)?;
+    Ok(noise)
+}
+
+/// Returns noise drawn according to the Gaussian mechanism, calibrated to zero-concentrated differential privacy.
+///
+/// Noise is drawn from a Gaussian distribution with scale sensitivity/sqrt(2*rho) and centered about 0.
+///
+/// For more information, see Proposition 1.6 in
+/// M. Bun, T. Steinke. Concentrated Differential Privacy: Simplifications, Extensions, and Lower Bounds. 2016.
+///
+/// # Arguments
+///
+/// * `rho` - Concentrated privacy loss parameter.
+/// * `sensitivity` - Upper bound on the L2 sensitivity of the function you want to privatize.
+///
+/// # Return
+/// A draw from Gaussian distribution with scale defined as above.
+///
+/// # Examples
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::gaussian_mechanism_concentrated;
+/// let n = gaussian_mechanism_concentrated(0.1, 2.0, false);
+/// ```
+#[cfg(feature = "use-mpfr")]
+pub fn gaussian_mechanism_concentrated(rho: f64, sensitivity: f64, _enforce_constant_time: bool) -> Result<f64> {
+    if rho <= 0. || sensitivity <= 0. {
+        return Err(format!("rho ({}) and sensitivity ({}) must both be positive", rho, sensitivity).into());
+    }
+    let scale: f64 = sensitivity / (2. * rho).sqrt();
+    Ok(noise::sample_gaussian_mpfr(0., scale)?.to_f64())
+}
+
+#[cfg(not(feature = "use-mpfr"))]
+pub fn gaussian_mechanism_concentrated(rho: f64, sensitivity: f64, enforce_constant_time: bool) -> Result<f64> {
+    if rho <= 0. || sensitivity <= 0. {
+        return Err(format!("rho ({}) and sensitivity ({}) must both be positive", rho, sensitivity).into());
+    }
+    let scale: f64 = sensitivity / (2. * rho).sqrt();
+    let noise: f64 = noise::sample_gaussian(0., scale, enforce_constant_time)?;
+    Ok(noise)
+}
+
+/// Returns noise drawn according to the discrete Gaussian mechanism, calibrated to zero-concentrated differential privacy.
+///
+/// Noise is drawn exactly from a discrete Gaussian distribution with variance parameter
+/// sigma^2 = sensitivity^2 / (2*rho), using only rational arithmetic and uniform integer draws.
+///
+/// For more information, see Theorem 4 in
+/// [Canonne, Kamath & Steinke (2020)](https://arxiv.org/abs/2004.00010).
+///
+/// # Arguments
+///
+/// * `rho` - Concentrated privacy loss parameter.
+/// * `sensitivity` - Upper bound on the L2 sensitivity of the function you want to privatize.
+///
+/// # Return
+/// A draw from the discrete Gaussian distribution with variance parameter defined as above.
+///
+/// # Examples
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::discrete_gaussian_mechanism;
+/// let n = discrete_gaussian_mechanism(0.1, 2.0).unwrap();
+/// ```
+#[cfg(feature = "use-mpfr")]
+pub fn discrete_gaussian_mechanism(rho: f64, sensitivity: f64) -> Result<Integer> {
+    if rho <= 0. || sensitivity <= 0. {
+        return Err(format!("rho ({}) and sensitivity ({}) must both be positive", rho, sensitivity).into());
+    }
+    // the conversion from f64 is exact
+    let to_rational = |v: f64| rug::Rational::from_f64(v)
+        .ok_or_else(|| Error::from(format!("{} is not finite", v)));
+
+    let sigma_squared = to_rational(sensitivity)?.square() / (to_rational(rho)? * 2);
+    noise::sample_discrete_gaussian(&sigma_squared)?.to_i64()
+        .ok_or_else(|| "discrete gaussian noise does not fit in an integer".into())
+}
+
+#[cfg(not(feature = "use-mpfr"))]
+pub fn discrete_gaussian_mechanism(_rho: f64, _sensitivity: f64) -> Result<Integer> {
+    Err("the discrete gaussian mechanism requires exact rational arithmetic from GMP. Enable the use-mpfr feature".into())
+}
+
 /// Returns noise drawn according to the Geometric mechanism.
 ///
 /// Uses the Geometric mechanism as originally proposed in
@@ -114,7 +305,7 @@ pub fn simple_geometric_mechanism(
         return Err(format!("epsilon ({}) and sensitivity ({}) must be positive", epsilon, sensitivity).into());
     }
     let scale: f64 = sensitivity / epsilon;
-    let noise: i64 = noise::sample_simple_geometric_mechanism(scale, min, max, enforce_constant_time);
+    let noise: i64 = noise::sample_simple_geometric_mechanism(scale, min, max, enforce_constant_time)?;
     Ok(noise)
 }
 
@@ -188,4 +379,901 @@ pub fn exponential_mechanism<T>(
 
     // sample element relative to probability
     utilities::sample_from_set(candidate_set, &weight_vec, enforce_constant_time)
-}
\ No newline at end of file
+}
+
+/// Returns data element according to report-noisy-max.
+///
+/// Each utility is perturbed with independent noise of scale 2 * sensitivity / epsilon,
+/// and the candidate with the largest noisy utility is released.
+/// Gumbel noise recovers the Exponential mechanism exactly,
+/// and exponential noise gives the same distribution as permute-and-flip.
+/// For more information, see [McKenna and Sheldon (2020)](https://arxiv.org/abs/2010.12603).
+///
+/// # Arguments
+///
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `sensitivity` - L1 sensitivity of utility function.
+/// * `candidate_set` - Data from which user wants an element returned.
+/// * `utilities` - Score for each candidate.
+/// * `noise` - One of "gumbel", "laplace" or "exponential".
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::report_noisy_max;
+/// let xs: Vec<f64> = vec![1., 2., 3., 4., 5.];
+/// let ans = report_noisy_max(1.0, 1.0, &xs, xs.clone(), "gumbel", false);
+/// # ans.unwrap();
+/// ```
+pub fn report_noisy_max<T>(
+    epsilon: f64,
+    sensitivity: f64,
+    candidate_set: &[T],
+    utilities: Vec<f64>,
+    noise: &str,
+    enforce_constant_time: bool
+) -> Result<T> where T: Clone, {
+    if epsilon <= 0. || sensitivity < 0. {
+        return Err(format!("epsilon ({}) must be positive and sensitivity ({}) must be non-negative", epsilon, sensitivity).into());
+    }
+    if candidate_set.len() != utilities.len() {
+        return Err("candidate_set and utilities must have the same length".into());
+    }
+    let scale = 2. * sensitivity / epsilon;
+
+    let mut best: Option<(usize, f64)> = None;
+    for (idx, utility) in utilities.into_iter().enumerate() {
+        let noisy_utility = utility + match noise.to_lowercase().as_str() {
+            "gumbel" => -(-noise::sample_uniform(0., 1., enforce_constant_time)?.ln()).ln() * scale,
+            "laplace" => noise::sample_laplace(0., scale, enforce_constant_time)?,
+            "exponential" => -(1. - noise::sample_uniform(0., 1., enforce_constant_time)?).ln() * scale,
+            _ => return Err(format!("noise ({}) must be one of gumbel, laplace or exponential", noise).into())
+        };
+        if best.map(|(_, max)| noisy_utility > max).unwrap_or(true) {
+            best = Some((idx, noisy_utility));
+        }
+    }
+
+    best.map(|(idx, _)| candidate_set[idx].clone())
+        .ok_or_else(|| "candidate_set must not be empty".into())
+}
+
+/// Returns data element according to the permute-and-flip mechanism.
+///
+/// Candidates are visited in a uniformly random order, and each is released with probability
+/// exp(epsilon * (utility - max utility) / (2 * sensitivity)).
+/// The mechanism is never worse than the Exponential mechanism in expected utility.
+/// For more information, see [McKenna and Sheldon (2020)](https://arxiv.org/abs/2010.12603).
+///
+/// # Arguments
+///
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `sensitivity` - L1 sensitivity of utility function.
+/// * `candidate_set` - Data from which user wants an element returned.
+/// * `utilities` - Score for each candidate.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::permute_and_flip;
+/// let xs: Vec<f64> = vec![1., 2., 3., 4., 5.];
+/// let ans = permute_and_flip(1.0, 1.0, &xs, xs.clone(), false);
+/// # ans.unwrap();
+/// ```
+pub fn permute_and_flip<T>(
+    epsilon: f64,
+    sensitivity: f64,
+    candidate_set: &[T],
+    utilities: Vec<f64>,
+    enforce_constant_time: bool
+) -> Result<T> where T: Clone, {
+    if epsilon <= 0. || sensitivity < 0. {
+        return Err(format!("epsilon ({}) must be positive and sensitivity ({}) must be non-negative", epsilon, sensitivity).into());
+    }
+    if candidate_set.len() != utilities.len() {
+        return Err("candidate_set and utilities must have the same length".into());
+    }
+    if utilities.is_empty() {
+        return Err("candidate_set must not be empty".into());
+    }
+    if utilities.iter().any(|v| !v.is_finite()) {
+        return Err("utilities must be finite".into());
+    }
+    let max_utility = utilities.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    // Fisher-Yates shuffle of the candidate indices
+    let mut order: Vec<usize> = (0..utilities.len()).collect();
+    for i in (1..order.len()).rev() {
+        let j = noise::sample_uniform_int(0, i as Integer)? as usize;
+        order.swap(i, j);
+    }
+
+    // the candidate with maximal utility is always accepted, so the loop terminates within one pass
+    for idx in order {
+        let probability = if sensitivity == 0. {
+            if utilities[idx] == max_utility { 1. } else { 0. }
+        } else {
+            (epsilon * (utilities[idx] - max_utility) / (2. * sensitivity)).exp().min(1.)
+        };
+        if noise::sample_bit_prob(probability, enforce_constant_time)? {
+            return Ok(candidate_set[idx].clone());
+        }
+    }
+    Err("permute-and-flip failed to select a candidate".into())
+}
+/// Returns the indices of the queries whose answers exceed a threshold, via the sparse vector technique.
+///
+/// The threshold is perturbed once with Laplace noise of scale 2 * sensitivity / epsilon,
+/// and each query answer with Laplace noise of scale 4 * cutoff * sensitivity / epsilon.
+/// Queries are visited in order until `cutoff` of them are reported.
+/// The privacy usage does not depend on the number of queries.
+/// For more information, see Algorithm 1 in [Lyu, Su and Li (2017)](https://arxiv.org/abs/1603.01699).
+///
+/// NOTE: this implementation of Laplace draws is likely non-private due to floating-point attacks
+/// See [Mironov (2012)](http://citeseerx.ist.psu.edu/viewdoc/download?doi=10.1.1.366.5957&rep=rep1&type=pdf)
+/// for more information
+///
+/// # Arguments
+///
+/// * `epsilon` - Multiplicative privacy loss parameter, shared by all queries.
+/// * `sensitivity` - Upper bound on the L1 sensitivity of each query.
+/// * `answers` - Query answers, in the order they are to be compared.
+/// * `threshold` - Public threshold.
+/// * `cutoff` - Maximum number of queries to report.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time, by drawing noise for every query.
+///
+/// # Return
+/// Indices of the queries that were found to be above the threshold.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::above_threshold;
+/// let indices = above_threshold(1., 1., &[0., 100., 0., 100.], 50., 1, false).unwrap();
+/// assert!(indices.len() <= 1);
+/// ```
+pub fn above_threshold(
+    epsilon: f64, sensitivity: f64,
+    answers: &[f64], threshold: f64,
+    cutoff: u32, enforce_constant_time: bool
+) -> Result<Vec<Integer>> {
+    if epsilon <= 0. || sensitivity < 0. {
+        return Err(format!("epsilon ({}) must be positive and sensitivity ({}) must be non-negative", epsilon, sensitivity).into());
+    }
+    if cutoff == 0 {
+        return Err("cutoff must be greater than zero".into());
+    }
+
+    // half of the budget is spent on the threshold, and half is shared by the reported queries
+    let noisy_threshold = threshold + noise::sample_laplace(0., 2. * sensitivity / epsilon, enforce_constant_time)?;
+    let query_scale = 4. * cutoff as f64 * sensitivity / epsilon;
+
+    let mut indices = Vec::new();
+    for (index, answer) in answers.iter().enumerate() {
+        if indices.len() == cutoff as usize && !enforce_constant_time { break }
+        let noisy_answer = answer + noise::sample_laplace(0., query_scale, enforce_constant_time)?;
+        if noisy_answer >= noisy_threshold && indices.len() < cutoff as usize {
+            indices.push(index as Integer);
+        }
+    }
+    Ok(indices)
+}
+
+/// Returns the categories present in the data, along with their noisy counts.
+///
+/// The count of each category present in the data is noised,
+/// and only categories with noisy counts above a threshold are released.
+/// The threshold bounds the probability of releasing a category held by a single record by delta.
+/// Neighboring datasets are assumed to differ by the addition or removal of one record.
+/// For more information, see [Wilson et al. (2020)](https://arxiv.org/abs/1909.01917).
+///
+/// NOTE: this implementation of Laplace and Gaussian draws is likely non-private due to floating-point attacks
+/// See [Mironov (2012)](http://citeseerx.ist.psu.edu/viewdoc/download?doi=10.1.1.366.5957&rep=rep1&type=pdf)
+/// for more information
+///
+/// # Arguments
+///
+/// * `data` - One column of categorical data.
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `mechanism` - Either "laplace" or "gaussian".
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// Released categories, in sorted order, and their noisy counts.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::key_selection;
+/// let data = vec!["a"; 1000].into_iter().chain(vec!["b"]).collect::<Vec<&str>>();
+/// let (keys, counts) = key_selection(data, 1., 1e-6, "laplace", false).unwrap();
+/// assert_eq!(keys, vec!["a"]);
+/// ```
+pub fn key_selection<T: Ord>(
+    data: Vec<T>, epsilon: f64, delta: f64,
+    mechanism: &str, enforce_constant_time: bool
+) -> Result<(Vec<T>, Vec<f64>)> {
+    if epsilon <= 0. || delta <= 0. || delta >= 1. {
+        return Err(format!("epsilon ({}) must be positive and delta ({}) must be within (0, 1)", epsilon, delta).into());
+    }
+
+    // count each category. Categories are sorted, so that the order of the release does not depend on the data
+    let mut counts = BTreeMap::new();
+    data.into_iter().for_each(|key| *counts.entry(key).or_insert(0.) += 1.);
+
+    let (threshold, noise): (f64, Box<dyn Fn() -> Result<f64>>) = match mechanism.to_lowercase().as_str() {
+        "laplace" => {
+            let scale = 1. / epsilon;
+            (1. + scale * (1. / (2. * delta)).ln(),
+             Box::new(move || noise::sample_laplace(0., scale, enforce_constant_time)))
+        },
+        "gaussian" => {
+            // half of delta is spent on the noisy counts, and half on suppressing unique categories
+            let scale = analytic_gaussian_scale(epsilon, delta / 2., 1.)?;
+            (1. + scale * Gaussian::new(0., 1.).inverse(1. - delta / 2.),
+             Box::new(move || noise::sample_gaussian(0., scale, enforce_constant_time)))
+        },
+        _ => return Err(format!("mechanism ({}) must be one of laplace or gaussian", mechanism).into())
+    };
+
+    let noisy_counts = counts.into_iter()
+        .map(|(key, count)| Ok((key, count + noise()?)))
+        .collect::<Result<Vec<(T, f64)>>>()?;
+
+    Ok(noisy_counts.into_iter()
+        .filter(|(_, noisy_count)| *noisy_count > threshold)
+        .unzip())
+}
+
+/// Returns a generalized randomized response report for one record.
+///
+/// The true category is reported with probability e^epsilon / (e^epsilon + k - 1),
+/// otherwise one of the other k - 1 categories is reported uniformly at random.
+/// Values that are not one of the categories are reported as if they were drawn uniformly from the categories.
+/// Binary randomized response is the special case where the categories are [false, true].
+/// For more information, see [Wang et al. (2017)](https://arxiv.org/abs/1705.04421).
+///
+/// # Arguments
+///
+/// * `value` - The value held by the record.
+/// * `categories` - The set of categories a report may take.
+/// * `epsilon` - Multiplicative privacy loss parameter of each report.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// One of the categories.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::randomized_response;
+/// let report = randomized_response(&"b", &["a", "b", "c"], 1., false).unwrap();
+/// assert!(["a", "b", "c"].contains(&report));
+/// ```
+pub fn randomized_response<T: PartialEq + Clone>(
+    value: &T, categories: &[T], epsilon: f64, enforce_constant_time: bool
+) -> Result<T> {
+    let (prob_truth, _) = randomized_response_probabilities("generalized", epsilon, categories.len())?;
+    let num_categories = categories.len() as Integer;
+
+    let index = match categories.iter().position(|category| category == value) {
+        Some(index) => if noise::sample_bit_prob(prob_truth, enforce_constant_time)? { index } else {
+            // uniformly choose among the categories other than the true category
+            let other = noise::sample_uniform_int(0, num_categories - 2)? as usize;
+            if other >= index { other + 1 } else { other }
+        },
+        None => noise::sample_uniform_int(0, num_categories - 1)? as usize
+    };
+    Ok(categories[index].clone())
+}
+
+/// Returns a unary encoded randomized response report for one record.
+///
+/// The record is one-hot encoded over the categories, and each bit is reported truthfully with probability e^(epsilon/2) / (e^(epsilon/2) + 1).
+/// Values that are not one of the categories are encoded as all zeros.
+/// For more information, see [Wang et al. (2017)](https://arxiv.org/abs/1705.04421).
+///
+/// # Arguments
+///
+/// * `value` - The value held by the record.
+/// * `categories` - The set of categories a record may take.
+/// * `epsilon` - Multiplicative privacy loss parameter of each report.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// One noisy bit for each category.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::unary_randomized_response;
+/// let report = unary_randomized_response(&2, &[1, 2, 3], 1., false).unwrap();
+/// assert_eq!(report.len(), 3);
+/// ```
+pub fn unary_randomized_response<T: PartialEq>(
+    value: &T, categories: &[T], epsilon: f64, enforce_constant_time: bool
+) -> Result<Vec<bool>> {
+    let (prob_one, prob_zero) = randomized_response_probabilities("unary", epsilon, categories.len())?;
+
+    categories.iter()
+        .map(|category| noise::sample_bit_prob(
+            if category == value { prob_one } else { prob_zero }, enforce_constant_time))
+        .collect()
+}
+
+/// Returns a release of the quantile of `column` according to the smooth sensitivity framework.
+///
+/// The order statistic nearest the quantile is perturbed with cauchy noise scaled to its smooth sensitivity,
+/// and the release is clamped to the bounds.
+/// Unlike the global sensitivity of a quantile, the smooth sensitivity is small when the data is concentrated about the quantile.
+/// For more information, see Nissim, Raskhodnikova and Smith, "Smooth Sensitivity and Sampling in Private Data Analysis" (2007).
+///
+/// # Arguments
+///
+/// * `column` - Data from which the quantile is released.
+/// * `alpha` - Desired quantile, defined on [0, 1].
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `lower` - Lower bound of the data.
+/// * `upper` - Upper bound of the data.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// Noisy estimate of the quantile.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::smooth_quantile_mechanism;
+/// let median = smooth_quantile_mechanism(vec![1., 2., 3., 4., 5.], 0.5, 1., 0., 10., false).unwrap();
+/// assert!(0. <= median && median <= 10.);
+/// ```
+pub fn smooth_quantile_mechanism(
+    mut column: Vec<f64>, alpha: f64, epsilon: f64, lower: f64, upper: f64, enforce_constant_time: bool
+) -> Result<f64> {
+    if epsilon <= 0. {
+        return Err("epsilon must be positive".into())
+    }
+    if !(0. ..=1.).contains(&alpha) {
+        return Err("alpha must be within [0, 1]".into())
+    }
+    if lower > upper {
+        return Err("lower may not be greater than upper".into())
+    }
+    if column.is_empty() {
+        return Err("column must not be empty".into())
+    }
+
+    column.iter_mut().for_each(|v| *v = v.max(lower).min(upper));
+    column.sort_by(|l, r| l.partial_cmp(r).unwrap_or(std::cmp::Ordering::Equal));
+
+    let rank = (alpha * (column.len() - 1) as f64).round() as usize;
+    let (noise_scale, smoothing) = cauchy_smooth_sensitivity_parameters(epsilon);
+    let sensitivity = smooth_sensitivity_quantile(&column, rank, lower, upper, smoothing);
+
+    let release = column[rank] + noise::sample_cauchy(0., sensitivity / noise_scale, enforce_constant_time)?;
+    Ok(release.max(lower).min(upper))
+}
+
+/// Computes the smooth sensitivity of an order statistic.
+///
+/// The local sensitivity at distance k is the largest gap spanning k + 1 records about the order statistic,
+/// where the sorted data is padded with the lower and upper bounds.
+/// The smooth sensitivity is the largest local sensitivity at distance k, discounted by e^(-k * smoothing).
+///
+/// # Arguments
+///
+/// * `sorted` - Data sorted in ascending order, within the bounds.
+/// * `rank` - Zero-based index of the order statistic.
+/// * `lower` - Lower bound of the data.
+/// * `upper` - Upper bound of the data.
+/// * `smoothing` - Smoothing parameter of the sensitivity.
+///
+/// # Return
+/// The smooth sensitivity of the order statistic.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::mechanisms::smooth_sensitivity_quantile;
+/// // a single record may only move the median to a neighboring record
+/// let sensitivity = smooth_sensitivity_quantile(&[1., 2., 3.], 1, 0., 4., 100.);
+/// assert_eq!(sensitivity, 1.);
+/// ```
+pub fn smooth_sensitivity_quantile(sorted: &[f64], rank: usize, lower: f64, upper: f64, smoothing: f64) -> f64 {
+    let num_records = sorted.len() as i64;
+    // one-based index into the padded data
+    let get = |index: i64| if index < 1 { lower } else if index > num_records { upper } else { sorted[index as usize - 1] };
+    let order = rank as i64 + 1;
+
+    let mut sensitivity: f64 = 0.;
+    for distance in 0..=num_records + 1 {
+        let discount = (-(distance as f64) * smoothing).exp();
+        // the local sensitivity is at most the range of the data, so farther distances cannot increase the smooth sensitivity
+        if discount * (upper - lower) <= sensitivity {
+            break
+        }
+        let local_sensitivity = (0..=distance + 1)
+            .map(|offset| get(order + offset) - get(order + offset - distance - 1))
+            .fold(0., f64::max);
+        sensitivity = sensitivity.max(discount * local_sensitivity);
+    }
+    sensitivity
+}
+
+#[cfg(test)]
+#[cfg(feature = "use-mpfr")]
+mod test_snapping_mechanism {
+    use crate::utilities::mechanisms::snapping_mechanism;
+
+    #[test]
+    fn test_snapping_grid() {
+        // scale is (1 + 2^-49 * 10) / 0.5, so releases snap to multiples of 4
+        (0..100).for_each(|_| {
+            let release = snapping_mechanism(4.0, 0.5, 1.0, 0.0, 10.0).unwrap();
+            assert!((-10. ..=10.).contains(&release));
+            assert!(release % 4. == 0. || release.abs() == 10.);
+        });
+        assert!(snapping_mechanism(4.0, 0.5, 1.0, 10.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_snapping_bound() {
+        // the scale is about 10, so a clamping bound of 10 is too small, and is not silently widened
+        assert!(snapping_mechanism(4.0, 0.1, 1.0, 0.0, 10.0).is_err());
+        assert!(snapping_mechanism(4.0, 0.1, 1.0, 0.0, 20.0).is_ok());
+    }
+
+    #[test]
+    fn test_snapping_release() {
+        use whitenoise_validator::bindings::Analysis;
+        use whitenoise_validator::proto;
+        use ndarray::arr2;
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = true;
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.], [3.], [4.], [5.]]).into_dyn().into())
+            .value_public(true).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(5.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let resized = analysis.resize(clamped)
+            .number_rows(number_rows).lower(lower).upper(upper)
+            .build();
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }];
+        let mean = analysis.dp_mean(resized, privacy_usage)
+            .mechanism("Snapping".to_string()).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        assert!(warnings.is_empty());
+        let mean = release.get(&mean).unwrap().value.ref_array().unwrap().first_float().unwrap();
+        // the mean has sensitivity below 1, so the release snaps to integers
+        assert!((-10. ..=10.).contains(&mean));
+        assert_eq!(mean.fract(), 0.);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "use-mpfr")]
+mod test_discrete_gaussian_mechanism {
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::proto;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_discrete_gaussian_release() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = true;
+        let data = analysis.literal()
+            .value(arr2(&[[1], [2], [3], [4], [5]]).into_dyn().into())
+            .value_public(true).build();
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho: 0.5
+            }))
+        }];
+        let lower = analysis.literal().value(0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let sum = analysis.dp_sum(clamped, privacy_usage)
+            .mechanism("DiscreteGaussian".to_string()).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        assert!(warnings.is_empty());
+        // releases remain integers
+        release.get(&sum).unwrap().value.ref_array().unwrap().first_int().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test_selection_mechanisms {
+    use crate::utilities::mechanisms::{report_noisy_max, permute_and_flip};
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::base::{Value, Jagged};
+    use whitenoise_validator::proto;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_selection_utility() {
+        // utilities are far apart relative to the noise, so the best candidate is always selected
+        let candidates = vec!["a", "b", "c"];
+        let utilities = vec![0., 1000., 0.];
+        for noise in &["gumbel", "laplace", "exponential"] {
+            assert_eq!(report_noisy_max(1., 1., &candidates, utilities.clone(), noise, false).unwrap(), "b");
+        }
+        assert_eq!(permute_and_flip(1., 1., &candidates, utilities.clone(), false).unwrap(), "b");
+
+        assert!(report_noisy_max(1., 1., &candidates, utilities.clone(), "cauchy", false).is_err());
+        assert!(permute_and_flip(1., 1., &candidates, vec![0.], false).is_err());
+        assert!(permute_and_flip::<f64>(1., 1., &[], vec![], false).is_err());
+    }
+
+    fn release_median(mechanism: &str) -> f64 {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.], [3.], [4.], [5.]]).into_dyn().into())
+            .value_public(true).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(5.into()).value_public(true).build();
+        let candidates = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![vec![0., 2.5, 5., 7.5, 10.]])))
+            .value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let resized = analysis.resize(clamped)
+            .number_rows(number_rows).lower(lower).upper(upper)
+            .build();
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }];
+        let median = analysis.dp_median(resized, privacy_usage)
+            .candidates(candidates)
+            .mechanism(mechanism.to_string()).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        assert!(warnings.is_empty());
+        release.get(&median).unwrap().value.ref_array().unwrap().first_float().unwrap()
+    }
+
+    #[test]
+    fn test_selection_release() {
+        for mechanism in &["Exponential", "ReportNoisyMax", "ReportNoisyMaxLaplace", "ReportNoisyMaxExponential", "PermuteAndFlip"] {
+            let median = release_median(mechanism);
+            assert!([0., 2.5, 5., 7.5, 10.].contains(&median));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_above_threshold {
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::proto;
+    use ndarray::{Array2, arr1};
+
+    fn release_above_threshold(cutoff: u32) -> Vec<i64> {
+        let mut analysis = Analysis::new();
+        // columns alternate between sums of 0 and 1000
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 4), |(_, j)| (j % 2) as f64 * 100.).into_dyn().into())
+            .value_public(true).build();
+        let lower = analysis.literal().value(arr1(&[0.; 4]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[100.; 4]).into_dyn().into()).value_public(true).build();
+        let number_rows = analysis.literal().value(10.into()).value_public(true).build();
+        let threshold = analysis.literal().value(500.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let resized = analysis.resize(clamped)
+            .number_rows(number_rows).lower(lower).upper(upper)
+            .build();
+        let sum = analysis.sum(resized).build();
+        let indices = analysis.above_threshold(sum, threshold, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 50., delta: 0.
+            }))
+        }]).cutoff(cutoff).build();
+
+        let (release, _warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        release.get(&indices).unwrap().value.ref_array().unwrap()
+            .ref_int().unwrap().iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_above_threshold_release() {
+        assert_eq!(release_above_threshold(1), vec![1]);
+        assert_eq!(release_above_threshold(2), vec![1, 3]);
+    }
+}
+
+#[cfg(test)]
+mod test_key_selection {
+    use crate::utilities::mechanisms::key_selection;
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::base::{Value, Jagged, IndexKey};
+    use whitenoise_validator::proto;
+    use ndarray::{arr1, Array1};
+
+    #[test]
+    fn test_key_selection_threshold() {
+        // categories held by a single record are suppressed, except with probability delta
+        for mechanism in &["laplace", "gaussian"] {
+            let data = vec![1; 1000].into_iter().chain(vec![2]).chain(vec![3; 1000]).collect();
+            let (keys, counts) = key_selection(data, 1., 1e-6, mechanism, false).unwrap();
+            assert_eq!(keys, vec![1, 3]);
+            assert!(counts.iter().all(|count| (count - 1000.).abs() < 100.));
+        }
+        assert!(key_selection(vec![1], 1., 0., "laplace", false).is_err());
+        assert!(key_selection(vec![1], 1., 1e-6, "cauchy", false).is_err());
+    }
+
+    #[test]
+    fn test_key_selection_histogram() {
+        let mut analysis = Analysis::new();
+        let values = Array1::from(vec!["a"; 100].into_iter().chain(vec!["b"; 50]).chain(vec!["c"])
+            .map(String::from).collect::<Vec<String>>());
+        let data = analysis.literal()
+            .value(values.insert_axis(ndarray::Axis(1)).into_dyn().into())
+            .value_public(true).build();
+        let selection = analysis.dp_key_selection(data, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 1e-6
+            }))
+        }]).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release.clone(),
+            proto::FilterLevel::All).unwrap();
+        assert!(warnings.is_empty());
+
+        let selected = release.get(&selection).unwrap().value.clone();
+        match &selected {
+            Value::Dataframe(dataframe) => match dataframe.get::<IndexKey>(&"categories".into()).unwrap() {
+                Value::Jagged(Jagged::Str(categories)) => assert_eq!(categories, &vec![vec!["a".to_string(), "b".to_string()]]),
+                _ => panic!("categories must be a jagged array of strings")
+            },
+            _ => panic!("key selection must release a dataframe")
+        };
+
+        // the released categories become public, and can be used downstream
+        analysis.release.insert(selection, release.get(&selection).unwrap().clone());
+        let names = analysis.literal().value("categories".to_string().into()).value_public(true).build();
+        let categories = analysis.index(selection).names(names).build();
+        let null_value = analysis.literal().value(arr1(&["null".to_string()]).into_dyn().into()).value_public(true).build();
+        let lower = analysis.literal().value(0.into()).value_public(true).build();
+        let inclusive_left = analysis.literal().value(false.into()).value_public(true).build();
+        let histogram = analysis.dp_histogram(data, lower, inclusive_left, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).categories(categories).null_value(null_value).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        // one count for each released category, and a final count for the null category
+        let counts = release.get(&histogram).unwrap().value.ref_array().unwrap().shape().to_vec();
+        assert_eq!(counts.iter().product::<usize>(), 3);
+    }
+}
+
+#[cfg(test)]
+mod test_randomized_response {
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::base::{Value, Jagged};
+    use whitenoise_validator::proto;
+    use whitenoise_validator::utilities::privacy::randomized_response_probabilities;
+    use ndarray::Array1;
+
+    fn release_histogram(encoding: &str, epsilon: f64, group_size: u32, data: Value, categories: Option<Value>) -> Vec<f64> {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = proto::privacy_definition::Neighboring::Substitute as i32;
+        analysis.privacy_definition.group_size = group_size;
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon, delta: 0.
+            }))
+        }];
+
+        let data = analysis.literal().value(data).value_public(true).build();
+        let categories = categories.map(|categories| analysis.literal().value(categories).value_public(true).build());
+        let mut reports = analysis.randomized_response(data, privacy_usage)
+            .encoding(encoding.to_string());
+        if let Some(categories) = categories {
+            reports = reports.categories(categories);
+        }
+        let reports = reports.build();
+
+        let mut histogram = analysis.randomized_response_histogram(reports)
+            .encoding(encoding.to_string());
+        if let Some(categories) = categories {
+            histogram = histogram.categories(categories);
+        }
+        let histogram = histogram.build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        release.get(&histogram).unwrap().value.clone().array().unwrap().float().unwrap().iter().cloned().collect()
+    }
+
+    fn int_data(counts: &[usize]) -> Value {
+        Array1::from(counts.iter().enumerate()
+            .flat_map(|(category, count)| vec![category as i64; *count])
+            .collect::<Vec<i64>>())
+            .insert_axis(ndarray::Axis(1)).into_dyn().into()
+    }
+
+    #[test]
+    fn test_randomized_response_truthful() {
+        // with a very large epsilon, reports are truthful, and the estimates are the true counts
+        let categories = Value::Jagged(Jagged::Int(vec![vec![0, 1, 2]]));
+        for encoding in &["Generalized", "Unary"] {
+            let estimates = release_histogram(encoding, 80., 1, int_data(&[5, 0, 7]), Some(categories.clone()));
+            assert_close(&estimates, &[5., 0., 7.]);
+        }
+
+        let data: Value = Array1::from(vec![true, true, false]).into_dyn().into();
+        assert_close(&release_histogram("Binary", 80., 1, data, None), &[1., 2.]);
+    }
+
+    fn assert_close(estimates: &[f64], counts: &[f64]) {
+        assert_eq!(estimates.len(), counts.len());
+        assert!(estimates.iter().zip(counts.iter()).all(|(estimate, count)| (estimate - count).abs() < 1e-10),
+            "estimates {:?} are not close to {:?}", estimates, counts);
+    }
+
+    #[test]
+    fn test_randomized_response_unbiased() {
+        let counts = [1000., 600., 200.];
+        let categories = Value::Jagged(Jagged::Int(vec![vec![0, 1, 2]]));
+        for encoding in &["Generalized", "Unary"] {
+            let estimates = release_histogram(encoding, 1., 1, int_data(&[1000, 600, 200]), Some(categories.clone()));
+
+            // Hoeffding bound on the error of each estimate, violated with probability 1e-9
+            let (prob_truth, prob_other) = randomized_response_probabilities(encoding, 1., 3).unwrap();
+            let bound = (1800. * (2e9f64).ln() / 2.).sqrt() / (prob_truth - prob_other);
+            estimates.iter().zip(counts.iter())
+                .for_each(|(estimate, count)| assert!((estimate - count).abs() < bound,
+                    "{}: estimate {} of count {}", encoding, estimate, count));
+        }
+    }
+
+    #[test]
+    fn test_randomized_response_group_size() {
+        // the reports spend half of the usage when protecting groups of two,
+        //     so debiasing with the usage passed to RandomizedResponse would underestimate the first count by ~1000
+        let categories = Value::Jagged(Jagged::Int(vec![vec![0, 1]]));
+        let estimates = release_histogram("Generalized", 4., 2, int_data(&[10000, 0]), Some(categories));
+
+        let (prob_truth, prob_other) = randomized_response_probabilities("Generalized", 2., 2).unwrap();
+        let bound = (10000. * (2e9f64).ln() / 2.).sqrt() / (prob_truth - prob_other);
+        assert!((estimates[0] - 10000.).abs() < bound, "estimate {} of count 10000", estimates[0]);
+    }
+
+    #[test]
+    fn test_randomized_response_post_processing() {
+        // reports are not aggregates, so they may be counted and tallied like any other data
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = proto::privacy_definition::Neighboring::Substitute as i32;
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 80., delta: 0.
+            }))
+        }];
+
+        let data = analysis.literal().value(int_data(&[5, 1, 7])).value_public(true).build();
+        let categories = analysis.literal().value(Value::Jagged(Jagged::Int(vec![vec![0, 1, 2]]))).value_public(true).build();
+        let null_value = analysis.literal().value(0.into()).value_public(true).build();
+        let reports = analysis.randomized_response(data, privacy_usage)
+            .categories(categories).build();
+        let count = analysis.count(reports).build();
+        analysis.component_count += 1;
+        let histogram = analysis.component_count;
+        analysis.components.insert(histogram, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap::indexmap![
+                "data".into() => reports, "categories".into() => categories, "null_value".into() => null_value])),
+            variant: Some(proto::component::Variant::Histogram(proto::Histogram {})),
+            omit: false,
+            submission: 0,
+        });
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::Public).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        assert_eq!(release.get(&count).unwrap().value.clone().array().unwrap().first_int().unwrap(), 13);
+        let tallies = release.get(&histogram).unwrap().value.clone().array().unwrap().int().unwrap().clone();
+        assert_eq!(tallies.into_raw_vec(), vec![5, 1, 7]);
+    }
+}
+
+#[cfg(test)]
+mod test_smooth_quantile_mechanism {
+    use crate::utilities::mechanisms::smooth_sensitivity_quantile;
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::proto;
+    use ndarray::Array;
+
+    #[test]
+    fn test_smooth_sensitivity() {
+        // when the data is concentrated about the median, many records must change to move it
+        let concentrated = vec![5.; 1000];
+        assert!(smooth_sensitivity_quantile(&concentrated, 500, 0., 10., 0.25) < 1e-10);
+
+        // the smooth sensitivity never exceeds the range of the data
+        let spread = (0..1000).map(|i| i as f64 / 100.).collect::<Vec<f64>>();
+        let sensitivity = smooth_sensitivity_quantile(&spread, 500, 0., 10., 0.25);
+        assert!(0. < sensitivity && sensitivity <= 10.);
+
+        // the smooth sensitivity is at least the local sensitivity
+        assert!(sensitivity >= 0.01);
+    }
+
+    #[test]
+    fn test_smooth_median_release() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = proto::privacy_definition::Neighboring::Substitute as i32;
+
+        // the median is surrounded by copies of itself, so the smooth sensitivity is negligible
+        let data = Array::from_shape_fn((1000, 2), |(i, j)| (i % 9 + 10 * j) as f64).into_dyn();
+        let data = analysis.literal().value(data.into()).value_public(true).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(20.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 100., delta: 0.
+            }))
+        }];
+        let median = analysis.dp_median(clamped, privacy_usage)
+            .implementation("smooth-sensitivity".to_string()).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let medians = release.get(&median).unwrap().value.clone().array().unwrap().float().unwrap();
+        assert_eq!(medians.len(), 2);
+        // the order statistic nearest the median is 4 in the first column, and 14 in the second
+        assert_eq!(medians.shape(), &[1, 2]);
+        assert!((medians[[0, 0]] - 4.).abs() < 1.);
+        assert!((medians[[0, 1]] - 14.).abs() < 1.);
+    }
+}
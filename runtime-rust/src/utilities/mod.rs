@@ -1,16 +1,114 @@
+pub mod entropy;
 pub mod mechanisms;
 pub mod noise;
+pub mod ledger;
+pub mod streaming;
+pub mod verification;
+#[cfg(test)]
+pub mod statistical_tests;
+#[cfg(test)]
+pub mod fuzzing;
 
 use whitenoise_validator::errors::*;
 
-use openssl::rand::rand_bytes;
 use ieee754::Ieee754;
 
 use ndarray::{ArrayD, Zip, Axis};
 use std::cmp::Ordering;
 use whitenoise_validator::utilities::array::{slow_select, slow_stack};
 use ndarray::prelude::IxDyn;
+use whitenoise_validator::proto;
+use whitenoise_validator::base::NodeProperties;
+use whitenoise_validator::utilities::get_public_num_records;
+use std::time::{Duration, Instant};
+
+pub use entropy::fill_bytes;
+
+
+/// Whether integer arithmetic saturates instead of overflowing, under the privacy definition.
+///
+/// # Arguments
+/// * `privacy_definition` - Definition of privacy, if any.
+pub fn is_overflow_protected(privacy_definition: &Option<proto::PrivacyDefinition>) -> bool {
+    privacy_definition.as_ref().map(|v| v.protect_overflow).unwrap_or(false)
+}
+
+/// Pad the elapsed time of a node evaluation to a bound derived from the public sizes of its private arguments.
+///
+/// The bound is `elapsed_time_nanos_per_record` for each record of the private arguments,
+/// and at least `elapsed_time_nanos_per_record`.
+/// If the evaluation took longer than the bound, the padding cannot hide the elapsed time, and an error is returned.
+/// The error is a fixed message, as the elapsed time depends on the private data.
+///
+/// Whether the bound is exceeded also depends on the private data, so a timeout leaks one bit that is not
+/// accounted for in the privacy usage. The bound should be set generously enough that timeouts do not occur.
+///
+/// # Arguments
+/// * `privacy_definition` - Definition of privacy, containing the time allotted per record.
+/// * `properties` - Properties of the arguments to the node.
+/// * `start` - Time at which evaluation of the node started.
+pub fn pad_elapsed_time(
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &NodeProperties,
+    start: Instant
+) -> Result<()> {
+    let num_records = properties.values()
+        .filter(|property| !property.is_public())
+        .map(get_public_num_records)
+        .collect::<Result<Vec<i64>>>()?.into_iter()
+        .sum::<i64>().max(1);
+
+    let bound = Duration::from_nanos(privacy_definition.elapsed_time_nanos_per_record
+        .checked_mul(num_records as u64)
+        .ok_or("elapsed time bound is too large")?);
+
+    let elapsed = start.elapsed();
+    std::thread::sleep(bound.checked_sub(elapsed).unwrap_or_default());
+
+    if elapsed > bound {
+        return Err("evaluation exceeded the elapsed time bound. Consider increasing elapsed_time_nanos_per_record".into())
+    }
+    Ok(())
+}
 
+#[cfg(test)]
+mod test_pad_elapsed_time {
+    use std::time::{Duration, Instant};
+    use whitenoise_validator::proto;
+    use whitenoise_validator::base::NodeProperties;
+    use crate::utilities::pad_elapsed_time;
+
+    fn get_privacy_definition(elapsed_time_nanos_per_record: u64) -> proto::PrivacyDefinition {
+        proto::PrivacyDefinition {
+            protect_elapsed_time: true,
+            elapsed_time_nanos_per_record,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        let start = Instant::now();
+        pad_elapsed_time(&get_privacy_definition(20_000_000), &NodeProperties::new(), start).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_exceeded() {
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(pad_elapsed_time(&get_privacy_definition(1), &NodeProperties::new(), start).is_err());
+    }
+
+    #[test]
+    fn test_exceeded_message() {
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(2));
+        let error = pad_elapsed_time(&get_privacy_definition(1), &NodeProperties::new(), start).unwrap_err();
+        // the message may not reveal how long the evaluation took
+        assert!(!error.to_string().chars().any(|c| c.is_ascii_digit()));
+    }
+}
 
 ///  Accepts an ndarray and returns the number of columns.
 ///
@@ -177,18 +275,18 @@ pub fn standardize_columns<T: Default + Clone>(array: ArrayD<T>, column_len: usi
 
 /// Return bytes of binary data as `String`.
 ///
-/// Reads bytes from OpenSSL, converts them into a string,
+/// Reads bytes from the entropy source, converts them into a string,
 /// concatenates them, and returns the combined string.
 ///
 /// # Arguments
-/// * `n_bytes` - The number of random bytes you wish to read from OpenSSL.
+/// * `n_bytes` - The number of random bytes you wish to read from the entropy source.
 ///
 /// # Return
 /// The `String` representation of the bytes.
-pub fn get_bytes(n_bytes: usize) -> String {
-    // read random bytes from OpenSSL
+pub fn get_bytes(n_bytes: usize) -> Result<String> {
+    // read random bytes from the entropy source
     let mut buffer = vec!(0_u8; n_bytes);
-    rand_bytes(&mut buffer).unwrap();
+    fill_bytes(&mut buffer)?;
 
     // create new buffer of binary representations, rather than u8
     let new_buffer = buffer.into_iter()
@@ -196,12 +294,7 @@ pub fn get_bytes(n_bytes: usize) -> String {
         .collect::<Vec<String>>();
 
     // combine binary representations into single string and subset mantissa, and return
-    new_buffer.concat()
-}
-
-// TODO: substitute implementation with different generators
-pub fn fill_bytes(mut buffer: &mut [u8]) {
-    rand_bytes(&mut buffer).ok();
+    Ok(new_buffer.concat())
 }
 
 /// Converts an `f64` to `String` of length 64, yielding the IEEE-754 binary representation of the `f64`.
@@ -13,16 +13,35 @@ use whitenoise_validator::Integer;
 #[cfg(not(feature="use-mpfr"))]
 use probability::prelude::Gaussian;
 
-// Give MPFR ability to draw randomness from OpenSSL
+// Give MPFR ability to draw randomness from the entropy source
 #[cfg(feature="use-mpfr")]
-struct GeneratorOpenSSL;
+#[derive(Default)]
+struct GeneratorEntropy {
+    error: Option<Error>,
+    fallback: u32
+}
 
 #[cfg(feature="use-mpfr")]
-impl ThreadRandGen for GeneratorOpenSSL {
+impl ThreadRandGen for GeneratorEntropy {
     fn gen(&mut self) -> u32 {
         let mut buffer = [0u8; 4];
-        utilities::fill_bytes(&mut buffer);
-        u32::from_ne_bytes(buffer)
+        match utilities::fill_bytes(&mut buffer) {
+            Ok(()) => u32::from_ne_bytes(buffer),
+            // MPFR cannot receive errors. Keep the first error, and return varying bits so that MPFR terminates
+            Err(err) => {
+                self.error.get_or_insert(err);
+                self.fallback = self.fallback.wrapping_add(0x9E37_79B9);
+                self.fallback
+            }
+        }
+    }
+}
+
+#[cfg(feature="use-mpfr")]
+impl GeneratorEntropy {
+    /// Fail closed if any draw was not backed by entropy.
+    fn check(self) -> Result<()> {
+        self.error.map_or(Ok(()), Err)
     }
 }
 
@@ -36,13 +55,13 @@ impl ThreadRandGen for GeneratorOpenSSL {
 /// only inside of the sample_bit_prob function. The major difference is that this function does not 
 /// call sample_bit_prob itself (whereas sample_geometric_censored does), so having this more specialized
 /// version allows us to avoid an infinite dependence loop.
-pub fn censored_specific_geom(enforce_constant_time: bool) -> i16 {
+pub fn censored_specific_geom(enforce_constant_time: bool) -> Result<i16> {
 
     if enforce_constant_time {
         let mut buffer = vec!(0_u8; 128);
-        utilities::fill_bytes(&mut buffer);
+        utilities::fill_bytes(&mut buffer)?;
 
-        cmp::min(buffer.into_iter().enumerate()
+        Ok(cmp::min(buffer.into_iter().enumerate()
             // ignore samples that contain no events
             .filter(|(_, sample)| sample > &0)
             // compute the index of the smallest event in the batch
@@ -50,19 +69,19 @@ pub fn censored_specific_geom(enforce_constant_time: bool) -> i16 {
             // retrieve the smallest index
             .min()
             // return 1022 if no events occurred (slight dp violation w.p. ~2^-52)
-            .unwrap_or(1022) as i16, 1022)
+            .unwrap_or(1022) as i16, 1022))
 
     } else {
         // retrieve up to 128 bytes, each containing 8 trials
         for i in 0..128 {
             let mut buffer = vec!(0_u8; 1);
-            utilities::fill_bytes(&mut buffer);
+            utilities::fill_bytes(&mut buffer)?;
 
             if buffer[0] > 0 {
-                return cmp::min(i * 8 + buffer[0].leading_zeros() as i16, 1022)
+                return Ok(cmp::min(i * 8 + buffer[0].leading_zeros() as i16, 1022))
             }
         }
-        1022
+        Ok(1022)
     }
 }
 
@@ -106,7 +125,7 @@ pub fn sample_bit_prob(prob: f64, enforce_constant_time: bool) -> Result<bool> {
     let (_sign, exponent, mantissa) = prob.decompose_raw();
 
     // repeatedly flip fair coin (up to 1023 times) and identify index (0-based) of first heads
-    let first_heads_index = censored_specific_geom(enforce_constant_time);
+    let first_heads_index = censored_specific_geom(enforce_constant_time)?;
 
     // if prob == 1., return after retrieving censored_specific_geom, to protect constant time
     if exponent == 1023 { return Ok(true) }
@@ -200,10 +219,10 @@ mod test_sample_bit_prob {
     }
 }
 
-pub fn sample_bit() -> bool {
+pub fn sample_bit() -> Result<bool> {
     let mut buffer = [0u8; 1];
-    utilities::fill_bytes(&mut buffer);
-    buffer[0] & 1 == 1
+    utilities::fill_bytes(&mut buffer)?;
+    Ok(buffer[0] & 1 == 1)
 }
 
 
@@ -214,7 +233,7 @@ mod test_sample_bit {
     #[test]
     fn test_sample_bit() {
         (0..100).for_each(|_| {
-            dbg!(sample_bit());
+            dbg!(sample_bit().unwrap());
         });
     }
 }
@@ -258,7 +277,7 @@ pub fn sample_uniform_int(min: Integer, max: Integer) -> Result<Integer> {
     // and rejecting integers that are too large
     let mut buffer = [0u8; mem::size_of::<Integer>()];
     loop {
-        utilities::fill_bytes(&mut buffer[..n_bytes]);
+        utilities::fill_bytes(&mut buffer[..n_bytes])?;
         let uniform_int = i64::from_le_bytes(buffer);
         if uniform_int < n_ints {
             return Ok(uniform_int + min)
@@ -325,7 +344,7 @@ pub fn sample_uniform(min: f64, max: f64, enforce_constant_time: bool) -> Result
     // Generate mantissa
     let mut mantissa_buffer = [0u8; 8];
     // mantissa bit index zero is implicit
-    utilities::fill_bytes(&mut mantissa_buffer[1..]);
+    utilities::fill_bytes(&mut mantissa_buffer[1..])?;
     // limit the buffer to 52 bits
     mantissa_buffer[1] %= 16;
 
@@ -333,7 +352,7 @@ pub fn sample_uniform(min: f64, max: f64, enforce_constant_time: bool) -> Result
     let mantissa_int = u64::from_be_bytes(mantissa_buffer);
 
     // Generate exponent. A saturated mantissa with implicit bit is ~2
-    let exponent: i16 = -(1 + censored_specific_geom(enforce_constant_time));
+    let exponent: i16 = -(1 + censored_specific_geom(enforce_constant_time)?);
 
     // Generate uniform random number from [0,1)
     let uniform_rand = f64::recompose(false, exponent, mantissa_int);
@@ -413,12 +432,14 @@ pub fn sample_uniform_mpfr(min: f64, max: f64) -> Result<rug::Float> {
     let mpfr_diff = Float::with_val(53, &mpfr_max - &mpfr_min);
 
     // initialize randomness
-    let mut rng = GeneratorOpenSSL {};
+    let mut rng = GeneratorEntropy::default();
     let mut state = ThreadRandState::new_custom(&mut rng);
 
     // generate Unif[0,1] according to mpfr standard, then convert to correct scale
     let mut unif = Float::with_val(53, Float::random_cont(&mut state));
     unif = unif.mul_add(&mpfr_diff, &mpfr_min);
+    drop(state);
+    rng.check()?;
 
     // return uniform
     Ok(unif)
@@ -441,22 +462,236 @@ pub fn sample_uniform_mpfr(min: f64, max: f64) -> Result<rug::Float> {
 /// ```
 /// use whitenoise_runtime::utilities::noise::sample_gaussian_mpfr;
 /// let gaussian = sample_gaussian_mpfr(0.0, 1.0);
+/// # gaussian.unwrap();
 /// ```
 #[cfg(feature = "use-mpfr")]
-pub fn sample_gaussian_mpfr(shift: f64, scale: f64) -> rug::Float {
+pub fn sample_gaussian_mpfr(shift: f64, scale: f64) -> Result<rug::Float> {
     // initialize 64-bit floats within mpfr/rug
-    // NOTE: We square the scale here because we ask for the standard deviation as the function input, but
-    //       the mpfr library wants the variance. We ask for std. dev. to be consistent with the rest of the library.
+    // NOTE: mpfr draws from the standard normal, so the draw is scaled by the standard deviation, not the variance
     let mpfr_shift = Float::with_val(53, shift);
-    let mpfr_scale = Float::with_val(53, Float::with_val(53, scale).square());
+    let mpfr_scale = Float::with_val(53, scale);
 
     // initialize randomness
-    let mut rng = GeneratorOpenSSL {};
+    let mut rng = GeneratorEntropy::default();
     let mut state = ThreadRandState::new_custom(&mut rng);
 
     // generate Gaussian(0,1) according to mpfr standard, then convert to correct scale
     let gauss = Float::with_val(64, Float::random_normal(&mut state));
-    gauss.mul_add(&mpfr_scale, &mpfr_shift)
+    drop(state);
+    rng.check()?;
+    Ok(gauss.mul_add(&mpfr_scale, &mpfr_shift))
+}
+
+#[cfg(test)]
+#[cfg(feature = "use-mpfr")]
+mod test_sample_gaussian_mpfr {
+    use crate::utilities::noise::sample_gaussian_mpfr;
+
+    #[test]
+    fn test_moments() {
+        let n = 20_000;
+        let samples: Vec<f64> = (0..n).map(|_| sample_gaussian_mpfr(1., 2.).unwrap().to_f64()).collect();
+
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+
+        // the scale is the standard deviation, so the variance is 4
+        assert!((mean - 1.).abs() < 0.1);
+        assert!((variance - 4.).abs() < 0.4);
+    }
+}
+
+/// Sample an integer uniformly from {0, 1, ..., upper - 1}.
+///
+/// # Arguments
+/// * `upper` - Exclusive upper bound of the support. Must be positive.
+///
+/// # Return
+/// Draw from Unif{0, ..., upper - 1}.
+#[cfg(feature = "use-mpfr")]
+pub fn sample_uniform_integer_below(upper: &rug::Integer) -> Result<rug::Integer> {
+    if *upper <= 0 {
+        return Err("upper must be positive".into())
+    }
+
+    // initialize randomness
+    let mut rng = GeneratorEntropy::default();
+    let mut state = ThreadRandState::new_custom(&mut rng);
+
+    let sample = rug::Integer::from(upper.random_below_ref(&mut state));
+    drop(state);
+    rng.check()?;
+    Ok(sample)
+}
+
+/// Sample a bit with probability exactly equal to a rational number.
+///
+/// # Arguments
+/// * `prob` - Probability of returning true. Must be within [0, 1].
+///
+/// # Return
+/// A draw from Bernoulli(prob).
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::noise::sample_bernoulli_rational;
+/// use rug::Rational;
+/// let bit = sample_bernoulli_rational(&Rational::from((1, 3))).unwrap();
+/// ```
+#[cfg(feature = "use-mpfr")]
+pub fn sample_bernoulli_rational(prob: &rug::Rational) -> Result<bool> {
+    if *prob < 0 || *prob > 1 {
+        return Err("probability is not within [0, 1]".into())
+    }
+    Ok(sample_uniform_integer_below(prob.denom())? < *prob.numer())
+}
+
+/// Sample a bit with probability exactly exp(-gamma), for a nonnegative rational gamma.
+///
+/// Implements Algorithm 1 in
+/// [Canonne, Kamath & Steinke (2020)](https://arxiv.org/abs/2004.00010),
+/// which only requires sampling bits with rational probabilities.
+///
+/// # Arguments
+/// * `gamma` - Nonnegative rational exponent.
+///
+/// # Return
+/// A draw from Bernoulli(exp(-gamma)).
+#[cfg(feature = "use-mpfr")]
+pub fn sample_bernoulli_exp(gamma: &rug::Rational) -> Result<bool> {
+    if *gamma < 0 {
+        return Err("gamma must be nonnegative".into())
+    }
+
+    if *gamma > 1 {
+        // exp(-gamma) = exp(-1)^floor(gamma) * exp(-(gamma - floor(gamma)))
+        let (fract, floor) = gamma.clone().fract_floor(rug::Integer::new());
+        let mut remaining = floor;
+        while remaining > 0 {
+            if !sample_bernoulli_exp(&rug::Rational::from(1))? {
+                return Ok(false)
+            }
+            remaining -= 1;
+        }
+        return sample_bernoulli_exp(&fract)
+    }
+
+    let mut k = rug::Integer::from(1);
+    while sample_bernoulli_rational(&(gamma.clone() / &k))? {
+        k += 1;
+    }
+    Ok(k.is_odd())
+}
+
+/// Sample from the discrete Laplace distribution with a positive integer scale.
+///
+/// The probability of returning x is proportional to exp(-|x| / scale).
+/// Implements Algorithm 2 in
+/// [Canonne, Kamath & Steinke (2020)](https://arxiv.org/abs/2004.00010).
+///
+/// # Arguments
+/// * `scale` - Positive integer scale of the distribution.
+///
+/// # Return
+/// A draw from the discrete Laplace distribution.
+#[cfg(feature = "use-mpfr")]
+pub fn sample_discrete_laplace(scale: &rug::Integer) -> Result<rug::Integer> {
+    if *scale <= 0 {
+        return Err("scale must be positive".into())
+    }
+    loop {
+        let uniform = sample_uniform_integer_below(scale)?;
+        if !sample_bernoulli_exp(&rug::Rational::from((uniform.clone(), scale.clone())))? {
+            continue
+        }
+
+        let mut geometric = rug::Integer::new();
+        while sample_bernoulli_exp(&rug::Rational::from(1))? {
+            geometric += 1;
+        }
+
+        let magnitude = uniform + geometric * scale;
+        let negative = sample_bit()?;
+        // reject negative zero, so that zero is not double-counted
+        if negative && magnitude == 0 {
+            continue
+        }
+        return Ok(if negative { -magnitude } else { magnitude })
+    }
+}
+
+/// Sample from the discrete Gaussian distribution centered at zero.
+///
+/// The probability of returning x is proportional to exp(-x^2 / (2 sigma^2)).
+/// Implements Algorithm 3 in
+/// [Canonne, Kamath & Steinke (2020)](https://arxiv.org/abs/2004.00010),
+/// rejection sampling from the discrete Laplace distribution.
+/// All arithmetic is exact over the rationals.
+///
+/// # Arguments
+/// * `sigma_squared` - Positive rational variance parameter.
+///
+/// # Return
+/// A draw from the discrete Gaussian distribution.
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::noise::sample_discrete_gaussian;
+/// use rug::Rational;
+/// let n = sample_discrete_gaussian(&Rational::from((9, 2))).unwrap();
+/// ```
+#[cfg(feature = "use-mpfr")]
+pub fn sample_discrete_gaussian(sigma_squared: &rug::Rational) -> Result<rug::Integer> {
+    if *sigma_squared <= 0 {
+        return Err("sigma_squared must be positive".into())
+    }
+
+    // scale of the discrete Laplace proposal is floor(sigma) + 1
+    let scale = sigma_squared.clone().floor().into_numer_denom().0.sqrt() + 1;
+
+    loop {
+        let candidate = sample_discrete_laplace(&scale)?;
+
+        // gamma = (|candidate| - sigma^2 / scale)^2 / (2 sigma^2)
+        let gamma = (rug::Rational::from(candidate.clone().abs()) - sigma_squared.clone() / &scale).square()
+            / (sigma_squared.clone() * 2);
+        if sample_bernoulli_exp(&gamma)? {
+            return Ok(candidate)
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "use-mpfr")]
+mod test_discrete_gaussian {
+    use crate::utilities::noise::{sample_bernoulli_exp, sample_discrete_gaussian};
+    use rug::Rational;
+
+    #[test]
+    fn test_bernoulli_exp() {
+        let trials = 10_000;
+        let gamma = Rational::from((3, 2));
+        let successes = (0..trials)
+            .filter(|_| sample_bernoulli_exp(&gamma).unwrap())
+            .count();
+        // exp(-1.5) is about 0.223
+        assert!((successes as f64 / trials as f64 - (-1.5f64).exp()).abs() < 0.03);
+        assert!(sample_bernoulli_exp(&Rational::from(-1)).is_err());
+    }
+
+    #[test]
+    fn test_discrete_gaussian_moments() {
+        let trials = 10_000;
+        let sigma_squared = Rational::from(4);
+        let samples = (0..trials)
+            .map(|_| sample_discrete_gaussian(&sigma_squared).unwrap().to_i64().unwrap() as f64)
+            .collect::<Vec<f64>>();
+        let mean = samples.iter().sum::<f64>() / trials as f64;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / trials as f64;
+        assert!(mean.abs() < 0.2);
+        // the variance of the discrete gaussian is slightly less than sigma^2
+        assert!((variance - 4.).abs() < 0.5);
+    }
 }
 
 /// Sample from Laplace distribution centered at shift and scaled by scale.
@@ -473,11 +708,47 @@ pub fn sample_gaussian_mpfr(shift: f64, scale: f64) -> rug::Float {
 /// ```
 /// use whitenoise_runtime::utilities::noise::sample_laplace;
 /// let n = sample_laplace(0.0, 2.0, false);
+/// # n.unwrap();
+/// ```
+pub fn sample_laplace(shift: f64, scale: f64, enforce_constant_time: bool) -> Result<f64> {
+    let probability: f64 = sample_uniform(0., 1., enforce_constant_time)?;
+    Ok(Laplace::new(shift, scale).inverse(probability))
+}
+
+/// Sample from Cauchy distribution centered at shift and scaled by scale.
+///
+/// # Arguments
+///
+/// * `shift` - The median of the Cauchy distribution.
+/// * `scale` - The scaling parameter (half the interquartile range) of the Cauchy distribution.
+///
+/// # Return
+/// Draw from Cauchy(shift, scale).
+///
+/// # Example
+/// ```
+/// use whitenoise_runtime::utilities::noise::sample_cauchy;
+/// let n = sample_cauchy(0.0, 2.0, false);
+/// # n.unwrap();
 /// ```
-pub fn sample_laplace(shift: f64, scale: f64, enforce_constant_time: bool) -> f64 {
-    // nothing in sample_uniform can throw an error
-    let probability: f64 = sample_uniform(0., 1., enforce_constant_time).unwrap();
-    Laplace::new(shift, scale).inverse(probability)
+pub fn sample_cauchy(shift: f64, scale: f64, enforce_constant_time: bool) -> Result<f64> {
+    let probability: f64 = sample_uniform(0., 1., enforce_constant_time)?;
+    Ok(shift + scale * (consts::PI * (probability - 0.5)).tan())
+}
+
+#[cfg(test)]
+mod test_sample_cauchy {
+    use crate::utilities::noise::sample_cauchy;
+
+    #[test]
+    fn test_quartiles() {
+        let mut samples = (0..10_000).map(|_| sample_cauchy(1., 2., false).unwrap()).collect::<Vec<f64>>();
+        samples.sort_by(|l, r| l.partial_cmp(r).unwrap());
+        // the quartiles of Cauchy(1, 2) are -1, 1 and 3
+        assert!((samples[2_500] + 1.).abs() < 0.25);
+        assert!((samples[5_000] - 1.).abs() < 0.25);
+        assert!((samples[7_500] - 3.).abs() < 0.25);
+    }
 }
 
 /// Sample from Gaussian distribution centered at shift and scaled by scale.
@@ -494,16 +765,17 @@ pub fn sample_laplace(shift: f64, scale: f64, enforce_constant_time: bool) -> f6
 /// ```
 /// use whitenoise_runtime::utilities::noise::sample_gaussian;
 /// let n = sample_gaussian(0.0, 2.0, false);
+/// # n.unwrap();
 /// ```
 #[cfg(not(feature = "use-mpfr"))]
-pub fn sample_gaussian(shift: f64, scale: f64, enforce_constant_time: bool) -> f64 {
-    let probability: f64 = sample_uniform(0., 1., enforce_constant_time).unwrap();
-    Gaussian::new(shift, scale).inverse(probability)
+pub fn sample_gaussian(shift: f64, scale: f64, enforce_constant_time: bool) -> Result<f64> {
+    let probability: f64 = sample_uniform(0., 1., enforce_constant_time)?;
+    Ok(Gaussian::new(shift, scale).inverse(probability))
 }
 
 #[cfg(feature = "use-mpfr")]
-pub fn sample_gaussian(shift: f64, scale: f64, _enforce_constant_time: bool) -> f64 {
-    sample_gaussian_mpfr(shift, scale).to_f64()
+pub fn sample_gaussian(shift: f64, scale: f64, _enforce_constant_time: bool) -> Result<f64> {
+    Ok(sample_gaussian_mpfr(shift, scale)?.to_f64())
 }
 
 /// Sample from truncated Gaussian distribution.
@@ -537,7 +809,7 @@ pub fn sample_gaussian_truncated(
 
     // return draw from distribution only if it is in correct range
     loop {
-        let trunc_gauss = sample_gaussian(shift, scale, enforce_constant_time);
+        let trunc_gauss = sample_gaussian(shift, scale, enforce_constant_time)?;
         if trunc_gauss >= min && trunc_gauss <= max {
             return Ok(trunc_gauss)
         }
@@ -618,21 +890,22 @@ pub fn sample_geometric_censored(prob: f64, max_trials: i64, enforce_constant_ti
 /// use ndarray::prelude::*;
 /// use whitenoise_runtime::utilities::noise::sample_simple_geometric_mechanism;
 /// let geom_noise = sample_simple_geometric_mechanism(1., 0, 100, false);
+/// # geom_noise.unwrap();
 /// ```
-pub fn sample_simple_geometric_mechanism(scale: f64, min: i64, max: i64, enforce_constant_time: bool) -> i64 {
+pub fn sample_simple_geometric_mechanism(scale: f64, min: i64, max: i64, enforce_constant_time: bool) -> Result<i64> {
 
     let alpha: f64 = consts::E.powf(-1. / scale);
     let max_trials: i64 = max - min;
 
     // return 0 noise with probability (1-alpha) / (1+alpha), otherwise sample from geometric
-    let unif: f64 = sample_uniform(0., 1., enforce_constant_time).unwrap();
+    let unif: f64 = sample_uniform(0., 1., enforce_constant_time)?;
     if unif < (1. - alpha) / (1. + alpha) {
-        0
+        Ok(0)
     } else {
         // get random sign
-        let sign: i64 = 2 * sample_bit() as i64 - 1;
-        // sample from censored geometric. Unwrap is safe because (1. - alpha) is bounded by 1.
-        let geom: i64 = sample_geometric_censored(1. - alpha, max_trials, enforce_constant_time).unwrap();
-        sign * geom
+        let sign: i64 = 2 * sample_bit()? as i64 - 1;
+        // sample from censored geometric
+        let geom: i64 = sample_geometric_censored(1. - alpha, max_trials, enforce_constant_time)?;
+        Ok(sign * geom)
     }
 }
\ No newline at end of file
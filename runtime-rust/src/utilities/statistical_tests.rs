@@ -0,0 +1,566 @@
+//! Statistical tests of the noise samplers and mechanisms.
+//!
+//! The goodness-of-fit tests check that samplers draw from their intended distributions,
+//! and the empirical privacy loss checks that mechanisms do not distinguish neighboring inputs
+//! by more than their privacy parameter.
+//! Both are run at a small significance level, so that a failure indicates a bug rather than chance.
+
+use probability::distribution::{Distribution, Gamma};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+
+/// Significance level of the goodness-of-fit tests.
+pub const SIGNIFICANCE: f64 = 1e-6;
+
+/// Kolmogorov–Smirnov statistic, the largest distance between the empirical and the intended CDF.
+///
+/// # Arguments
+/// * `samples` - Draws from the sampler under test.
+/// * `cdf` - Intended cumulative distribution function.
+///
+/// # Return
+/// The supremum of |F_n(x) - F(x)| over x.
+pub fn kolmogorov_smirnov_statistic(mut samples: Vec<f64>, cdf: impl Fn(f64) -> f64) -> f64 {
+    samples.sort_by(|l, r| l.partial_cmp(r).unwrap());
+    let num_samples = samples.len() as f64;
+
+    // the empirical CDF jumps at each sample, so the distance is checked on either side of the jump
+    samples.iter().enumerate()
+        .map(|(i, sample)| {
+            let expected = cdf(*sample);
+            (expected - i as f64 / num_samples).max((i + 1) as f64 / num_samples - expected)
+        })
+        .fold(0., f64::max)
+}
+
+/// Asymptotic critical value of the Kolmogorov–Smirnov statistic.
+///
+/// # Arguments
+/// * `num_samples` - Number of draws.
+/// * `alpha` - Significance level.
+///
+/// # Return
+/// The statistic is larger than the critical value with probability at most alpha, under the null hypothesis.
+pub fn kolmogorov_smirnov_critical_value(num_samples: usize, alpha: f64) -> f64 {
+    (-(alpha / 2.).ln() / 2.).sqrt() / (num_samples as f64).sqrt()
+}
+
+/// Panic if the samples are not plausibly drawn from the distribution with the given CDF.
+///
+/// Only applies to continuous distributions.
+pub fn assert_kolmogorov_smirnov(samples: Vec<f64>, cdf: impl Fn(f64) -> f64) {
+    let critical_value = kolmogorov_smirnov_critical_value(samples.len(), SIGNIFICANCE);
+    let statistic = kolmogorov_smirnov_statistic(samples, cdf);
+    assert!(statistic < critical_value,
+            "Kolmogorov-Smirnov statistic {} exceeds critical value {}", statistic, critical_value);
+}
+
+/// Pearson's chi-square statistic.
+///
+/// # Arguments
+/// * `observed` - Number of draws in each category.
+/// * `probabilities` - Intended probability of each category. Must sum to one.
+///
+/// # Return
+/// The sum over categories of (observed - expected)^2 / expected.
+pub fn chi_square_statistic(observed: &[usize], probabilities: &[f64]) -> f64 {
+    let num_samples = observed.iter().sum::<usize>() as f64;
+    observed.iter().zip(probabilities.iter())
+        .map(|(observed, probability)| {
+            let expected = num_samples * probability;
+            (*observed as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Probability that a chi-square random variable exceeds the statistic.
+///
+/// # Arguments
+/// * `statistic` - Chi-square statistic.
+/// * `degrees_of_freedom` - Number of categories, less one.
+pub fn chi_square_p_value(statistic: f64, degrees_of_freedom: usize) -> f64 {
+    // the chi-square distribution with k degrees of freedom is Gamma(k / 2, 2)
+    1. - Gamma::new(degrees_of_freedom as f64 / 2., 2.).distribution(statistic)
+}
+
+/// Panic if the categorical samples are not plausibly drawn from the given probabilities.
+///
+/// Each category should be expected to contain at least five draws.
+pub fn assert_chi_square(observed: &[usize], probabilities: &[f64]) {
+    assert_eq!(observed.len(), probabilities.len());
+    let statistic = chi_square_statistic(observed, probabilities);
+    let p_value = chi_square_p_value(statistic, observed.len() - 1);
+    assert!(p_value > SIGNIFICANCE,
+            "chi-square statistic {} has p-value {}. observed: {:?}, probabilities: {:?}",
+            statistic, p_value, observed, probabilities);
+}
+
+/// Number of draws that fall into each of the given categories.
+///
+/// Draws outside the categories are counted in an additional, final category.
+pub fn count_categories<T: PartialEq>(samples: &[T], categories: &[T]) -> Vec<usize> {
+    let mut counts = vec![0; categories.len() + 1];
+    samples.iter().for_each(|sample| match categories.iter().position(|category| category == sample) {
+        Some(index) => counts[index] += 1,
+        None => counts[categories.len()] += 1
+    });
+    counts
+}
+
+/// Estimate the privacy loss of a mechanism from its outputs on neighboring inputs.
+///
+/// Outputs are compared by equality, so continuous outputs should be discretized into bins first.
+/// Only outputs drawn at least `min_count` times on either input contribute,
+/// because the ratio of rare outputs cannot be estimated.
+///
+/// # Arguments
+/// * `left` - Outputs of the mechanism on one input.
+/// * `right` - Outputs of the mechanism on a neighboring input.
+/// * `min_count` - Minimum number of draws of an output for it to be considered.
+///
+/// # Return
+/// The largest absolute log ratio of the probabilities of an output. Infinite if a common output is never drawn on the other input.
+pub fn empirical_privacy_loss<T: Hash + Eq>(left: Vec<T>, right: Vec<T>, min_count: usize) -> f64 {
+    let (num_left, num_right) = (left.len() as f64, right.len() as f64);
+
+    let mut counts: HashMap<T, (usize, usize)> = HashMap::new();
+    left.into_iter().for_each(|output| counts.entry(output).or_insert((0, 0)).0 += 1);
+    right.into_iter().for_each(|output| counts.entry(output).or_insert((0, 0)).1 += 1);
+
+    counts.values()
+        .filter(|(count_left, count_right)| *count_left.max(count_right) >= min_count)
+        .map(|(count_left, count_right)| if *count_left.min(count_right) == 0 {
+            f64::INFINITY
+        } else {
+            ((*count_left as f64 / num_left) / (*count_right as f64 / num_right)).ln().abs()
+        })
+        .fold(0., f64::max)
+}
+
+/// Panic if the empirical privacy loss exceeds epsilon by more than the sampling error.
+///
+/// `sample` is called with `false` for the left input, and `true` for the neighboring input.
+pub fn assert_privacy_loss<T: Hash + Eq>(
+    epsilon: f64, num_samples: usize, mut sample: impl FnMut(bool) -> T
+) {
+    let min_count = num_samples / 20;
+    let left = (0..num_samples).map(|_| sample(false)).collect();
+    let right = (0..num_samples).map(|_| sample(true)).collect();
+    let loss = empirical_privacy_loss(left, right, min_count);
+
+    // the standard error of the log ratio of two counts of at least min_count / e^epsilon
+    let tolerance = 5. * (2. * epsilon.exp() / min_count as f64).sqrt();
+    assert!(loss <= epsilon + tolerance,
+            "empirical privacy loss {} exceeds epsilon {} by more than {}", loss, epsilon, tolerance);
+}
+
+/// Discretize continuous outputs for the empirical privacy loss.
+pub fn bin(value: f64, width: f64) -> i64 {
+    (value / width).floor() as i64
+}
+
+
+#[cfg(test)]
+mod test_harness {
+    use crate::utilities::statistical_tests::*;
+
+    #[test]
+    fn test_rejects_wrong_distribution() {
+        let samples = (0..10_000).map(|i| i as f64 / 10_000.).collect::<Vec<f64>>();
+        let uniform_cdf = |x: f64| x.clamp(0., 1.);
+        assert!(kolmogorov_smirnov_statistic(samples.clone(), uniform_cdf)
+            < kolmogorov_smirnov_critical_value(samples.len(), SIGNIFICANCE));
+        assert!(kolmogorov_smirnov_statistic(samples.clone(), |x| uniform_cdf(x).powi(2))
+            > kolmogorov_smirnov_critical_value(samples.len(), SIGNIFICANCE));
+
+        assert!(chi_square_p_value(chi_square_statistic(&[500, 500], &[0.5, 0.5]), 1) > 0.99);
+        assert!(chi_square_p_value(chi_square_statistic(&[600, 400], &[0.5, 0.5]), 1) < SIGNIFICANCE);
+
+        assert_eq!(empirical_privacy_loss(vec![0, 0, 1, 1], vec![0, 1, 1, 1], 1), 2f64.ln());
+        assert!(empirical_privacy_loss(vec![0, 0], vec![1, 1], 1).is_infinite());
+    }
+}
+
+#[cfg(test)]
+mod test_noise_distributions {
+    use crate::utilities::noise;
+    use crate::utilities::statistical_tests::*;
+    use probability::distribution::{Distribution, Gaussian, Laplace};
+
+    const NUM_SAMPLES: usize = 20_000;
+
+    #[test]
+    fn test_sample_uniform() {
+        let samples = (0..NUM_SAMPLES).map(|_| noise::sample_uniform(-1., 3., false).unwrap()).collect();
+        assert_kolmogorov_smirnov(samples, |x| ((x + 1.) / 4.).clamp(0., 1.));
+
+        let samples = (0..NUM_SAMPLES).map(|_| noise::sample_uniform(-1., 3., true).unwrap()).collect();
+        assert_kolmogorov_smirnov(samples, |x| ((x + 1.) / 4.).clamp(0., 1.));
+    }
+
+    #[test]
+    fn test_sample_laplace() {
+        let samples = (0..NUM_SAMPLES).map(|_| noise::sample_laplace(1., 2., false).unwrap()).collect();
+        assert_kolmogorov_smirnov(samples, |x| Laplace::new(1., 2.).distribution(x));
+    }
+
+    #[test]
+    fn test_sample_gaussian() {
+        let samples = (0..NUM_SAMPLES).map(|_| noise::sample_gaussian(1., 2., false).unwrap()).collect();
+        assert_kolmogorov_smirnov(samples, |x| Gaussian::new(1., 2.).distribution(x));
+    }
+
+    #[test]
+    #[cfg(feature = "use-mpfr")]
+    fn test_sample_gaussian_mpfr() {
+        let samples = (0..NUM_SAMPLES).map(|_| noise::sample_gaussian_mpfr(1., 2.).unwrap().to_f64()).collect();
+        assert_kolmogorov_smirnov(samples, |x| Gaussian::new(1., 2.).distribution(x));
+    }
+
+    #[test]
+    fn test_sample_cauchy() {
+        let samples = (0..NUM_SAMPLES).map(|_| noise::sample_cauchy(1., 2., false).unwrap()).collect();
+        assert_kolmogorov_smirnov(samples, |x| 0.5 + ((x - 1.) / 2.).atan() / std::f64::consts::PI);
+    }
+
+    #[test]
+    fn test_sample_gaussian_truncated() {
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| noise::sample_gaussian_truncated(-1., 2., 0., 1., false).unwrap()).collect();
+        let gaussian = Gaussian::new(0., 1.);
+        let (lower, upper) = (gaussian.distribution(-1.), gaussian.distribution(2.));
+        assert_kolmogorov_smirnov(samples, |x| ((gaussian.distribution(x) - lower) / (upper - lower)).clamp(0., 1.));
+    }
+
+    #[test]
+    fn test_sample_bit_prob() {
+        for prob in &[0.01, 0.3, 0.5, 0.99] {
+            let samples = (0..NUM_SAMPLES)
+                .map(|_| noise::sample_bit_prob(*prob, false).unwrap()).collect::<Vec<bool>>();
+            assert_chi_square(&count_categories(&samples, &[true])[..], &[*prob, 1. - prob]);
+        }
+    }
+
+    #[test]
+    fn test_sample_uniform_int() {
+        let categories = (-3..=3).collect::<Vec<i64>>();
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| noise::sample_uniform_int(-3, 3).unwrap()).collect::<Vec<i64>>();
+        let mut counts = count_categories(&samples, &categories);
+        assert_eq!(counts.pop(), Some(0));
+        assert_chi_square(&counts, &[1. / 7.; 7]);
+    }
+
+    #[test]
+    fn test_sample_simple_geometric_mechanism() {
+        let (scale, max_trials): (f64, i64) = (2., 5);
+        let alpha = (-1. / scale).exp();
+
+        // two-sided geometric distribution, censored at +-max_trials
+        let categories = (-max_trials..=max_trials).collect::<Vec<i64>>();
+        let probabilities = categories.iter()
+            .map(|k| if k.abs() == max_trials {
+                alpha.powi(max_trials as i32) / (1. + alpha)
+            } else {
+                alpha.powi(k.abs() as i32) * (1. - alpha) / (1. + alpha)
+            })
+            .collect::<Vec<f64>>();
+
+        for enforce_constant_time in &[false, true] {
+            let samples = (0..NUM_SAMPLES)
+                .map(|_| noise::sample_simple_geometric_mechanism(scale, 0, max_trials, *enforce_constant_time).unwrap())
+                .collect::<Vec<i64>>();
+            let mut counts = count_categories(&samples, &categories);
+            assert_eq!(counts.pop(), Some(0));
+            assert_chi_square(&counts, &probabilities);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "use-mpfr")]
+    fn test_sample_discrete_gaussian() {
+        let sigma_squared: f64 = 4.;
+
+        // the tails beyond +-6 are merged into the final category
+        let categories = (-6..=6).collect::<Vec<i64>>();
+        let weight = |x: i64| (-(x * x) as f64 / (2. * sigma_squared)).exp();
+        let normalization = (-100..=100).map(weight).sum::<f64>();
+        let mut probabilities = categories.iter().map(|x| weight(*x) / normalization).collect::<Vec<f64>>();
+        probabilities.push(1. - probabilities.iter().sum::<f64>());
+
+        let samples = (0..NUM_SAMPLES / 2)
+            .map(|_| noise::sample_discrete_gaussian(&rug::Rational::from(4)).unwrap().to_i64().unwrap())
+            .collect::<Vec<i64>>();
+        assert_chi_square(&count_categories(&samples, &categories), &probabilities);
+    }
+}
+
+#[cfg(test)]
+mod test_mechanism_distributions {
+    use crate::utilities::mechanisms;
+    use crate::utilities::statistical_tests::*;
+    use whitenoise_validator::utilities::privacy::{analytic_gaussian_scale, randomized_response_probabilities};
+    use probability::distribution::{Distribution, Gaussian, Laplace};
+
+    const NUM_SAMPLES: usize = 20_000;
+
+    /// Probabilities of the exponential mechanism.
+    fn softmax(epsilon: f64, sensitivity: f64, utilities: &[f64]) -> Vec<f64> {
+        let weights = utilities.iter()
+            .map(|utility| (epsilon * utility / (2. * sensitivity)).exp())
+            .collect::<Vec<f64>>();
+        let total = weights.iter().sum::<f64>();
+        weights.into_iter().map(|weight| weight / total).collect()
+    }
+
+    #[test]
+    fn test_laplace_mechanism() {
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::laplace_mechanism(0.5, 2., false).unwrap()).collect();
+        assert_kolmogorov_smirnov(samples, |x| Laplace::new(0., 4.).distribution(x));
+    }
+
+    #[test]
+    fn test_gaussian_mechanism() {
+        let (epsilon, delta, sensitivity) = (0.5, 1e-5, 2.);
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::gaussian_mechanism(epsilon, delta, sensitivity, false).unwrap()).collect();
+        let scale = sensitivity * (2. * (1.25 / delta).ln()).sqrt() / epsilon;
+        assert_kolmogorov_smirnov(samples, |x| Gaussian::new(0., scale).distribution(x));
+    }
+
+    #[test]
+    fn test_analytic_gaussian_mechanism() {
+        let (epsilon, delta, sensitivity) = (0.5, 1e-5, 2.);
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::analytic_gaussian_mechanism(epsilon, delta, sensitivity, false).unwrap()).collect();
+        let scale = analytic_gaussian_scale(epsilon, delta, sensitivity).unwrap();
+        assert_kolmogorov_smirnov(samples, |x| Gaussian::new(0., scale).distribution(x));
+    }
+
+    #[test]
+    fn test_gaussian_mechanism_concentrated() {
+        let (rho, sensitivity) = (0.5, 2.);
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::gaussian_mechanism_concentrated(rho, sensitivity, false).unwrap()).collect();
+        let scale = sensitivity / (2. * rho).sqrt();
+        assert_kolmogorov_smirnov(samples, |x| Gaussian::new(0., scale).distribution(x));
+    }
+
+    #[test]
+    fn test_simple_geometric_mechanism() {
+        let (epsilon, sensitivity): (f64, f64) = (0.5, 1.);
+        let alpha = (-epsilon / sensitivity).exp();
+        let categories = (-3..=3).collect::<Vec<i64>>();
+        let mut probabilities = categories.iter()
+            .map(|k| alpha.powi(k.abs() as i32) * (1. - alpha) / (1. + alpha))
+            .collect::<Vec<f64>>();
+        probabilities.push(1. - probabilities.iter().sum::<f64>());
+
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::simple_geometric_mechanism(epsilon, sensitivity, 0, 100, false).unwrap())
+            .collect::<Vec<i64>>();
+        assert_chi_square(&count_categories(&samples, &categories), &probabilities);
+    }
+
+    #[test]
+    #[cfg(feature = "use-mpfr")]
+    fn test_discrete_gaussian_mechanism() {
+        // sigma^2 = sensitivity^2 / (2 rho) = 4
+        let (rho, sensitivity) = (0.5, 2.);
+        let categories = (-6..=6).collect::<Vec<i64>>();
+        let weight = |x: i64| (-(x * x) as f64 / 8.).exp();
+        let normalization = (-100..=100).map(weight).sum::<f64>();
+        let mut probabilities = categories.iter().map(|x| weight(*x) / normalization).collect::<Vec<f64>>();
+        probabilities.push(1. - probabilities.iter().sum::<f64>());
+
+        let samples = (0..NUM_SAMPLES / 2)
+            .map(|_| mechanisms::discrete_gaussian_mechanism(rho, sensitivity).unwrap())
+            .collect::<Vec<i64>>();
+        assert_chi_square(&count_categories(&samples, &categories), &probabilities);
+    }
+
+    #[test]
+    fn test_exponential_mechanism() {
+        let candidates = [0, 1, 2, 3];
+        let utilities = vec![0., 1., 2., 4.];
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::exponential_mechanism(1., 1., &candidates, utilities.clone(), false).unwrap())
+            .collect::<Vec<i64>>();
+        let mut counts = count_categories(&samples, &candidates);
+        assert_eq!(counts.pop(), Some(0));
+        assert_chi_square(&counts, &softmax(1., 1., &utilities));
+    }
+
+    #[test]
+    fn test_report_noisy_max_gumbel() {
+        // report-noisy-max with gumbel noise is the exponential mechanism
+        let candidates = [0, 1, 2, 3];
+        let utilities = vec![0., 1., 2., 4.];
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::report_noisy_max(1., 1., &candidates, utilities.clone(), "gumbel", false).unwrap())
+            .collect::<Vec<i64>>();
+        let mut counts = count_categories(&samples, &candidates);
+        assert_eq!(counts.pop(), Some(0));
+        assert_chi_square(&counts, &softmax(1., 1., &utilities));
+    }
+
+    #[test]
+    fn test_permute_and_flip() {
+        // with two candidates, the worse candidate is released only if it is visited first and accepted
+        let candidates = [0, 1];
+        let utilities = vec![2., 0.];
+        // accepted with probability exp(epsilon * (0 - 2) / (2 * sensitivity))
+        let accept = (-1f64).exp();
+        let probabilities = [1. - accept / 2., accept / 2.];
+
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::permute_and_flip(1., 1., &candidates, utilities.clone(), false).unwrap())
+            .collect::<Vec<i64>>();
+        let mut counts = count_categories(&samples, &candidates);
+        assert_eq!(counts.pop(), Some(0));
+        assert_chi_square(&counts, &probabilities);
+
+        // report-noisy-max with exponential noise has the same distribution as permute-and-flip
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::report_noisy_max(1., 1., &candidates, utilities.clone(), "exponential", false).unwrap())
+            .collect::<Vec<i64>>();
+        let mut counts = count_categories(&samples, &candidates);
+        assert_eq!(counts.pop(), Some(0));
+        assert_chi_square(&counts, &probabilities);
+    }
+
+    #[test]
+    fn test_randomized_response() {
+        let categories = ["a", "b", "c"];
+        let (prob_truth, _) = randomized_response_probabilities("generalized", 1., categories.len()).unwrap();
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::randomized_response(&"b", &categories, 1., false).unwrap())
+            .collect::<Vec<&str>>();
+        let mut counts = count_categories(&samples, &categories);
+        assert_eq!(counts.pop(), Some(0));
+        assert_chi_square(&counts, &[(1. - prob_truth) / 2., prob_truth, (1. - prob_truth) / 2.]);
+    }
+
+    #[test]
+    fn test_unary_randomized_response() {
+        let categories = ["a", "b", "c"];
+        let (prob_one, prob_zero) = randomized_response_probabilities("unary", 1., categories.len()).unwrap();
+        let samples = (0..NUM_SAMPLES)
+            .map(|_| mechanisms::unary_randomized_response(&"b", &categories, 1., false).unwrap())
+            .collect::<Vec<Vec<bool>>>();
+
+        // each bit is an independent draw
+        for (index, category) in categories.iter().enumerate() {
+            let bits = samples.iter().map(|report| report[index]).collect::<Vec<bool>>();
+            let prob = if *category == "b" { prob_one } else { prob_zero };
+            assert_chi_square(&count_categories(&bits, &[true]), &[prob, 1. - prob]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_mechanism_privacy_loss {
+    use crate::utilities::mechanisms;
+    use crate::utilities::statistical_tests::*;
+
+    const NUM_SAMPLES: usize = 20_000;
+    const EPSILON: f64 = 1.;
+
+    #[test]
+    fn test_laplace_mechanism() {
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| bin(
+            neighbor as i64 as f64 + mechanisms::laplace_mechanism(EPSILON, 1., false).unwrap(), 0.5));
+    }
+
+    #[test]
+    #[cfg(feature = "use-mpfr")]
+    fn test_snapping_mechanism() {
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| bin(
+            mechanisms::snapping_mechanism(neighbor as i64 as f64, EPSILON, 1., -10., 10.).unwrap(), 0.5));
+    }
+
+    #[test]
+    fn test_simple_geometric_mechanism() {
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor|
+            neighbor as i64 + mechanisms::simple_geometric_mechanism(EPSILON, 1., 0, 100, false).unwrap());
+    }
+
+    #[test]
+    fn test_exponential_mechanism() {
+        // each utility changes by at most the sensitivity
+        let candidates = [0, 1, 2];
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| {
+            let utilities = if neighbor { vec![1., 0., 1.] } else { vec![0., 1., 2.] };
+            mechanisms::exponential_mechanism(EPSILON, 1., &candidates, utilities, false).unwrap()
+        });
+    }
+
+    #[test]
+    fn test_report_noisy_max() {
+        let candidates = [0, 1, 2];
+        for noise in &["gumbel", "laplace", "exponential"] {
+            assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| {
+                let utilities = if neighbor { vec![1., 0., 1.] } else { vec![0., 1., 2.] };
+                mechanisms::report_noisy_max(EPSILON, 1., &candidates, utilities, noise, false).unwrap()
+            });
+        }
+    }
+
+    #[test]
+    fn test_permute_and_flip() {
+        let candidates = [0, 1, 2];
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| {
+            let utilities = if neighbor { vec![1., 0., 1.] } else { vec![0., 1., 2.] };
+            mechanisms::permute_and_flip(EPSILON, 1., &candidates, utilities, false).unwrap()
+        });
+    }
+
+    #[test]
+    fn test_above_threshold() {
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| {
+            let answers = if neighbor { [1., 0., 1.] } else { [0., 1., 0.] };
+            mechanisms::above_threshold(EPSILON, 1., &answers, 0.5, 1, false).unwrap()
+        });
+    }
+
+    #[test]
+    fn test_randomized_response() {
+        let categories = ["a", "b", "c"];
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor|
+            mechanisms::randomized_response(if neighbor { &"a" } else { &"b" }, &categories, EPSILON, false).unwrap());
+    }
+
+    #[test]
+    fn test_unary_randomized_response() {
+        let categories = ["a", "b", "c"];
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor|
+            mechanisms::unary_randomized_response(if neighbor { &"a" } else { &"b" }, &categories, EPSILON, false).unwrap());
+    }
+
+    #[test]
+    fn test_key_selection() {
+        // the count of "a" is near the threshold, and the neighboring dataset contains one more "a"
+        let data = (0..65).map(|i| if i < 15 { "a" } else { "b" }).collect::<Vec<&str>>();
+        for mechanism in &["laplace", "gaussian"] {
+            assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| {
+                let mut data = data.clone();
+                if neighbor { data.push("a") }
+                mechanisms::key_selection(data, EPSILON, 1e-6, mechanism, false).unwrap().0
+            });
+        }
+    }
+
+    #[test]
+    fn test_smooth_quantile_mechanism() {
+        // substitute the record at the median
+        let column = (0..=10).map(|v| v as f64).collect::<Vec<f64>>();
+        let mut neighboring_column = column.clone();
+        neighboring_column[5] = 10.;
+
+        assert_privacy_loss(EPSILON, NUM_SAMPLES, |neighbor| bin(mechanisms::smooth_quantile_mechanism(
+            if neighbor { neighboring_column.clone() } else { column.clone() },
+            0.5, EPSILON, 0., 10., false).unwrap(), 0.5));
+    }
+}
@@ -0,0 +1,446 @@
+//! Streaming evaluation of decomposable aggregations over chunked data sources.
+//!
+//! When a Materialize component of a csv sets a nonzero `chunk_size`, and the data consumed by a
+//! Sum, Count, Mean, RawMoment or Histogram is derived from it only through row-wise components,
+//! then the file is read in chunks. Each chunk is passed through the row-wise components,
+//! aggregated, and folded into the aggregate of the prior chunks.
+//! Peak memory is bounded by the chunk size, instead of the size of the file.
+
+use whitenoise_validator::errors::*;
+
+use whitenoise_validator::{proto, Float};
+use whitenoise_validator::base::{Value, Array, ReleaseNode, Release, IndexKey, ValueProperties};
+use whitenoise_validator::components::Named;
+use whitenoise_validator::utilities::get_input_properties;
+use proto::component::Variant;
+
+use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+use ndarray::Zip;
+
+use crate::NodeArguments;
+use crate::components::Evaluable;
+use crate::components::materialize::{open_csv, read_records, check_max_records};
+use crate::utilities::is_overflow_protected;
+
+
+/// Outcome of an attempt to stream an aggregation.
+pub enum Streaming {
+    /// The aggregation cannot be streamed, and is evaluated on fully materialized data.
+    Unavailable,
+    /// Nodes that must be evaluated before the aggregation can be streamed.
+    Dependencies(Vec<u32>),
+    /// The aggregation, folded from the partial aggregates of each chunk.
+    Evaluated(Box<ReleaseNode>),
+}
+
+/// Result of a single pass over the subgraph consumed by an aggregation.
+enum Discovery {
+    /// Expansions modified the subgraph, so it must be traversed again.
+    Restart,
+    Dependencies(Vec<u32>),
+    Unavailable,
+    /// The id of the chunked Materialize, and the remaining node ids in evaluation order, ending with the aggregation.
+    Chain(u32, Vec<u32>),
+}
+
+/// Attempt to evaluate an aggregation by streaming its data source in chunks.
+///
+/// Nodes in the streamed subgraph are expanded in place, in the same manner as the runtime would expand them.
+/// Intermediate values of the row-wise components are never stored in the release.
+///
+/// # Arguments
+/// * `privacy_definition` - Definition of privacy, used when evaluating each chunk.
+/// * `computation_graph` - Graph being executed. Expansions of streamed nodes are written back to the graph.
+/// * `properties` - Properties of the nodes in the graph.
+/// * `release` - Values of the evaluated nodes in the graph.
+/// * `maximum_id` - Largest node id in the graph.
+/// * `component_id` - Id of the aggregation to stream.
+///
+/// # Return
+/// Whether the aggregation was streamed, and if so, the folded aggregate.
+pub fn stream_aggregation(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    computation_graph: &mut HashMap<u32, proto::Component>,
+    properties: &mut HashMap<u32, ValueProperties>,
+    release: &mut Release,
+    maximum_id: &mut u32,
+    component_id: u32,
+) -> Result<Streaming> {
+
+    // elapsed time is padded for each node evaluation, which streaming would bypass
+    if privacy_definition.as_ref().map(|v| v.protect_elapsed_time).unwrap_or(false) {
+        return Ok(Streaming::Unavailable)
+    }
+
+    match computation_graph.get(&component_id).and_then(|v| v.variant.as_ref()) {
+        Some(variant) if is_decomposable(variant).is_some() => (),
+        _ => return Ok(Streaming::Unavailable)
+    };
+
+    let mut expanded = HashSet::new();
+    let (root_id, chain) = loop {
+        match discover(privacy_definition, computation_graph, properties, release, maximum_id, &mut expanded, component_id)? {
+            Discovery::Restart => continue,
+            Discovery::Dependencies(dependencies) => return Ok(Streaming::Dependencies(dependencies)),
+            Discovery::Unavailable => return Ok(Streaming::Unavailable),
+            Discovery::Chain(root_id, chain) => break (root_id, chain)
+        }
+    };
+
+    let materialize = match computation_graph.get(&root_id).and_then(|v| v.variant.as_ref()) {
+        Some(Variant::Materialize(materialize)) => materialize.clone(),
+        _ => return Err("the root of a stream must be a Materialize".into())
+    };
+    let column_names = materialize.get_names(
+        computation_graph.get(&root_id).unwrap().arguments().into_iter()
+            .filter_map(|(name, node_id)| release.get(&node_id).map(|v| (name, &v.value)))
+            .collect(),
+        IndexMap::new(), None)?;
+
+    let weighted = computation_graph.get(&component_id).and_then(|v| v.variant.as_ref())
+        .and_then(is_decomposable).unwrap_or(false);
+    let protect_overflow = is_overflow_protected(privacy_definition);
+
+    let mut reader = open_csv(&materialize)?;
+    let mut aggregate: Option<Array> = None;
+    let mut num_records_total: usize = 0;
+
+    loop {
+        let (chunk, num_records) = read_records(&mut reader, &column_names, Some(materialize.chunk_size as usize))?;
+        if num_records == 0 {
+            break
+        }
+
+        // evaluate the row-wise components and aggregation on the chunk
+        let mut values = HashMap::<u32, Value>::new();
+        values.insert(root_id, chunk);
+        for node_id in &chain {
+            let component = computation_graph.get(node_id)
+                .ok_or_else(|| Error::from("attempted to retrieve a non-existent component id"))?;
+
+            let mut arguments = NodeArguments::new();
+            for (name, argument_id) in component.arguments() {
+                let value = match values.get(&argument_id) {
+                    Some(value) => value.clone(),
+                    None => release.get(&argument_id)
+                        .ok_or_else(|| Error::from("arguments to streamed components must be evaluated"))?
+                        .value.clone()
+                };
+                arguments.insert(name, value);
+            }
+
+            let evaluation = component.variant.as_ref()
+                .ok_or_else(|| Error::from("variant of component must be known"))?
+                .evaluate(privacy_definition, arguments)?;
+            values.insert(*node_id, evaluation.value);
+        }
+
+        let partial = values.remove(&component_id)
+            .ok_or_else(|| Error::from("aggregation must be evaluated on each chunk"))?.array()?;
+        aggregate = Some(fold(aggregate, partial, if weighted { Some(num_records) } else { None }, protect_overflow)?);
+        num_records_total += num_records;
+        check_max_records(&materialize, num_records_total)?;
+    }
+
+    // the dataset is assumed to be non-empty, so defer to the standard evaluation for its failure
+    let aggregate = match aggregate {
+        Some(aggregate) => aggregate,
+        None => return Ok(Streaming::Unavailable)
+    };
+
+    Ok(Streaming::Evaluated(Box::new(ReleaseNode::new(Value::Array(match (aggregate, weighted) {
+        (Array::Float(aggregate), true) => Array::Float(aggregate / num_records_total as Float),
+        (aggregate, _) => aggregate
+    })))))
+}
+
+/// Traverse the subgraph consumed by an aggregation, and expand its nodes.
+///
+/// Every node reachable from the aggregation must be either the aggregation,
+/// a single chunked Materialize, a row-wise component, or a public value in the release.
+fn discover(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    computation_graph: &mut HashMap<u32, proto::Component>,
+    properties: &mut HashMap<u32, ValueProperties>,
+    release: &mut Release,
+    maximum_id: &mut u32,
+    expanded: &mut HashSet<u32>,
+    component_id: u32,
+) -> Result<Discovery> {
+
+    let mut root_id = None;
+    let mut dependencies = Vec::new();
+    let mut visited = HashSet::new();
+    // node ids in evaluation order, including the root
+    let mut order = Vec::new();
+
+    // depth-first traversal. The flag is set once the arguments of the node have been traversed
+    let mut traversal = vec![(component_id, false)];
+    while let Some((node_id, arguments_traversed)) = traversal.pop() {
+        if arguments_traversed {
+            order.push(node_id);
+            continue
+        }
+        if !visited.insert(node_id) {
+            continue
+        }
+
+        // evaluated nodes are shared by every chunk, so they must not be data
+        if let Some(release_node) = release.get(&node_id) {
+            if !release_node.public {
+                return Ok(Discovery::Unavailable)
+            }
+            continue
+        }
+
+        let component = match computation_graph.get(&node_id) {
+            Some(component) => component,
+            None => return Ok(Discovery::Unavailable)
+        };
+        let variant = match component.variant.as_ref() {
+            Some(variant) => variant,
+            None => return Ok(Discovery::Unavailable)
+        };
+
+        match variant {
+            Variant::Materialize(materialize) if materialize.chunk_size > 0
+                && ["", "csv"].contains(&materialize.file_format.to_lowercase().as_str()) => {
+                if root_id.map(|v| v != node_id).unwrap_or(false) {
+                    return Ok(Discovery::Unavailable)
+                }
+                root_id = Some(node_id);
+            }
+            _ if node_id == component_id && is_decomposable(variant).is_some() => (),
+            _ if is_row_wise(variant) => (),
+            // any other node is evaluated normally before streaming
+            _ => {
+                dependencies.push(node_id);
+                continue
+            }
+        }
+
+        traversal.push((node_id, true));
+        component.arguments().values()
+            .for_each(|argument_id| traversal.push((*argument_id, false)));
+    }
+
+    if !dependencies.is_empty() {
+        return Ok(Discovery::Dependencies(dependencies))
+    }
+    let root_id = match root_id {
+        Some(root_id) => root_id,
+        None => return Ok(Discovery::Unavailable)
+    };
+
+    // expand nodes in evaluation order, so that the properties of arguments are known
+    let mut modified = false;
+    for node_id in &order {
+        if expanded.contains(node_id) {
+            continue
+        }
+        match expand(privacy_definition, computation_graph, properties, release, maximum_id, *node_id) {
+            Ok(true) => {
+                expanded.insert(*node_id);
+            }
+            // the expansion added nodes that must be traversed
+            Ok(false) => return Ok(Discovery::Restart),
+            // defer to the standard evaluation to report the failure
+            Err(_) => return Ok(Discovery::Unavailable)
+        }
+        modified = true;
+    }
+    if modified {
+        return Ok(Discovery::Restart)
+    }
+
+    // broadcasting values with more than one record against a chunk would misalign the records
+    for node_id in &order {
+        let component = computation_graph.get(node_id).unwrap();
+        if !is_broadcasting(component.variant.as_ref().unwrap()) {
+            continue
+        }
+        for argument_id in component.arguments().values() {
+            let num_records = release.get(argument_id)
+                .and_then(|v| v.value.ref_array().ok())
+                .map(|v| v.shape().first().cloned().unwrap_or(1));
+            if num_records.map(|v| v > 1).unwrap_or(false) {
+                return Ok(Discovery::Unavailable)
+            }
+        }
+    }
+
+    Ok(Discovery::Chain(root_id, order.into_iter().filter(|v| *v != root_id).collect()))
+}
+
+/// Expand a node in place.
+///
+/// # Return
+/// True if the expansion is complete, or false if the expansion added nodes that must be traversed.
+fn expand(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    computation_graph: &mut HashMap<u32, proto::Component>,
+    properties: &mut HashMap<u32, ValueProperties>,
+    release: &mut Release,
+    maximum_id: &mut u32,
+    node_id: u32,
+) -> Result<bool> {
+    let component = computation_graph.get(&node_id)
+        .ok_or_else(|| Error::from("attempted to retrieve a non-existent component id"))?.clone();
+
+    let node_properties = get_input_properties(&component, properties)?;
+    let public_arguments = component.arguments().into_iter()
+        .filter_map(|(name, argument_id)| release.get(&argument_id)
+            .filter(|release_node| release_node.public)
+            .map(|release_node| (name, release_node.clone())))
+        .collect::<IndexMap<IndexKey, ReleaseNode>>();
+
+    let expansion = whitenoise_validator::expand_component(
+        component,
+        node_properties,
+        public_arguments,
+        privacy_definition.clone(),
+        node_id,
+        *maximum_id)?;
+
+    *maximum_id = expansion.computation_graph.keys()
+        .max().cloned().unwrap_or(0).max(*maximum_id);
+
+    computation_graph.extend(expansion.computation_graph);
+    properties.extend(expansion.properties);
+    release.extend(expansion.releases);
+
+    Ok(expansion.traversal.is_empty())
+}
+
+/// Fold a partial aggregate into the aggregate of the prior chunks.
+///
+/// Partial aggregates that are averages over a chunk are weighted by the number of records in the chunk.
+fn fold(aggregate: Option<Array>, partial: Array, weight: Option<usize>, protect_overflow: bool) -> Result<Array> {
+    let partial = match (partial, weight) {
+        (Array::Float(partial), Some(weight)) => Array::Float(partial * weight as Float),
+        (partial, None) => partial,
+        _ => return Err("weighted partial aggregates must be floats".into())
+    };
+
+    Ok(match (aggregate, partial) {
+        (None, partial) => partial,
+        (Some(Array::Float(aggregate)), Array::Float(partial)) => Array::Float(aggregate + partial),
+        (Some(Array::Int(mut aggregate)), Array::Int(partial)) => {
+            if protect_overflow {
+                Zip::from(&mut aggregate).and(&partial)
+                    .apply(|l, r| *l = l.saturating_add(*r));
+            } else {
+                aggregate += &partial;
+            }
+            Array::Int(aggregate)
+        }
+        _ => return Err("partial aggregates must be homogeneously typed numbers".into())
+    })
+}
+
+/// Determine if a component may be computed by folding its evaluations over chunks.
+///
+/// # Return
+/// None if the component is not decomposable, otherwise whether the partial aggregates must be weighted by the size of the chunk.
+fn is_decomposable(variant: &Variant) -> Option<bool> {
+    match variant {
+        Variant::Sum(_) | Variant::Histogram(_) => Some(false),
+        Variant::Count(count) if !count.distinct => Some(false),
+        Variant::Mean(_) | Variant::RawMoment(_) => Some(true),
+        _ => None
+    }
+}
+
+/// Determine if each record of the output of a component only depends on the same record of its data.
+fn is_row_wise(variant: &Variant) -> bool {
+    matches!(variant,
+        Variant::Index(_) | Variant::Cast(_) | Variant::Clamp(_) | Variant::Digitize(_) | Variant::Impute(_) |
+        Variant::ToBool(_) | Variant::ToFloat(_) | Variant::ToInt(_) | Variant::ToString(_))
+        || is_broadcasting(variant)
+}
+
+/// Determine if a component broadcasts its arguments against each other, record by record.
+fn is_broadcasting(variant: &Variant) -> bool {
+    matches!(variant,
+        Variant::Abs(_) | Variant::Add(_) | Variant::LogicalAnd(_) | Variant::Divide(_) | Variant::Equal(_) |
+        Variant::GreaterThan(_) | Variant::LessThan(_) | Variant::Log(_) | Variant::Modulo(_) |
+        Variant::Multiply(_) | Variant::Negate(_) | Variant::Negative(_) | Variant::LogicalOr(_) |
+        Variant::Power(_) | Variant::RowMax(_) | Variant::RowMin(_) | Variant::Subtract(_))
+}
+
+
+#[cfg(test)]
+mod test_streaming {
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::base::{Value, ReleaseNode};
+    use whitenoise_validator::proto;
+    use ndarray::arr1;
+    use std::io::Write;
+
+    /// Sum, mean and count of a clamped float column, from a csv read in chunks of `chunk_size` records.
+    /// Also returns whether the entire csv was materialized.
+    fn aggregate(file_path: &str, chunk_size: u64) -> (Vec<Value>, bool) {
+        let mut analysis = Analysis::new();
+        let column_names = analysis.literal()
+            .value(arr1(&["a".to_string(), "b".to_string()]).into_dyn().into())
+            .value_public(true).build();
+        let data = analysis.materialize(column_names, file_path.to_string())
+            .chunk_size(chunk_size).build();
+
+        let name = analysis.literal().value("b".to_string().into()).value_public(true).build();
+        analysis.component_count += 1;
+        let indexed = analysis.component_count;
+        analysis.components.insert(indexed, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap::indexmap![
+                "data".into() => data, "names".into() => name])),
+            variant: Some(proto::component::Variant::Index(proto::Index {})),
+            omit: false,
+            submission: 0,
+        });
+
+        let cast = analysis.to_float(indexed).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(5.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(cast).lower(lower).upper(upper).build();
+
+        let ids = [
+            analysis.sum(clamped).build(),
+            analysis.mean(clamped).build(),
+            analysis.count(clamped).build()
+        ];
+
+        let (release, _) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        (ids.iter()
+            .map(|id| release.get(id).map(|v: &ReleaseNode| v.value.clone()).unwrap())
+            .collect(), release.contains_key(&data))
+    }
+
+    #[test]
+    fn test_stream_aggregation() {
+        let file_path = std::env::temp_dir().join(format!("whitenoise_streaming_{}.csv", std::process::id()));
+        let mut file = std::fs::File::create(&file_path).unwrap();
+        writeln!(file, "a,b").unwrap();
+        (0..10).for_each(|i| writeln!(file, "{},{}", i, i).unwrap());
+        let file_path = file_path.to_str().unwrap();
+
+        let (materialized, is_materialized) = aggregate(file_path, 0);
+        let (streamed, is_streamed_materialized) = aggregate(file_path, 3);
+        assert!(is_materialized);
+        assert!(!is_streamed_materialized);
+
+        // 0 + 1 + 2 + 3 + 4 + 5 * 5
+        assert_eq!(materialized[0].ref_array().unwrap().first_float().unwrap(), 35.);
+        assert_eq!(streamed[0].ref_array().unwrap().first_float().unwrap(), 35.);
+        assert!((streamed[1].ref_array().unwrap().first_float().unwrap() - 3.5).abs() < 1e-12);
+        assert_eq!(materialized[2].ref_array().unwrap().first_int().unwrap(), 10);
+        assert_eq!(streamed[2].ref_array().unwrap().first_int().unwrap(), 10);
+
+        std::fs::remove_file(file_path).unwrap();
+    }
+}
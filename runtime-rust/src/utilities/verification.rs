@@ -0,0 +1,370 @@
+//! Runtime verification of the properties derived by the validator.
+//!
+//! The validator derives bounds, nullity, sizes and categories for every node without evaluating it,
+//! and sensitivities are computed from these properties.
+//! When `verify_properties` is enabled in the privacy definition,
+//! each evaluated value is compared against its derived properties,
+//! and the release is aborted at the first node whose value violates them.
+
+use whitenoise_validator::errors::*;
+
+use whitenoise_validator::proto;
+use whitenoise_validator::base::{Value, Array, Jagged, ValueProperties, ArrayProperties, JaggedProperties, IndexKey, Nature, Vector1DNull, DataType};
+use whitenoise_validator::utilities::prepend;
+
+use indexmap::map::IndexMap;
+use ndarray::ArrayD;
+use std::fmt::Debug;
+
+
+/// Check that the evaluation of a node satisfies the properties derived by the validator.
+///
+/// # Arguments
+/// * `component_id` - Id of the evaluated node.
+/// * `component` - The evaluated component, if known. Used to name the component in the diagnostic.
+/// * `property` - Properties derived by the validator for the node.
+/// * `value` - The evaluated value of the node.
+///
+/// # Return
+/// An error describing the node and the first violated property, if any property is violated.
+pub fn verify_evaluation(
+    component_id: u32,
+    component: Option<&proto::Component>,
+    property: &ValueProperties,
+    value: &Value
+) -> Result<()> {
+    verify_properties(property, value).chain_err(|| {
+        let name = component
+            .and_then(|component| component.variant.as_ref())
+            .map(|variant| format!("{:?}", variant).split('(').next().unwrap_or_default().to_string())
+            .unwrap_or_else(|| "unknown component".to_string());
+        format!("evaluation of {} at node_id {:?} violates the properties derived by the validator", name, component_id)
+    })
+}
+
+/// Check that a value satisfies its derived properties.
+///
+/// Properties that are not known statically, like undefined bounds, are not checked.
+pub fn verify_properties(property: &ValueProperties, value: &Value) -> Result<()> {
+    match (property, value) {
+        (ValueProperties::Array(property), Value::Array(array)) =>
+            verify_array(property, array),
+        (ValueProperties::Jagged(property), Value::Jagged(jagged)) =>
+            verify_jagged(property, jagged),
+        (ValueProperties::Dataframe(property), Value::Dataframe(dataframe)) =>
+            verify_children(&property.children, dataframe, "column"),
+        (ValueProperties::Partitions(property), Value::Partitions(partitions)) =>
+            verify_children(&property.children, partitions, "partition"),
+        (ValueProperties::Function(_), Value::Function(_)) => Ok(()),
+        (property, _) => Err(format!(
+            "value: derived {}, but evaluated a different type", property_type_name(property)).into())
+    }
+}
+
+fn verify_children(
+    properties: &IndexMap<IndexKey, ValueProperties>,
+    values: &IndexMap<IndexKey, Value>,
+    kind: &str
+) -> Result<()> {
+    // evaluated keys may be private, so only derived keys are named
+    if values.keys().any(|key| !properties.contains_key(key)) {
+        return Err(format!("{}: evaluated a key that has no derived properties", kind).into())
+    }
+    properties.iter().try_for_each(|(key, property)| {
+        let value = values.get(key)
+            .ok_or_else(|| Error::from(format!("{} {:?} has derived properties, but was not evaluated", kind, key)))?;
+        verify_properties(property, value).map_err(prepend(&format!("{} {:?}:", kind, key)))
+    })
+}
+
+fn verify_array(property: &ArrayProperties, array: &Array) -> Result<()> {
+    // the dimensionality is not checked, because aggregations may evaluate to a lower dimensionality than derived
+    let num_records = array.num_records()? as i64;
+    if let Some(expected) = property.num_records {
+        if expected != num_records {
+            return Err(violation("num_records"))
+        }
+    }
+    if property.is_not_empty && num_records == 0 {
+        return Err(violation("is_not_empty"))
+    }
+
+    let num_columns = array.num_columns()? as i64;
+    if let Some(expected) = property.num_columns {
+        if expected != num_columns {
+            return Err(violation("num_columns"))
+        }
+    }
+
+    match array {
+        Array::Bool(data) => {
+            verify_data_type(&property.data_type, DataType::Bool)?;
+            verify_nature(&property.nature, &columns(data), |nature| match nature {
+                Nature::Categorical(nature) => match &nature.categories {
+                    Jagged::Bool(categories) => Some(Constraint::Categories(categories)),
+                    _ => None
+                },
+                Nature::Continuous(nature) => match (&nature.lower, &nature.upper) {
+                    (Vector1DNull::Bool(lower), Vector1DNull::Bool(upper)) => Some(Constraint::Bounds(lower, upper)),
+                    _ => None
+                }
+            })
+        },
+        Array::Int(data) => {
+            verify_data_type(&property.data_type, DataType::Int)?;
+            verify_nature(&property.nature, &columns(data), |nature| match nature {
+                Nature::Categorical(nature) => match &nature.categories {
+                    Jagged::Int(categories) => Some(Constraint::Categories(categories)),
+                    _ => None
+                },
+                Nature::Continuous(nature) => match (&nature.lower, &nature.upper) {
+                    (Vector1DNull::Int(lower), Vector1DNull::Int(upper)) => Some(Constraint::Bounds(lower, upper)),
+                    _ => None
+                }
+            })
+        },
+        Array::Float(data) => {
+            verify_data_type(&property.data_type, DataType::Float)?;
+            if !property.nullity && data.iter().any(|v| !v.is_finite()) {
+                return Err(violation("nullity"))
+            }
+            verify_nature(&property.nature, &columns(data), |nature| match nature {
+                Nature::Categorical(nature) => match &nature.categories {
+                    Jagged::Float(categories) => Some(Constraint::Categories(categories)),
+                    _ => None
+                },
+                Nature::Continuous(nature) => match (&nature.lower, &nature.upper) {
+                    (Vector1DNull::Float(lower), Vector1DNull::Float(upper)) => Some(Constraint::Bounds(lower, upper)),
+                    _ => None
+                }
+            })
+        },
+        Array::Str(data) => {
+            verify_data_type(&property.data_type, DataType::Str)?;
+            verify_nature(&property.nature, &columns(data), |nature| match nature {
+                Nature::Categorical(nature) => match &nature.categories {
+                    Jagged::Str(categories) => Some(Constraint::Categories(categories)),
+                    _ => None
+                },
+                Nature::Continuous(nature) => match (&nature.lower, &nature.upper) {
+                    (Vector1DNull::Str(lower), Vector1DNull::Str(upper)) => Some(Constraint::Bounds(lower, upper)),
+                    _ => None
+                }
+            })
+        }
+    }
+}
+
+fn verify_jagged(property: &JaggedProperties, jagged: &Jagged) -> Result<()> {
+    if let Some(expected) = &property.num_records {
+        let num_records = jagged.num_records();
+        if expected != &num_records {
+            return Err(violation("num_records"))
+        }
+    }
+
+    match jagged {
+        Jagged::Bool(_) => verify_data_type(&property.data_type, DataType::Bool),
+        Jagged::Int(data) => {
+            verify_data_type(&property.data_type, DataType::Int)?;
+            verify_nature(&property.nature, &jagged_columns(data), |nature| match nature {
+                Nature::Continuous(nature) => match (&nature.lower, &nature.upper) {
+                    (Vector1DNull::Int(lower), Vector1DNull::Int(upper)) => Some(Constraint::Bounds(lower, upper)),
+                    _ => None
+                },
+                _ => None
+            })
+        },
+        Jagged::Float(data) => {
+            verify_data_type(&property.data_type, DataType::Float)?;
+            if !property.nullity && data.iter().flatten().any(|v| !v.is_finite()) {
+                return Err(violation("nullity"))
+            }
+            verify_nature(&property.nature, &jagged_columns(data), |nature| match nature {
+                Nature::Continuous(nature) => match (&nature.lower, &nature.upper) {
+                    (Vector1DNull::Float(lower), Vector1DNull::Float(upper)) => Some(Constraint::Bounds(lower, upper)),
+                    _ => None
+                },
+                _ => None
+            })
+        },
+        Jagged::Str(_) => verify_data_type(&property.data_type, DataType::Str)
+    }
+}
+
+fn verify_data_type(expected: &DataType, evaluated: DataType) -> Result<()> {
+    if expected != &DataType::Unknown && expected != &evaluated {
+        return Err(violation("data_type"))
+    }
+    Ok(())
+}
+
+/// A constraint on the members of each column, extracted from a nature of matching atomic type.
+enum Constraint<'a, T> {
+    Bounds(&'a Vec<Option<T>>, &'a Vec<Option<T>>),
+    Categories(&'a Vec<Vec<T>>),
+}
+
+fn verify_nature<'a, T: 'a + PartialOrd + Debug>(
+    nature: &'a Option<Nature>,
+    columns: &[Vec<&T>],
+    constraint: impl Fn(&'a Nature) -> Option<Constraint<'a, T>>
+) -> Result<()> {
+    let constraint = match nature.as_ref().and_then(constraint) {
+        Some(constraint) => constraint,
+        None => return Ok(())
+    };
+
+    match constraint {
+        Constraint::Bounds(lower, upper) => {
+            if lower.len() != columns.len() || upper.len() != columns.len() {
+                return Err(violation("nature"))
+            }
+            // incomparable members, like NaN, are checked by the nullity
+            let is_violated = columns.iter().zip(lower.iter().zip(upper.iter()))
+                .any(|(column, (lower, upper))| column.iter().any(|v|
+                    lower.as_ref().map(|l| *v < l).unwrap_or(false)
+                        || upper.as_ref().map(|u| *v > u).unwrap_or(false)));
+            if is_violated { Err(violation("nature")) } else { Ok(()) }
+        },
+        Constraint::Categories(categories) => {
+            // a single set of categories is broadcast across all columns
+            if categories.len() != 1 && categories.len() != columns.len() {
+                return Err(violation("nature"))
+            }
+            let is_violated = columns.iter().enumerate().any(|(index, column)| {
+                let categories = &categories[if categories.len() == 1 { 0 } else { index }];
+                column.iter().any(|v| !categories.contains(*v))
+            });
+            if is_violated { Err(violation("nature")) } else { Ok(()) }
+        }
+    }
+}
+
+/// References to the members of each column. Scalars and vectors are one column.
+fn columns<T>(data: &ArrayD<T>) -> Vec<Vec<&T>> {
+    match data.ndim() {
+        0 | 1 => vec![data.iter().collect()],
+        _ => data.gencolumns().into_iter().map(|column| column.into_iter().collect()).collect()
+    }
+}
+
+/// References to the members of each column of a jagged array.
+fn jagged_columns<T>(data: &[Vec<T>]) -> Vec<Vec<&T>> {
+    data.iter().map(|column| column.iter().collect()).collect()
+}
+
+/// A violation of a derived property.
+///
+/// Evaluated values are private, so the message only names the property, and never the evaluated value.
+fn violation(property: &str) -> Error {
+    format!("{}: the evaluated value does not satisfy the derived property", property).into()
+}
+
+fn property_type_name(property: &ValueProperties) -> &'static str {
+    match property {
+        ValueProperties::Array(_) => "an array",
+        ValueProperties::Jagged(_) => "a jagged array",
+        ValueProperties::Dataframe(_) => "a dataframe",
+        ValueProperties::Partitions(_) => "partitions",
+        ValueProperties::Function(_) => "a function",
+    }
+}
+
+#[cfg(test)]
+mod test_verification {
+    use crate::utilities::verification::{verify_properties, verify_evaluation};
+    use whitenoise_validator::base::Value;
+    use whitenoise_validator::bindings::Analysis;
+    use whitenoise_validator::utilities::inference::infer_property;
+    use whitenoise_validator::proto;
+    use ndarray::{arr1, arr2};
+
+    #[test]
+    fn test_detects_violations() {
+        let value: Value = arr2(&[[1., 2.], [3., 4.]]).into_dyn().into();
+        let property = infer_property(&value, None).unwrap();
+        assert!(verify_properties(&property, &value).is_ok());
+
+        // out of bounds
+        let value: Value = arr2(&[[1., 2.], [3., 5.]]).into_dyn().into();
+        assert!(verify_properties(&property, &value).is_err());
+
+        // a different number of records
+        let value: Value = arr2(&[[1., 2.]]).into_dyn().into();
+        assert!(verify_properties(&property, &value).is_err());
+
+        // a null where none was derived
+        let value: Value = arr2(&[[1., 2.], [3., f64::NAN]]).into_dyn().into();
+        assert!(verify_properties(&property, &value).is_err());
+
+        // a different data type
+        let value: Value = arr2(&[[1, 2], [3, 4]]).into_dyn().into();
+        assert!(verify_properties(&property, &value).is_err());
+
+        // a member outside the categories
+        let value: Value = arr1(&["a".to_string(), "b".to_string()]).into_dyn().into();
+        let property = infer_property(&value, None).unwrap();
+        let value: Value = arr1(&["a".to_string(), "c".to_string()]).into_dyn().into();
+        let error = verify_evaluation(3, None, &property, &value).unwrap_err();
+        assert!(error.to_string().contains("node_id 3"));
+    }
+
+    #[test]
+    fn test_errors_omit_values() {
+        let value: Value = arr2(&[[1., 2.], [3., 4.]]).into_dyn().into();
+        let property = infer_property(&value, None).unwrap();
+
+        let violations: Vec<Value> = vec![
+            arr2(&[[1., 2.], [3., 4567.]]).into_dyn().into(),
+            arr2(&[[1., 2.], [3., 4.], [3., 4.], [3., 4.], [3., 4.], [3., 4.], [3., 4.]]).into_dyn().into(),
+            arr2(&[[1, 2], [3, 4567]]).into_dyn().into(),
+            arr1(&["4567".to_string(), "b".to_string()]).into_dyn().into()
+        ];
+        violations.iter().for_each(|value| {
+            let error = verify_evaluation(3, None, &property, value).unwrap_err();
+            let message = error.iter().map(|error| error.to_string()).collect::<Vec<_>>().join("\n");
+            assert!(message.contains("node_id 3"), "{}", message);
+            // neither evaluated members nor evaluated sizes are disclosed
+            assert!(!message.contains("4567") && !message.contains('7'), "{}", message);
+        });
+    }
+
+    #[test]
+    fn test_resize_impute_release() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.verify_properties = true;
+
+        let data = analysis.literal()
+            .value(arr2(&[[1., 2.], [-3., 12.], [3., -4.], [9., 7.]]).into_dyn().into())
+            .value_public(true).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(10.into()).value_public(true).build();
+
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let imputed = analysis.impute(clamped).lower(lower).upper(upper).build();
+        let resized = analysis.resize(imputed)
+            .number_rows(number_rows).lower(lower).upper(upper).build();
+        let mean = analysis.mean(resized).build();
+
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }];
+        let dp_mean = analysis.dp_mean(resized, privacy_usage).build();
+
+        let (release, _warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let resized = release.get(&resized).unwrap().value.clone().array().unwrap().float().unwrap();
+        assert_eq!(resized.shape(), &[10, 2]);
+        assert!(resized.iter().all(|v| (0. ..=10.).contains(v)));
+        assert!(release.contains_key(&mean));
+        assert!(release.contains_key(&dp_mean));
+    }
+}
@@ -12,6 +12,7 @@ use ndarray::{ArrayD, arr0, Dimension, arr1};
 use crate::utilities::{standardize_categorical_argument, deduplicate, get_common_value};
 use indexmap::IndexMap;
 use crate::utilities::serial::{parse_argument_node_ids, serialize_index_key};
+use crate::utilities::privacy::pure_to_concentrated;
 use std::ops::{Add, Div, Mul};
 use itertools::Itertools;
 
@@ -698,7 +699,9 @@ pub struct ArrayProperties {
     /// used for tracking subpartitions
     pub group_id: Vec<GroupId>,
     /// used to determine if order of rows has changed
-    pub naturally_ordered: bool
+    pub naturally_ordered: bool,
+    /// set when data are reports released by randomized response, used to debias tallies of the reports
+    pub randomized_response: Option<RandomizedResponseProperties>
 }
 
 
@@ -823,6 +826,31 @@ impl ArrayProperties {
     pub fn assert_is_releasable(&self) -> Result<()> {
         if self.releasable { Ok(()) } else { Err("data is not releasable when releasability is required".into()) }
     }
+    /// Check that accumulating the `order`-th power of every record cannot overflow.
+    ///
+    /// The worst-case accumulator range is derived from the number of records and the continuous bounds on the data.
+    pub fn assert_accumulator_bounded(&self, order: u32) -> Result<()> {
+        let num_records = self.num_records()
+            .map_err(|_| Error::from("number of records must be known to protect against overflow"))?;
+
+        match self.data_type {
+            DataType::Int => self.lower_int()?.iter().zip(self.upper_int()?.iter())
+                .try_for_each(|(lower, upper)| lower.checked_abs()
+                    .zip(upper.checked_abs())
+                    .and_then(|(lower, upper)| lower.max(upper).checked_pow(order))
+                    .and_then(|magnitude| magnitude.checked_mul(num_records))
+                    .map(|_| ())
+                    .ok_or_else(|| "accumulating the data may overflow".into())),
+            DataType::Float => self.lower_float()?.iter().zip(self.upper_float()?.iter())
+                .try_for_each(|(lower, upper)|
+                    if (lower.abs().max(upper.abs()).powi(order as i32) * num_records as Float).is_finite() {
+                        Ok(())
+                    } else {
+                        Err("accumulating the data may overflow".into())
+                    }),
+            _ => Err("data must be numeric to accumulate".into())
+        }
+    }
     pub fn num_columns(&self) -> Result<i64> {
         self.num_columns.ok_or_else(|| "number of columns is not defined".into())
     }
@@ -859,6 +887,19 @@ pub struct AggregatorProperties {
     pub lipschitz_constants: Value,
 }
 
+/// Derived properties of the reports released by a RandomizedResponse component.
+///
+/// The RandomizedResponseProperties has a one-to-one mapping to a protobuf RandomizedResponseProperties.
+#[derive(Clone, Debug)]
+pub struct RandomizedResponseProperties {
+    /// encoding of each report
+    pub encoding: String,
+    /// number of categories a report may take
+    pub num_categories: i64,
+    /// privacy usage spent on each report, after accounting for c-stability and group size
+    pub privacy_usage: proto::PrivacyUsage,
+}
+
 #[derive(Clone, Debug)]
 pub enum Nature {
     Continuous(NatureContinuous),
@@ -1103,10 +1144,26 @@ impl proto::PrivacyUsage {
     pub(crate) fn actual_to_effective(&self, p: f64, c_stability: f64, group_size: u32) -> Result<Self> {
         Ok(proto::PrivacyUsage {
             distance: Some(match self.distance.as_ref().ok_or_else(|| "distance must be defined")? {
-                proto::privacy_usage::Distance::Approximate(app) => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-                    epsilon: app.epsilon / c_stability / p / group_size as f64,
-                    delta: app.delta / c_stability / p / ((group_size as f64 * app.epsilon).exp() - 1.) / (app.epsilon.exp() - 1.),
-                })
+                proto::privacy_usage::Distance::Approximate(app) => {
+                    let epsilon = app.epsilon / c_stability / p / group_size as f64;
+                    // inverts the group privacy bound on delta in effective_to_actual
+                    let group_ratio = if group_size == 1 { 1. } else {
+                        (epsilon.exp() - 1.) / ((group_size as f64 * epsilon).exp() - 1.)
+                    };
+                    proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                        epsilon,
+                        delta: app.delta / c_stability / p * group_ratio,
+                    })
+                },
+                // rho-zCDP implies (k^2 rho)-zCDP for groups of size k
+                proto::privacy_usage::Distance::Concentrated(conc) => {
+                    if p != 1. {
+                        return Err("privacy amplification by subsampling is not supported for concentrated privacy usages".into())
+                    }
+                    proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                        rho: conc.rho / (c_stability * group_size as f64).powi(2)
+                    })
+                }
             })
         })
     }
@@ -1117,29 +1174,47 @@ impl proto::PrivacyUsage {
                 proto::privacy_usage::Distance::Approximate(app) => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
                     epsilon: app.epsilon * c_stability * p * group_size as f64,
                     delta: app.delta * c_stability * p * ((group_size as f64 * app.epsilon).exp() - 1.) / (app.epsilon.exp() - 1.),
-                })
+                }),
+                proto::privacy_usage::Distance::Concentrated(conc) => {
+                    if p != 1. {
+                        return Err("privacy amplification by subsampling is not supported for concentrated privacy usages".into())
+                    }
+                    proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                        rho: conc.rho * (c_stability * group_size as f64).powi(2)
+                    })
+                }
             })
         })
     }
 }
 
-
 impl Add<proto::PrivacyUsage> for proto::PrivacyUsage {
     type Output = Result<proto::PrivacyUsage>;
 
-    fn add(mut self, rhs: proto::PrivacyUsage) -> Self::Output {
-        let left_distance = self.distance.ok_or_else(|| "distance must be defined")?;
-        let right_distance = rhs.distance.ok_or_else(|| "distance must be defined")?;
-
+    fn add(self, rhs: proto::PrivacyUsage) -> Self::Output {
         use proto::privacy_usage::Distance;
 
-        self.distance = Some(match (left_distance, right_distance) {
+        // a pure usage is converted to the concentrated usage it implies
+        let (mut lhs, rhs) = match (&self.distance, &rhs.distance) {
+            (Some(Distance::Approximate(_)), Some(Distance::Concentrated(_))) => (pure_to_concentrated(&self)?, rhs),
+            (Some(Distance::Concentrated(_)), Some(Distance::Approximate(_))) => (self, pure_to_concentrated(&rhs)?),
+            _ => (self, rhs)
+        };
+
+        let left_distance = lhs.distance.ok_or_else(|| "distance must be defined")?;
+        let right_distance = rhs.distance.ok_or_else(|| "distance must be defined")?;
+
+        lhs.distance = Some(match (left_distance, right_distance) {
             (Distance::Approximate(lhs), Distance::Approximate(rhs)) => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
                 epsilon: lhs.epsilon + rhs.epsilon,
                 delta: lhs.delta + rhs.delta,
-            })
+            }),
+            (Distance::Concentrated(lhs), Distance::Concentrated(rhs)) => proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho: lhs.rho + rhs.rho
+            }),
+            _ => return Err("privacy usages must share a distance".into())
         });
-        Ok(self)
+        Ok(lhs)
     }
 }
 
@@ -1152,6 +1227,9 @@ impl Mul<f64> for proto::PrivacyUsage {
             proto::privacy_usage::Distance::Approximate(approximate) => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
                 epsilon: approximate.epsilon * rhs,
                 delta: approximate.delta * rhs,
+            }),
+            proto::privacy_usage::Distance::Concentrated(concentrated) => proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho: concentrated.rho * rhs
             })
         });
         Ok(self)
@@ -1166,6 +1244,9 @@ impl Div<f64> for proto::PrivacyUsage {
             proto::privacy_usage::Distance::Approximate(approximate) => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
                 epsilon: approximate.epsilon / rhs,
                 delta: approximate.delta / rhs,
+            }),
+            proto::privacy_usage::Distance::Concentrated(concentrated) => proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho: concentrated.rho / rhs
             })
         });
         Ok(self)
@@ -1260,4 +1341,31 @@ pub mod test_data {
             [true, true, true],
         ]).into()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_privacy_usage {
+    use crate::proto;
+
+    #[test]
+    fn test_group_privacy_round_trip() {
+        let usage = proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 1e-6
+            }))
+        };
+
+        for group_size in 1..4 {
+            let round_trip = usage.actual_to_effective(1., 2., group_size).unwrap()
+                .effective_to_actual(1., 2., group_size).unwrap();
+
+            match round_trip.distance.unwrap() {
+                proto::privacy_usage::Distance::Approximate(approximate) => {
+                    assert!((approximate.epsilon - 1.).abs() < 1e-10);
+                    assert!((approximate.delta - 1e-6).abs() < 1e-16);
+                },
+                _ => panic!("expected an approximate privacy usage")
+            }
+        }
+    }
+}
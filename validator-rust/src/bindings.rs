@@ -49,7 +49,12 @@ impl Analysis {
                 protect_overflow: false,
                 protect_elapsed_time: false,
                 protect_memory_utilization: false,
-                protect_floating_point: false
+                protect_floating_point: false,
+                composition: proto::privacy_definition::Composition::Basic as i32,
+                composition_slack_delta: 0.,
+                concentrated_conversion_delta: 0.,
+                elapsed_time_nanos_per_record: 0,
+                verify_properties: false
             },
             components: HashMap::new(),
             component_count: 0,
@@ -0,0 +1,206 @@
+use crate::errors::*;
+
+use crate::components::{Sensitivity, Mechanism};
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, Array, SensitivitySpace, ValueProperties, DataType, ArrayProperties, NodeProperties, IndexKey};
+use crate::utilities::{prepend, get_literal, get_argument};
+use crate::utilities::privacy::privacy_usage_check;
+use crate::utilities::inference::infer_property;
+use itertools::Itertools;
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::AboveThreshold {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. The sparse vector technique is susceptible to floating-point attacks.".into())
+        }
+
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into())
+        }
+
+        if self.cutoff == 0 {
+            return Err("cutoff: must be greater than zero".into())
+        }
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into());
+        }
+
+        match get_argument(&public_arguments, "threshold")?.ref_array()? {
+            Array::Float(threshold) if threshold.len() == 1 => (),
+            Array::Int(threshold) if threshold.len() == 1 => (),
+            _ => return Err("threshold: must be a numeric scalar".into())
+        };
+
+        let aggregator = data_property.aggregator.clone()
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        // sensitivity must be computable
+        aggregator.component.compute_sensitivity(
+            privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(1))?.array()?.float()?;
+
+        // make sure lipschitz constants is available as a float array
+        aggregator.lipschitz_constants.array()?.float()?;
+
+        // the usage is shared by all queries, so it is not spread over the columns
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or("privacy_usage: must be defined")??;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        Ok(Warnable(ArrayProperties {
+            // the number of reported indices is private
+            num_records: None,
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Int,
+            dataset_id: None,
+            is_not_empty: false,
+            dimensionality: Some(1),
+            group_id: vec![],
+            naturally_ordered: true,
+            randomized_response: None
+        }.into(), warnings))
+    }
+}
+
+impl Expandable for proto::AboveThreshold {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy definition must be defined")?;
+
+        // always overwrite sensitivity. This is not something a user may configure
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let aggregator = data_property.aggregator
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        let mut sensitivity = aggregator.component.compute_sensitivity(
+            privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(1))?.array()?.float()?;
+        let lipschitz = aggregator.lipschitz_constants.array()?.float()?;
+        if lipschitz.iter().any(|v| v != &1.) {
+            sensitivity *= &lipschitz;
+        }
+
+        // every query shares the noisy threshold, so the largest query sensitivity bounds all queries
+        let sensitivity = sensitivity.iter().cloned().fold(0., f64::max);
+
+        maximum_id += 1;
+        let id_sensitivity = maximum_id;
+        let (patch_node, release) = get_literal(sensitivity.into(), component.submission)?;
+        expansion.computation_graph.insert(id_sensitivity, patch_node);
+        expansion.properties.insert(id_sensitivity, infer_property(&release.value, None)?);
+        expansion.releases.insert(id_sensitivity, release);
+
+        // reduce the usage allowed to the algorithm based on the least stable query and group size
+        let c_stability = data_property.c_stability.iter().cloned().fold(1., f64::max);
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or("privacy_usage: must be defined")??
+            .actual_to_effective(1., c_stability, privacy_definition.group_size)?;
+
+        let mut noise_component = component.clone();
+        noise_component.insert_argument(&"sensitivity".into(), id_sensitivity);
+        if let Some(proto::component::Variant::AboveThreshold(variant)) = noise_component.variant.as_mut() {
+            variant.privacy_usage = vec![privacy_usage];
+        }
+        expansion.computation_graph.insert(component_id, noise_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::AboveThreshold {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // a single usage is charged, regardless of the number of queries
+        let c_stability = data_property.c_stability.iter().cloned().fold(1., f64::max);
+        release_usage.unwrap_or(&self.privacy_usage).iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?)
+            .map(|usage| Ok(vec![usage?.effective_to_actual(1., c_stability, privacy_definition.group_size)?]))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test_above_threshold {
+    use crate::bindings::Analysis;
+    use crate::proto;
+    use ndarray::Array2;
+
+    fn analysis_above_threshold(num_queries: usize) -> Analysis {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_elem((10, num_queries), 1.).into_dyn().into())
+            .value_public(true).build();
+        let threshold = analysis.literal().value(5.into()).value_public(true).build();
+        let count = analysis.count(data).build();
+        analysis.above_threshold(count, threshold, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).cutoff(2).build();
+        analysis
+    }
+
+    #[test]
+    fn test_usage_independent_of_queries() {
+        let usages = [1, 20].iter().map(|num_queries| {
+            let analysis = analysis_above_threshold(*num_queries);
+            crate::compute_privacy_usage(
+                analysis.privacy_definition, analysis.components, analysis.release).unwrap().0
+        }).collect::<Vec<proto::PrivacyUsage>>();
+
+        assert_eq!(usages[0], usages[1]);
+        assert_eq!(usages[0].distance, Some(proto::privacy_usage::Distance::Approximate(
+            proto::privacy_usage::DistanceApproximate { epsilon: 1., delta: 0. })));
+    }
+}
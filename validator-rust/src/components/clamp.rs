@@ -106,19 +106,21 @@ impl Component for proto::Clamp {
                     return Err("lower is greater than upper".into());
                 }
 
-                // the actual data bound (if it exists) may be tighter than the clamping parameters
+                // the actual data bound (if it exists) may be tighter than the clamping parameters.
+                // Data bounds are clamped too, as they may lie outside of the clamping parameters
+                let (parameter_lower, parameter_upper) = (clamp_lower.clone(), clamp_upper.clone());
                 if let Ok(data_minimum) = data_property.lower_float_option() {
-                    clamp_lower = clamp_lower.into_iter().zip(data_minimum)
+                    clamp_lower = clamp_lower.into_iter().zip(data_minimum).zip(parameter_upper.iter())
                         // match on if the actual bound exists for each column, and remain conservative if not
-                        .map(|(clamp_lower, optional_data_lower)| match optional_data_lower {
-                            Some(data_lower) => clamp_lower.max(data_lower), // tighter data bound is only applied here
+                        .map(|((clamp_lower, optional_data_lower), clamp_upper)| match optional_data_lower {
+                            Some(data_lower) => data_lower.clamp(clamp_lower, *clamp_upper), // tighter data bound is only applied here
                             None => clamp_lower
                         }).collect()
                 }
                 if let Ok(data_upper) = data_property.upper_float_option() {
-                    clamp_upper = clamp_upper.into_iter().zip(data_upper)
-                        .map(|(clamp_upper, optional_data_upper)| match optional_data_upper {
-                            Some(data_upper) => clamp_upper.min(data_upper),
+                    clamp_upper = clamp_upper.into_iter().zip(data_upper).zip(parameter_lower.iter())
+                        .map(|((clamp_upper, optional_data_upper), clamp_lower)| match optional_data_upper {
+                            Some(data_upper) => data_upper.clamp(*clamp_lower, clamp_upper),
                             None => clamp_upper
                         }).collect()
                 }
@@ -168,19 +170,21 @@ impl Component for proto::Clamp {
                     return Err("lower is greater than upper".into());
                 }
 
-                // the actual data bound (if it exists) may be tighter than the clamping parameters
+                // the actual data bound (if it exists) may be tighter than the clamping parameters.
+                // Data bounds are clamped too, as they may lie outside of the clamping parameters
+                let (parameter_lower, parameter_upper) = (clamp_lower.clone(), clamp_upper.clone());
                 if let Ok(data_lower) = data_property.lower_int_option() {
-                    clamp_lower = clamp_lower.into_iter().zip(data_lower)
+                    clamp_lower = clamp_lower.into_iter().zip(data_lower).zip(parameter_upper.iter())
                         // match on if the actual bound exists for each column, and remain conservative if not
-                        .map(|(clamp_lower, optional_data_lower)| match optional_data_lower {
-                            Some(data_lower) => clamp_lower.max(data_lower), // tighter data bound is only applied here
+                        .map(|((clamp_lower, optional_data_lower), clamp_upper)| match optional_data_lower {
+                            Some(data_lower) => data_lower.clamp(clamp_lower, *clamp_upper), // tighter data bound is only applied here
                             None => clamp_lower
                         }).collect()
                 }
                 if let Ok(data_upper) = data_property.upper_int_option() {
-                    clamp_upper = clamp_upper.into_iter().zip(data_upper)
-                        .map(|(clamp_upper, optional_data_upper)| match optional_data_upper {
-                            Some(data_upper) => clamp_upper.min(data_upper),
+                    clamp_upper = clamp_upper.into_iter().zip(data_upper).zip(parameter_lower.iter())
+                        .map(|((clamp_upper, optional_data_upper), clamp_lower)| match optional_data_upper {
+                            Some(data_upper) => data_upper.clamp(*clamp_lower, clamp_upper),
                             None => clamp_upper
                         }).collect()
                 }
@@ -432,4 +436,17 @@ pub mod test_clamp {
         array1d_bool_0,
         array1d_bool_10_uniform,
     );
+    #[test]
+    fn test_data_bounds_outside_parameters() {
+        // the data is known to lie in [20, 30], above the clamping parameters
+        let (mut analysis, clamped) = utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), Some(20.0.into()), Some(30.0.into()));
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let reclamped = analysis.clamp(clamped).lower(lower).upper(upper).build();
+
+        let property = analysis.properties(reclamped).unwrap().array().unwrap().clone();
+        assert_eq!(property.lower_float().unwrap(), vec![10.]);
+        assert_eq!(property.upper_float().unwrap(), vec![10.]);
+    }
 }
@@ -37,6 +37,7 @@ impl Component for proto::Count {
             data_property.assert_is_not_aggregated()?;
         }
 
+        let data_num_records = data_property.num_records.map(|v| v as Integer);
         data_property.num_records = Some(1);
         data_property.num_columns = Some(1);
 
@@ -70,9 +71,9 @@ impl Component for proto::Count {
         });
         data_property.c_stability = c_stability;
 
-        let data_num_records = data_property.num_records.map(|v| v as Integer);
+        // a distinct count may be smaller than the number of records
         data_property.nature = Some(Nature::Continuous(NatureContinuous {
-            lower: Vector1DNull::Int(vec![data_num_records.or(Some(0))]),
+            lower: Vector1DNull::Int(vec![if self.distinct { Some(0) } else { data_num_records.or(Some(0)) }]),
             upper: Vector1DNull::Int(vec![data_num_records]),
         }));
         data_property.data_type = DataType::Int;
@@ -4,7 +4,7 @@ use crate::errors::*;
 use crate::{proto, base, Warnable, Float};
 
 use crate::components::{Component, Sensitivity};
-use crate::base::{IndexKey, Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType};
+use crate::base::{IndexKey, Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, ArrayProperties, Nature, NatureContinuous, Vector1DNull};
 use crate::utilities::prepend;
 use ndarray::prelude::*;
 use indexmap::map::IndexMap;
@@ -47,14 +47,21 @@ impl Component for proto::Covariance {
                     (0..num_columns).map(|_| 1.).collect())?.into_dyn().into()
             });
 
-            data_property.num_records = Some(1);
             data_property.num_columns = Some(num_columns);
 
             if data_property.data_type != DataType::Float {
                 return Err("data: atomic type must be float".into());
             }
-            // min/max of data is not known after computing covariance
-            data_property.nature = None;
+
+            let ranges = get_ranges(&data_property);
+            data_property.nature = covariance_nature(
+                ranges.as_ref().map(|ranges| ranges.iter().enumerate()
+                    .flat_map(|(i, l_range)| ranges.iter().enumerate()
+                        .filter(move |(j, _)| i <= *j)
+                        .map(move |(_, r_range)| l_range * r_range))
+                    .collect()),
+                self.finite_sample_correction, data_property.num_records);
+            data_property.num_records = Some(1);
             data_property.dataset_id = Some(node_id as i64);
             Ok(ValueProperties::Array(data_property).into())
         } else if properties.contains_key::<IndexKey>(&"left".into()) && properties.contains_key::<IndexKey>(&"right".into()) {
@@ -99,7 +106,13 @@ impl Component for proto::Covariance {
                     (0..num_columns).map(|_| 1.).collect())?.into_dyn().into()
             });
 
-            left_property.nature = None;
+            left_property.nature = covariance_nature(
+                get_ranges(&left_property).and_then(|l_ranges| get_ranges(&right_property)
+                    .map(|r_ranges| l_ranges.iter()
+                        .flat_map(|l_range| r_ranges.iter().map(move |r_range| l_range * r_range))
+                        .collect())),
+                self.finite_sample_correction,
+                left_property.num_records.filter(|n| Some(*n) == right_property.num_records));
             left_property.releasable = left_property.releasable && right_property.releasable;
 
             left_property.num_records = Some(1);
@@ -112,6 +125,34 @@ impl Component for proto::Covariance {
     }
 }
 
+/// Width of the interval containing each column of the data.
+fn get_ranges(property: &ArrayProperties) -> Option<Vec<Float>> {
+    Some(property.lower_float().ok()?.iter()
+        .zip(property.upper_float().ok()?.iter())
+        .map(|(l, u)| u - l).collect())
+}
+
+/// Bounds on covariances, given the products of the widths of the intervals containing each pair of columns.
+///
+/// By the Cauchy-Schwarz inequality, and the bound on variance of (upper - lower)^2 / 4,
+/// each covariance is at most the product of the widths divided by four.
+fn covariance_nature(
+    range_products: Option<Vec<Float>>,
+    finite_sample_correction: bool,
+    num_records: Option<i64>
+) -> Option<Nature> {
+    let correction = if finite_sample_correction {
+        num_records.filter(|n| *n > 1).map(|n| n as Float / (n - 1) as Float)?
+    } else { 1. };
+    let bounds = range_products?.into_iter()
+        .map(|product| product / 4. * correction).collect::<Vec<Float>>();
+
+    Some(Nature::Continuous(NatureContinuous {
+        lower: Vector1DNull::Float(bounds.iter().map(|bound| Some(-bound)).collect()),
+        upper: Vector1DNull::Float(bounds.into_iter().map(Some).collect())
+    }))
+}
+
 impl Sensitivity for proto::Covariance {
     /// Covariance sensitivities [are backed by the the proofs here](https://github.com/opendifferentialprivacy/whitenoise-core/blob/955703e3d80405d175c8f4642597ccdf2c00332a/whitepapers/sensitivities/covariance/covariance.pdf).
     fn compute_sensitivity(
@@ -0,0 +1,131 @@
+use crate::errors::*;
+
+use crate::components::{Sensitivity, Mechanism};
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, SensitivitySpace, ValueProperties, DataType, NodeProperties, IndexKey};
+use crate::utilities::{prepend, expand_mechanism};
+use crate::utilities::privacy::{get_rho, privacy_usage_check, gaussian_rdp_curve, approximate_to_concentrated_for_delta};
+use itertools::Itertools;
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::DiscreteGaussianMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into());
+        }
+
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be integer".into());
+        }
+        let aggregator = data_property.aggregator.clone()
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        // sensitivity must be computable
+        aggregator.component.compute_sensitivity(
+            privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(2))?.array()?.float()?;
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or("privacy_usage: must be defined")??;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        // approximate usages are satisfied via the rho-zCDP usage that implies them
+        if let Some(proto::privacy_usage::Distance::Approximate(_)) = privacy_usage.distance {
+            approximate_to_concentrated_for_delta(&privacy_usage)?;
+        }
+
+        data_property.releasable = true;
+        data_property.aggregator = None;
+        // noise may move the release outside the bounds of the aggregate
+        data_property.nature = None;
+
+        Ok(Warnable(data_property.into(), warnings))
+    }
+}
+
+impl Expandable for proto::DiscreteGaussianMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        expand_mechanism(
+            &SensitivitySpace::KNorm(2),
+            privacy_definition,
+            self.privacy_usage.as_ref(),
+            component,
+            properties,
+            component_id,
+            maximum_id,
+        )
+    }
+}
+
+impl Mechanism for proto::DiscreteGaussianMechanism {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .zip(data_property.c_stability.iter())
+            .map(|(usage, c_stab)|
+                usage.effective_to_actual(1., *c_stab, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+
+    fn get_rdp_curves(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<Vec<f64>>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // the discrete gaussian satisfies the same zCDP bound as the continuous gaussian of equal variance,
+        //    and the sensitivity grows with the c-stability and group size
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .zip(data_property.c_stability.iter())
+            .map(|(usage, c_stab)| {
+                let rho = match usage.distance.as_ref().ok_or("distance must be defined")? {
+                    proto::privacy_usage::Distance::Approximate(_) => get_rho(&approximate_to_concentrated_for_delta(usage)?)?,
+                    proto::privacy_usage::Distance::Concentrated(concentrated) => concentrated.rho
+                };
+                Ok(gaussian_rdp_curve(rho * (*c_stab * privacy_definition.group_size as f64).powi(2)))
+            })
+            .collect::<Result<Vec<Vec<f64>>>>()).transpose()
+    }
+}
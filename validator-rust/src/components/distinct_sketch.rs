@@ -0,0 +1,159 @@
+use crate::errors::*;
+
+
+use crate::{proto, Warnable, base, Float, Integer};
+
+use crate::components::{Component, Sensitivity};
+use crate::base::{IndexKey, Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, ArrayProperties, DataType, NatureContinuous, Nature, Vector1DNull};
+use crate::utilities::{get_common_value, prepend};
+use crate::utilities::privacy::get_c_stability_multiplier;
+use ndarray::Array;
+use itertools::Itertools;
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::DistinctSketch {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+
+        if self.size <= 0 {
+            return Err("size: must be positive".into())
+        }
+
+        let (mut data_property, c_stability) = get_data_properties(&properties)?;
+        let num_columns = data_property.num_columns()?;
+
+        data_property.num_records = Some(1);
+
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties {
+            component: proto::component::Variant::DistinctSketch(self.clone()),
+            properties,
+            lipschitz_constants: Array::from_shape_vec(
+                vec![1, num_columns as usize],
+                (0..num_columns).map(|_| 1.).collect())?.into_dyn().into()
+        });
+        data_property.c_stability = c_stability;
+
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Int((0..num_columns).map(|_| Some(0)).collect()),
+            upper: Vector1DNull::Int((0..num_columns).map(|_| Some(self.size as Integer)).collect()),
+        }));
+        data_property.data_type = DataType::Int;
+        data_property.dataset_id = Some(node_id as i64);
+        data_property.group_id = vec![];
+        data_property.naturally_ordered = true;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Sensitivity for proto::DistinctSketch {
+    fn compute_sensitivity(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace
+    ) -> Result<Value> {
+
+        let (_data_property, c_stability) = get_data_properties(properties)?;
+
+        match sensitivity_type {
+            SensitivitySpace::KNorm(_k) => {
+                // k has no effect on the sensitivity, and is ignored
+
+                // Adding or removing a record occupies or frees at most one bit in the sketch of each column.
+                // Substituting a record may free one bit and occupy another,
+                //     so the number of occupied bits still changes by at most one.
+                // Unlike an exact distinct count, this holds whether or not N is known.
+                Ok(Array::from_shape_vec(vec![1, c_stability.len()], c_stability)?.into_dyn().into())
+            },
+            _ => Err("DistinctSketch sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
+
+/// Properties of the sketched data, and the c-stability of each column.
+///
+/// Partitions are sketched separately and merged, so each column is as stable as the least stable partition.
+fn get_data_properties(properties: &NodeProperties) -> Result<(ArrayProperties, Vec<Float>)> {
+    match properties.get::<IndexKey>(&"data".into()).ok_or("data: missing")? {
+        ValueProperties::Array(data_property) => {
+            data_property.assert_is_not_aggregated()?;
+            Ok((data_property.clone(), data_property.c_stability.clone()))
+        },
+        ValueProperties::Partitions(partitions_property) => {
+            let partition_properties = partitions_property.children.values()
+                .map(|v| v.array()).collect::<Result<Vec<&ArrayProperties>>>()
+                .map_err(prepend("data:"))?;
+
+            partition_properties.iter()
+                .try_for_each(|v| v.assert_is_not_aggregated())?;
+
+            get_common_value(&partition_properties.iter().map(|v| v.num_columns).collect())
+                .ok_or("data: partitions must have the same number of columns")?;
+            get_common_value(&partition_properties.iter().map(|v| v.data_type.clone()).collect())
+                .ok_or("data: partitions must have the same atomic type")?;
+
+            let c_stab_mult = get_c_stability_multiplier(
+                partition_properties.iter().map(|v| v.group_id.clone()).collect())?;
+
+            let c_stability = partition_properties.iter()
+                .map(|v| v.c_stability.clone())
+                .fold1(|l, r| l.iter().zip(r).map(|(l, r)| l.max(r)).collect::<Vec<Float>>())
+                .ok_or("data: must have at least one partition")?.into_iter()
+                .map(|c_stab| c_stab * c_stab_mult)
+                .collect();
+
+            let mut data_property = (*partition_properties.first().unwrap()).clone();
+            data_property.releasable = partition_properties.iter().all(|v| v.releasable);
+            Ok((data_property, c_stability))
+        },
+        _ => Err("data: must be an array or partitions".into())
+    }
+}
+
+#[cfg(test)]
+mod test_distinct_sketch {
+    use crate::proto;
+    use crate::base::{Value, IndexKey, ValueProperties, PartitionsProperties, SensitivitySpace};
+    use crate::components::{Component, Sensitivity};
+    use crate::utilities::inference::infer_property;
+    use ndarray::Array2;
+
+    #[test]
+    fn test_partitions_sensitivity() {
+        let privacy_definition = proto::PrivacyDefinition {
+            group_size: 1,
+            ..Default::default()
+        };
+        let partition: Value = Array2::from_shape_fn((10, 2), |(i, j)| (i * j) as i64).into_dyn().into();
+        let mut partition_property = infer_property(&partition, None).unwrap();
+        if let ValueProperties::Array(array_property) = &mut partition_property {
+            array_property.releasable = false;
+        }
+
+        let properties = indexmap![IndexKey::from("data") => ValueProperties::Partitions(PartitionsProperties {
+            children: indexmap![
+                IndexKey::from(0) => partition_property.clone(),
+                IndexKey::from(1) => partition_property
+            ]
+        })];
+
+        let sketch = proto::DistinctSketch { size: 64 };
+        let aggregated = sketch.propagate_property(
+            &Some(privacy_definition.clone()), indexmap![], properties.clone(), 0).unwrap().0;
+        let aggregated = aggregated.array().unwrap();
+        assert_eq!(aggregated.num_columns, Some(2));
+        assert_eq!(aggregated.upper_int().unwrap(), vec![64, 64]);
+
+        let sensitivity = sketch.compute_sensitivity(
+            &privacy_definition, &properties, &SensitivitySpace::KNorm(1)).unwrap();
+        assert_eq!(sensitivity.array().unwrap().float().unwrap().shape(), &[1, 2]);
+    }
+}
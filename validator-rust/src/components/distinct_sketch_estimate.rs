@@ -0,0 +1,40 @@
+use crate::errors::*;
+
+use crate::{proto, base, Warnable};
+
+use crate::components::Component;
+use crate::base::{IndexKey, Value, ValueProperties, DataType};
+use crate::utilities::prepend;
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::DistinctSketchEstimate {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+
+        if self.size <= 0 {
+            return Err("size: must be positive".into())
+        }
+
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            return Err("data: must be public".into())
+        }
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        data_property.data_type = DataType::Int;
+        data_property.nature = None;
+
+        Ok(Warnable::new(data_property.into()))
+    }
+}
@@ -2,7 +2,7 @@ use crate::errors::*;
 
 
 use crate::{proto, base, Integer};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 use ndarray::arr0;
 
 use crate::base::{IndexKey, NodeProperties, Value, ValueProperties};
@@ -27,6 +27,14 @@ impl Expandable for proto::DpCount {
         let privacy_definition = privacy_definition.as_ref()
             .ok_or_else(|| Error::from("privacy_definition must be known"))?;
 
+        if self.sketch_size < 0 {
+            return Err("sketch_size: must be non-negative".into())
+        }
+        if self.sketch_size > 0 && !self.distinct {
+            return Err("sketch_size: only applies to distinct counts".into())
+        }
+        let sketch = self.sketch_size > 0;
+
         // count
         maximum_id += 1;
         let id_count = maximum_id;
@@ -35,14 +43,29 @@ impl Expandable for proto::DpCount {
                 "data".into() => *component.arguments().get(&IndexKey::from("data"))
                     .ok_or_else(|| Error::from("data must be provided as an argument"))?
             ])),
-            variant: Some(proto::component::Variant::Count(proto::Count {
-                distinct: self.distinct
-            })),
+            variant: Some(if sketch {
+                proto::component::Variant::DistinctSketch(proto::DistinctSketch {
+                    size: self.sketch_size
+                })
+            } else {
+                proto::component::Variant::Count(proto::Count {
+                    distinct: self.distinct
+                })
+            }),
             omit: true,
             submission: component.submission,
         });
         expansion.traversal.push(id_count);
 
+        // when sketching, the noised number of occupied bits is post-processed into an estimate
+        let (id_noised, omit_noised) = if sketch {
+            maximum_id += 1;
+            expansion.traversal.push(maximum_id);
+            (maximum_id, true)
+        } else {
+            (component_id, component.omit)
+        };
+
         if self.mechanism.to_lowercase().as_str() == "simplegeometric" {
 
             let count_min_id = match component.arguments().get::<IndexKey>(&"lower".into()) {
@@ -60,6 +83,16 @@ impl Expandable for proto::DpCount {
             };
 
             let count_max_id = match component.arguments().get::<IndexKey>(&"upper".into()) {
+                // the number of occupied bits is at most the size of the sketch
+                None if sketch => {
+                    maximum_id += 1;
+                    let id_count_max = maximum_id;
+                    let (patch_node, count_max_release) = get_literal(arr0(self.sketch_size as Integer).into_dyn().into(), component.submission)?;
+                    expansion.computation_graph.insert(id_count_max, patch_node);
+                    expansion.properties.insert(id_count_max, infer_property(&count_max_release.value, None)?);
+                    expansion.releases.insert(id_count_max, count_max_release);
+                    id_count_max
+                }
                 None => {
                     let num_records = match properties.get::<IndexKey>(&"data".into())
                         .ok_or("data: missing")? {
@@ -89,7 +122,7 @@ impl Expandable for proto::DpCount {
             };
 
             // noising
-            expansion.computation_graph.insert(component_id, proto::Component {
+            expansion.computation_graph.insert(id_noised, proto::Component {
                 variant: Some(proto::component::Variant::SimpleGeometricMechanism(proto::SimpleGeometricMechanism {
                     privacy_usage: self.privacy_usage.clone()
                 })),
@@ -98,12 +131,12 @@ impl Expandable for proto::DpCount {
                     "lower".into() => count_min_id,
                     "upper".into() => count_max_id
                 ])),
-                omit: component.omit,
+                omit: omit_noised,
                 submission: component.submission,
             });
         } else {
             // noising
-            expansion.computation_graph.insert(component_id, proto::Component {
+            expansion.computation_graph.insert(id_noised, proto::Component {
                 arguments: Some(proto::ArgumentNodeIds::new(
                     indexmap!["data".into() => id_count])),
                 variant: Some(match self.mechanism.to_lowercase().as_str() {
@@ -111,10 +144,30 @@ impl Expandable for proto::DpCount {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: false
+                    }),
+                    "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: true
+                    }),
+                    "discretegaussian" => proto::component::Variant::DiscreteGaussianMechanism(proto::DiscreteGaussianMechanism {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     _ => panic!("Unexpected invalid token {:?}", self.mechanism.as_str()),
                 }),
+                omit: omit_noised,
+                submission: component.submission,
+            });
+        }
+
+        if sketch {
+            expansion.computation_graph.insert(component_id, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(
+                    indexmap!["data".into() => id_noised])),
+                variant: Some(proto::component::Variant::DistinctSketchEstimate(proto::DistinctSketchEstimate {
+                    size: self.sketch_size
+                })),
                 omit: component.omit,
                 submission: component.submission,
             });
@@ -127,7 +180,7 @@ impl Expandable for proto::DpCount {
 impl Report for proto::DpCount {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         _properties: NodeProperties,
@@ -144,14 +197,15 @@ impl Report for proto::DpCount {
             privacy_loss: privacy_usage_to_json(&self.privacy_usage[0].clone()),
             accuracy: None,
             submission: component.submission,
-            node_id,
+            node_id: context.node_id,
             postprocess: false,
             algorithm_info: AlgorithmInfo {
                 name: "".to_string(),
                 cite: "".to_string(),
                 mechanism: self.mechanism.clone(),
                 argument: serde_json::json!({
-                    "distinct": self.distinct
+                    "distinct": self.distinct,
+                    "sketch_size": self.sketch_size
                 }),
             },
         }]))
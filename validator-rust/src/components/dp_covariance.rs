@@ -1,7 +1,7 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 
 
 use crate::base::{IndexKey, NodeProperties, Value};
@@ -82,6 +82,14 @@ impl Expandable for proto::DpCovariance {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: false
+                }),
+                "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: true
+                }),
+                "snapping" => proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 _x => panic!("Unexpected invalid token {:?}", self.mechanism.as_str()),
@@ -110,7 +118,7 @@ impl Expandable for proto::DpCovariance {
 impl Report for proto::DpCovariance {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -169,7 +177,7 @@ impl Report for proto::DpCovariance {
             privacy_loss: serde_json::json![privacy_usage],
             accuracy: None,
             submission: component.submission,
-            node_id,
+            node_id: context.node_id,
             postprocess: false,
             algorithm_info: AlgorithmInfo {
                 name: "".to_string(),
@@ -2,7 +2,7 @@ use crate::errors::*;
 
 
 use crate::{proto, base, Integer};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 use ndarray::{arr0};
 
 use crate::base::{NodeProperties, Value, IndexKey};
@@ -115,6 +115,14 @@ impl Expandable for proto::DpHistogram {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: false
+                    }),
+                    "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: true
+                    }),
+                    "discretegaussian" => proto::component::Variant::DiscreteGaussianMechanism(proto::DiscreteGaussianMechanism {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     _ => panic!("Unexpected invalid token {:?}", self.mechanism.as_str()),
@@ -131,7 +139,7 @@ impl Expandable for proto::DpHistogram {
 impl Report for proto::DpHistogram {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -165,7 +173,7 @@ impl Report for proto::DpHistogram {
                     privacy_loss: privacy_usage_to_json(&privacy_usage),
                     accuracy: None,
                     submission: component.submission,
-                    node_id,
+                    node_id: context.node_id,
                     postprocess: false,
                     algorithm_info: AlgorithmInfo {
                         name: "".to_string(),
@@ -0,0 +1,182 @@
+use crate::errors::*;
+
+use crate::components::Mechanism;
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, ValueProperties, DataType, DataframeProperties, JaggedProperties, NodeProperties, IndexKey};
+use crate::utilities::prepend;
+use crate::utilities::privacy::{spread_privacy_usage, privacy_usage_check};
+use itertools::Itertools;
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::DpKeySelection {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. Key selection adds floating-point noise to category counts.".into())
+        }
+
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into())
+        }
+
+        if !["laplace", "gaussian"].contains(&self.mechanism.to_lowercase().as_str()) {
+            return Err("mechanism: must be one of [Laplace, Gaussian]".into())
+        }
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        if data_property.data_type == DataType::Float {
+            return Err("data: categories may not be floats".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or("privacy_usage: must be defined")??;
+
+        // categories absent from neighboring datasets are only suppressed with probability 1 - delta
+        match privacy_usage.distance.as_ref().ok_or("privacy_usage: distance must be defined")? {
+            proto::privacy_usage::Distance::Approximate(approximate) => if approximate.delta <= 0. {
+                return Err("privacy_usage: delta must be positive for key selection".into())
+            },
+            _ => return Err("privacy_usage: key selection requires an approximate privacy usage".into())
+        }
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        // the number of released categories in each column is private
+        let released_property = |data_type: DataType| JaggedProperties {
+            num_records: None,
+            nullity: false,
+            aggregator: None,
+            nature: None,
+            data_type,
+            releasable: true
+        }.into();
+
+        Ok(Warnable(DataframeProperties {
+            children: indexmap![
+                IndexKey::from("categories") => released_property(data_property.data_type),
+                IndexKey::from("counts") => released_property(DataType::Float)
+            ]
+        }.into(), warnings))
+    }
+}
+
+impl Expandable for proto::DpKeySelection {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        _maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy definition must be defined")?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // spread privacy usage over each column, then reduce it based on c-stability, neighboring and group size
+        let stabilities = get_key_stabilities(privacy_definition, &data_property.c_stability)?;
+        let effective_usages = spread_privacy_usage(&self.privacy_usage, stabilities.len())?.into_iter()
+            .zip(stabilities)
+            .map(|(usage, stability)| usage.actual_to_effective(1., stability, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+
+        let mut component = component.clone();
+        if let Some(proto::component::Variant::DpKeySelection(variant)) = component.variant.as_mut() {
+            variant.privacy_usage = effective_usages;
+        }
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::DpKeySelection {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let stabilities = get_key_stabilities(privacy_definition, &data_property.c_stability)?;
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .zip(stabilities)
+            .map(|(usage, stability)|
+                usage.effective_to_actual(1., stability, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+/// The key selection algorithms are private when neighboring datasets differ by the addition or removal of one record.
+/// A substitution is a removal followed by an addition, so it is accounted for as a group of two.
+fn get_key_stabilities(privacy_definition: &proto::PrivacyDefinition, c_stability: &[f64]) -> Result<Vec<f64>> {
+    let neighboring = proto::privacy_definition::Neighboring::from_i32(privacy_definition.neighboring)
+        .ok_or("neighboring must be defined")?;
+    let factor = match neighboring {
+        proto::privacy_definition::Neighboring::AddRemove => 1.,
+        proto::privacy_definition::Neighboring::Substitute => 2.
+    };
+    Ok(c_stability.iter().map(|c_stab| c_stab * factor).collect())
+}
+
+#[cfg(test)]
+mod test_dp_key_selection {
+    use crate::bindings::Analysis;
+    use crate::proto;
+    use crate::base::test_data;
+    use crate::components::cast::test_cast;
+
+    fn analysis_key_selection(epsilon: f64, delta: f64, neighboring: proto::privacy_definition::Neighboring) -> Analysis {
+        let (mut analysis, casted) = test_cast::utilities::analysis_i64(
+            test_data::array1d_i64_10_uniform(), Some(0.into()), Some(10.into()));
+        analysis.privacy_definition.neighboring = neighboring as i32;
+        analysis.dp_key_selection(casted, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon, delta
+            }))
+        }]).build();
+        analysis
+    }
+
+    #[test]
+    fn test_delta_required() {
+        use proto::privacy_definition::Neighboring;
+        let analysis = analysis_key_selection(1., 0., Neighboring::AddRemove);
+        assert!(crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).is_err());
+
+        let analysis = analysis_key_selection(1., 1e-6, Neighboring::AddRemove);
+        let (usage, _) = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        assert_eq!(usage.distance, Some(proto::privacy_usage::Distance::Approximate(
+            proto::privacy_usage::DistanceApproximate { epsilon: 1., delta: 1e-6 })));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::errors::*;
+
+use crate::{proto, base};
+use crate::components::{Component, Expandable, Report, ReportContext, Accuracy};
+
+use crate::base::{IndexKey, NodeProperties, Value, ValueProperties};
+use crate::utilities::json::{JSONRelease, value_to_json, AlgorithmInfo, privacy_usage_to_json};
+use crate::utilities::prepend;
+use crate::utilities::privacy::spread_privacy_usage;
+use indexmap::map::IndexMap;
+
+
+impl Expandable for proto::DpLinearRegression {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+
+        let mut expansion = base::ComponentExpansion::default();
+
+        if !["laplace", "gaussian", "analyticgaussian", "snapping"].contains(&self.mechanism.to_lowercase().as_str()) {
+            return Err("mechanism: must be one of [Laplace, Gaussian, AnalyticGaussian, Snapping]".into())
+        }
+        if self.ridge < 0. {
+            return Err("ridge: must be non-negative".into())
+        }
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+        let target_property = properties.get::<IndexKey>(&"target".into())
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?;
+
+        if target_property.num_columns()? != 1 {
+            return Err("target: must contain exactly one column".into())
+        }
+
+        let id_data = *component.arguments().get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let id_target = *component.arguments().get::<IndexKey>(&"target".into())
+            .ok_or_else(|| Error::from("target must be provided as an argument"))?;
+
+        let statistics = get_statistics(self, data_property.num_columns()?)?;
+        let mut statistic_ids = Vec::new();
+        for (variant, arguments) in statistics {
+            maximum_id += 1;
+            expansion.computation_graph.insert(maximum_id, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(arguments.into_iter()
+                    .map(|(name, is_data)| (name.into(), if is_data { id_data } else { id_target }))
+                    .collect())),
+                variant: Some(variant),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(maximum_id);
+            statistic_ids.push(maximum_id);
+        }
+
+        // the ridge penalty on the sum of squared residuals, scaled to the penalty on the covariance
+        let ridge = self.ridge / data_property.num_records()? as f64;
+
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "covariance".into() => statistic_ids[0],
+                "cross_covariance".into() => statistic_ids[1],
+                "data_mean".into() => statistic_ids[2],
+                "target_mean".into() => statistic_ids[3]
+            ])),
+            variant: Some(proto::component::Variant::SolveLinearRegression(proto::SolveLinearRegression {
+                ridge
+            })),
+            omit: component.omit,
+            submission: component.submission,
+        });
+
+        Ok(expansion)
+    }
+}
+
+/// A statistic, and the names of its arguments, flagged true if bound to the features
+type Statistic = (proto::component::Variant, Vec<(&'static str, bool)>);
+
+/// The released sufficient statistics, in the order of the arguments of SolveLinearRegression.
+///
+/// The privacy usage is divided among the statistics in proportion to their number of elements.
+fn get_statistics(
+    component: &proto::DpLinearRegression, num_features: i64,
+) -> Result<Vec<Statistic>> {
+    let num_features = num_features as f64;
+    let sizes = [num_features * (num_features + 1.) / 2., num_features, num_features, 1.];
+    let total: f64 = sizes.iter().sum();
+
+    let usages = sizes.iter()
+        .map(|size| component.privacy_usage.iter().cloned()
+            .map(|usage| usage * (size / total))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>())
+        .collect::<Result<Vec<Vec<proto::PrivacyUsage>>>>()?;
+
+    let covariance = |privacy_usage: &Vec<proto::PrivacyUsage>| proto::component::Variant::DpCovariance(proto::DpCovariance {
+        mechanism: component.mechanism.clone(),
+        privacy_usage: privacy_usage.clone(),
+        finite_sample_correction: false
+    });
+    let mean = |privacy_usage: &Vec<proto::PrivacyUsage>| proto::component::Variant::DpMean(proto::DpMean {
+        implementation: "resize".to_string(),
+        mechanism: component.mechanism.clone(),
+        privacy_usage: privacy_usage.clone()
+    });
+
+    Ok(vec![
+        (covariance(&usages[0]), vec![("data", true)]),
+        (covariance(&usages[1]), vec![("left", true), ("right", false)]),
+        (mean(&usages[2]), vec![("data", true)]),
+        (mean(&usages[3]), vec![("data", false)])
+    ])
+}
+
+/// Bound on the error of the released sufficient statistics, at the given alpha.
+///
+/// The coefficients are solved from the statistics, so they do not have an accuracy of their own.
+fn get_statistics_accuracy(
+    component: &proto::DpLinearRegression,
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &NodeProperties,
+    alpha: f64,
+) -> Result<Option<f64>> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.clone();
+    let target_property = properties.get::<IndexKey>(&"target".into())
+        .ok_or("target: missing")?.clone();
+
+    let accuracies = get_statistics(component, data_property.array()?.num_columns()?)?.into_iter()
+        .map(|(variant, arguments)| {
+            // the aggregators and privacy usages of the statistics
+            let (aggregator, privacy_usage) = match variant {
+                proto::component::Variant::DpCovariance(covariance) => (
+                    proto::component::Variant::Covariance(proto::Covariance { finite_sample_correction: false }),
+                    covariance.privacy_usage),
+                proto::component::Variant::DpMean(mean) => (
+                    proto::component::Variant::Mean(proto::Mean {}),
+                    mean.privacy_usage),
+                _ => return Err("unexpected statistic".into())
+            };
+
+            let arguments = arguments.into_iter()
+                .map(|(name, is_data)| (name.into(), if is_data { data_property.clone() } else { target_property.clone() }))
+                .collect::<IndexMap<IndexKey, ValueProperties>>();
+            let aggregated = proto::Component {
+                arguments: None,
+                variant: Some(aggregator),
+                omit: true,
+                submission: 0
+            }.propagate_property(&Some(privacy_definition.clone()), IndexMap::new(), arguments, 0)?.0;
+
+            // reduce the usage of each element based on c-stability and group size, as the mechanism does
+            let aggregated_property = aggregated.array()?;
+            let privacy_usage = spread_privacy_usage(&privacy_usage, aggregated_property.num_columns()? as usize)?.into_iter()
+                .zip(aggregated_property.c_stability.iter())
+                .map(|(usage, c_stab)| usage.actual_to_effective(1., *c_stab, privacy_definition.group_size))
+                .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+
+            let mechanism = proto::Component {
+                arguments: None,
+                variant: Some(match component.mechanism.to_lowercase().as_str() {
+                    "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                        privacy_usage
+                    }),
+                    "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage, analytic: false
+                    }),
+                    "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage, analytic: true
+                    }),
+                    _ => return Ok(None)
+                }),
+                omit: true,
+                submission: 0
+            };
+
+            Ok(mechanism.privacy_usage_to_accuracy(
+                privacy_definition, &indexmap!["data".into() => aggregated], alpha)?
+                .map(|accuracies| accuracies.iter()
+                    .map(|accuracy| accuracy.value)
+                    .fold(0., f64::max)))
+        })
+        .collect::<Result<Option<Vec<f64>>>>()?;
+
+    Ok(accuracies.map(|accuracies| accuracies.into_iter().fold(0., f64::max)))
+}
+
+impl Report for proto::DpLinearRegression {
+    fn summarize(
+        &self,
+        context: &ReportContext,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+        let target_property = properties.get::<IndexKey>(&"target".into())
+            .ok_or("target: missing")?.array()
+            .map_err(prepend("target:"))?.clone();
+
+        let privacy_usage: Vec<serde_json::Value> = self.privacy_usage.iter()
+            .map(privacy_usage_to_json).clone().collect();
+
+        let alpha = 0.05;
+        let accuracy = get_statistics_accuracy(self, context.privacy_definition, &properties, alpha)?
+            .map(|accuracy_value| crate::utilities::json::Accuracy { accuracy_value, alpha });
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPLinearRegression".to_string(),
+            variables: serde_json::json!(variable_names.cloned()
+                .unwrap_or_else(Vec::new).iter()
+                .map(|v| v.to_string()).collect::<Vec<String>>()),
+            release_info: value_to_json(release)?,
+            privacy_loss: serde_json::json![privacy_usage],
+            accuracy,
+            submission: component.submission,
+            node_id: context.node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Sufficient statistics perturbation".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "n": data_property.num_records()?,
+                    "ridge": self.ridge,
+                    "constraint": {
+                        "lowerbound": data_property.lower_float()?,
+                        "upperbound": data_property.upper_float()?,
+                        "lowerbound_target": target_property.lower_float()?,
+                        "upperbound_target": target_property.upper_float()?
+                    }
+                })
+            }
+        }]))
+    }
+}
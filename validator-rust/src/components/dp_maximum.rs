@@ -1,11 +1,12 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 
 use crate::base::{NodeProperties, Value, Array, IndexKey};
 use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
 use crate::utilities::{prepend, privacy::spread_privacy_usage, array::get_ith_column};
+use crate::components::dp_quantile::{exponential_quantile_accuracies, is_exponential};
 use indexmap::map::IndexMap;
 
 
@@ -34,7 +35,8 @@ impl Expandable for proto::DpMaximum {
                 alpha: 1.,
                 interpolation: "upper".to_string(),
                 mechanism,
-                privacy_usage: self.privacy_usage.clone()
+                privacy_usage: self.privacy_usage.clone(),
+                implementation: "standard".to_string()
             })),
             omit: component.omit,
             submission: component.submission,
@@ -48,7 +50,7 @@ impl Expandable for proto::DpMaximum {
 impl Report for proto::DpMaximum {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -67,6 +69,14 @@ impl Report for proto::DpMaximum {
         let num_columns = data_property.num_columns()?;
         let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
 
+        // accuracies are only derived for the exponential mechanism
+        let accuracies = if is_exponential(&self.mechanism, &properties) {
+            exponential_quantile_accuracies(context.privacy_definition, &properties, proto::Quantile {
+                alpha: 1.,
+                interpolation: "upper".to_string()
+            }, &self.privacy_usage, 0.05)?
+        } else { None };
+
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
                 .and_then(|names| names.get(column_number)).cloned()
@@ -82,9 +92,12 @@ impl Report for proto::DpMaximum {
                     _ => return Err("maximum must be numeric".into())
                 },
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
-                accuracy: None,
+                accuracy: accuracies.as_ref().map(|accuracies| crate::utilities::json::Accuracy {
+                    accuracy_value: accuracies[column_number].value,
+                    alpha: accuracies[column_number].alpha
+                }),
                 submission: component.submission,
-                node_id,
+                node_id: context.node_id,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
                     name: "".to_string(),
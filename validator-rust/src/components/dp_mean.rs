@@ -1,7 +1,7 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 
 use crate::base::{IndexKey, NodeProperties, Value};
 use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
@@ -66,7 +66,8 @@ impl Expandable for proto::DpMean {
                     mechanism: "SimpleGeometric".to_string(),
                     privacy_usage: self.privacy_usage.iter().cloned()
                         .map(|v| v * (num_columns / (num_columns + 1.)))
-                        .collect::<Result<Vec<proto::PrivacyUsage>>>()?
+                        .collect::<Result<Vec<proto::PrivacyUsage>>>()?,
+                    sketch_size: 0
                 })),
                 omit: true,
                 submission: component.submission,
@@ -120,6 +121,14 @@ impl Expandable for proto::DpMean {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: false
+                    }),
+                    "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: true
+                    }),
+                    "snapping" => proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     _ => panic!("Unexpected invalid token {:?}", self.mechanism.as_str()),
@@ -148,7 +157,7 @@ impl Report for proto::DpMean {
     /// * `release` - JSONRelease containing DP release information
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -187,7 +196,7 @@ impl Report for proto::DpMean {
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
                 accuracy: None,
                 submission: component.submission,
-                node_id,
+                node_id: context.node_id,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
                     name: "".to_string(),
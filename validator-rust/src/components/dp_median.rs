@@ -1,12 +1,13 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext, Accuracy};
 
 
 use crate::base::{NodeProperties, Value, Array, IndexKey};
 use crate::utilities::json::{JSONRelease, value_to_json, privacy_usage_to_json, AlgorithmInfo};
 use crate::utilities::{prepend, privacy::spread_privacy_usage, array::get_ith_column};
+use crate::components::dp_quantile::{exponential_quantile_accuracies, is_exponential};
 use indexmap::map::IndexMap;
 
 
@@ -35,9 +36,10 @@ impl Expandable for proto::DpMedian {
                 alpha: 0.5,
                 interpolation: self.interpolation.clone(),
                 privacy_usage: self.privacy_usage.clone(),
-                mechanism
+                mechanism,
+                implementation: self.implementation.clone()
             })),
-            omit: true,
+            omit: component.omit,
             submission: component.submission,
         });
         expansion.traversal.push(component_id);
@@ -50,7 +52,7 @@ impl Expandable for proto::DpMedian {
 impl Report for proto::DpMedian {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -69,6 +71,20 @@ impl Report for proto::DpMedian {
         let num_columns = data_property.num_columns()?;
         let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
 
+        let is_smooth = self.implementation.to_lowercase() == "smooth-sensitivity";
+        // accuracies are derived for the smooth sensitivity implementation and for the exponential mechanism
+        let accuracies = if is_smooth {
+            proto::SmoothQuantileMechanism {
+                alpha: 0.5,
+                privacy_usage: self.privacy_usage.clone()
+            }.privacy_usage_to_accuracy(context.privacy_definition, &properties, 0.05)?
+        } else if is_exponential(&self.mechanism, &properties) {
+            exponential_quantile_accuracies(context.privacy_definition, &properties, proto::Quantile {
+                alpha: 0.5,
+                interpolation: self.interpolation.clone()
+            }, &self.privacy_usage, 0.05)?
+        } else { None };
+
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
                 .and_then(|names| names.get(column_number)).cloned()
@@ -84,14 +100,17 @@ impl Report for proto::DpMedian {
                     _ => return Err("maximum must be numeric".into())
                 },
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
-                accuracy: None,
+                accuracy: accuracies.as_ref().map(|accuracies| crate::utilities::json::Accuracy {
+                    accuracy_value: accuracies[column_number].value,
+                    alpha: accuracies[column_number].alpha
+                }),
                 submission: component.submission,
-                node_id,
+                node_id: context.node_id,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
-                    name: "".to_string(),
+                    name: if is_smooth { "Smooth sensitivity" } else { "" }.to_string(),
                     cite: "".to_string(),
-                    mechanism: self.mechanism.clone(),
+                    mechanism: if is_smooth { "Cauchy".to_string() } else { self.mechanism.clone() },
                     argument: serde_json::json!({
                         "constraint": {
                             "lowerbound": minimums[column_number],
@@ -1,7 +1,7 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Component, Expandable, Report, ReportContext, Accuracy};
 
 
 use crate::base::{IndexKey, NodeProperties, Value, Array};
@@ -26,11 +26,36 @@ impl Expandable for proto::DpQuantile {
         let data_id = *component.arguments().get::<IndexKey>(&"data".into())
             .ok_or_else(|| Error::from("data is a required argument to DPQuantile"))?;
 
+        match self.implementation.to_lowercase().as_str() {
+            "standard" => (),
+            // the smooth quantile mechanism computes the quantile itself, from the bounds of the data
+            "smooth-sensitivity" => {
+                expansion.computation_graph.insert(component_id, proto::Component {
+                    arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => data_id])),
+                    variant: Some(proto::component::Variant::SmoothQuantileMechanism(proto::SmoothQuantileMechanism {
+                        alpha: self.alpha,
+                        privacy_usage: self.privacy_usage.clone()
+                    })),
+                    omit: component.omit,
+                    submission: component.submission,
+                });
+                expansion.traversal.push(component_id);
+                return Ok(expansion)
+            },
+            _ => return Err("implementation: must be one of [standard, smooth-sensitivity]".into())
+        }
+
         // quantile
+        let mechanism = self.mechanism.to_lowercase();
+        // selection mechanisms score a public set of candidates, rather than noising the quantile directly
+        let is_selection = [
+            "exponential", "reportnoisymax", "reportnoisymaxlaplace", "reportnoisymaxexponential", "permuteandflip"
+        ].contains(&mechanism.as_str());
+
         let mut quantile_args = indexmap![IndexKey::from("data") => data_id];
-        if self.mechanism.to_lowercase().as_str() == "exponential" {
+        if is_selection {
             quantile_args.insert("candidates".into(), *component.arguments().get::<IndexKey>(&"candidates".into())
-                .ok_or_else(|| Error::from("candidates is a required argument to DPQuantile when the exponential mechanism is used."))?);
+                .ok_or_else(|| Error::from("candidates is a required argument to DPQuantile when a selection mechanism is used."))?);
         }
         maximum_id += 1;
         let id_quantile = maximum_id;
@@ -47,25 +72,45 @@ impl Expandable for proto::DpQuantile {
 
         // sanitizing
         let mut sanitize_args = IndexMap::new();
-        if self.mechanism.to_lowercase().as_str() == "exponential" {
+        if is_selection {
             sanitize_args.insert("utilities".into(), id_quantile);
             sanitize_args.insert("candidates".into(), *component.arguments().get::<IndexKey>(&"candidates".into())
-                .ok_or_else(|| Error::from("candidates is a required argument to DPQuantile when the exponential mechanism is used."))?);
+                .ok_or_else(|| Error::from("candidates is a required argument to DPQuantile when a selection mechanism is used."))?);
         } else {
             sanitize_args.insert("data".into(), id_quantile);
         }
         expansion.computation_graph.insert(component_id, proto::Component {
             arguments: Some(proto::ArgumentNodeIds::new(sanitize_args)),
-            variant: Some(match self.mechanism.to_lowercase().as_str() {
+            variant: Some(match mechanism.as_str() {
                 "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
-                    privacy_usage: self.privacy_usage.clone()
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: false
+                }),
+                "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: true
                 }),
                 "exponential" => proto::component::Variant::ExponentialMechanism(proto::ExponentialMechanism {
                     privacy_usage: self.privacy_usage.clone()
                 }),
+                "reportnoisymax" => proto::component::Variant::ReportNoisyMaxMechanism(proto::ReportNoisyMaxMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    noise: "Gumbel".to_string()
+                }),
+                "reportnoisymaxlaplace" => proto::component::Variant::ReportNoisyMaxMechanism(proto::ReportNoisyMaxMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    noise: "Laplace".to_string()
+                }),
+                "reportnoisymaxexponential" => proto::component::Variant::ReportNoisyMaxMechanism(proto::ReportNoisyMaxMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    noise: "Exponential".to_string()
+                }),
+                "permuteandflip" => proto::component::Variant::PermuteAndFlipMechanism(proto::PermuteAndFlipMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                }),
                 _ => panic!("Unexpected invalid token {:?}", self.mechanism.as_str()),
             }),
             omit: component.omit,
@@ -80,7 +125,7 @@ impl Expandable for proto::DpQuantile {
 impl Report for proto::DpQuantile {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -99,6 +144,20 @@ impl Report for proto::DpQuantile {
         let num_columns = data_property.num_columns()?;
         let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
 
+        let is_smooth = self.implementation.to_lowercase() == "smooth-sensitivity";
+        // accuracies are derived for the smooth sensitivity implementation and for the exponential mechanism
+        let accuracies = if is_smooth {
+            proto::SmoothQuantileMechanism {
+                alpha: self.alpha,
+                privacy_usage: self.privacy_usage.clone()
+            }.privacy_usage_to_accuracy(context.privacy_definition, &properties, 0.05)?
+        } else if is_exponential(&self.mechanism, &properties) {
+            exponential_quantile_accuracies(context.privacy_definition, &properties, proto::Quantile {
+                alpha: self.alpha,
+                interpolation: self.interpolation.clone()
+            }, &self.privacy_usage, 0.05)?
+        } else { None };
+
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
                 .and_then(|names| names.get(column_number)).cloned()
@@ -114,14 +173,17 @@ impl Report for proto::DpQuantile {
                     _ => return Err("maximum must be numeric".into())
                 },
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
-                accuracy: None,
+                accuracy: accuracies.as_ref().map(|accuracies| crate::utilities::json::Accuracy {
+                    accuracy_value: accuracies[column_number].value,
+                    alpha: accuracies[column_number].alpha
+                }),
                 submission: component.submission,
-                node_id,
+                node_id: context.node_id,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
-                    name: "".to_string(),
+                    name: if is_smooth { "Smooth sensitivity" } else { "" }.to_string(),
                     cite: "".to_string(),
-                    mechanism: self.mechanism.clone(),
+                    mechanism: if is_smooth { "Cauchy".to_string() } else { self.mechanism.clone() },
                     argument: serde_json::json!({
                         "constraint": {
                             "lowerbound": minimums[column_number],
@@ -134,3 +196,38 @@ impl Report for proto::DpQuantile {
         Ok(Some(releases))
     }
 }
+
+/// Accuracies of quantiles released by the exponential mechanism, in ranks.
+///
+/// # Arguments
+/// * `privacy_definition` - Definition of privacy in the analysis.
+/// * `properties` - Properties of the data and candidates passed to the DP component.
+/// * `quantile` - The quantile that scores the candidates.
+/// * `privacy_usage` - Privacy usage of the exponential mechanism.
+/// * `alpha` - Probability that the error exceeds the accuracy.
+///
+/// # Return
+/// An accuracy for each column of the data.
+pub fn exponential_quantile_accuracies(
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &NodeProperties,
+    quantile: proto::Quantile,
+    privacy_usage: &[proto::PrivacyUsage],
+    alpha: f64
+) -> Result<Option<Vec<proto::Accuracy>>> {
+    let utilities_property = quantile.propagate_property(
+        &Some(privacy_definition.clone()), IndexMap::new(), properties.clone(), 0)?.0;
+
+    proto::ExponentialMechanism {
+        privacy_usage: privacy_usage.to_vec()
+    }.privacy_usage_to_accuracy(privacy_definition, &indexmap!["utilities".into() => utilities_property], alpha)
+}
+
+/// Whether a quantile is released by the exponential mechanism, once an "automatic" mechanism is resolved.
+pub fn is_exponential(mechanism: &str, properties: &NodeProperties) -> bool {
+    match mechanism.to_lowercase().as_str() {
+        "exponential" => true,
+        "automatic" => properties.contains_key::<IndexKey>(&"candidates".into()),
+        _ => false
+    }
+}
@@ -1,7 +1,7 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 
 use crate::base::{NodeProperties, Value, Array};
 use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
@@ -47,6 +47,14 @@ impl Expandable for proto::DpRawMoment {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: false
+                }),
+                "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: true
+                }),
+                "snapping" => proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 _ => panic!("Unexpected invalid token {:?}", self.mechanism.as_str()),
@@ -63,7 +71,7 @@ impl Expandable for proto::DpRawMoment {
 impl Report for proto::DpRawMoment {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -100,7 +108,7 @@ impl Report for proto::DpRawMoment {
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
                 accuracy: None,
                 submission: component.submission,
-                node_id,
+                node_id: context.node_id,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
                     name: "".to_string(),
@@ -1,7 +1,7 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 
 use crate::base::{NodeProperties, Value, Array, IndexKey, DataType, ArrayProperties};
 use crate::utilities::json::{JSONRelease, AlgorithmInfo, privacy_usage_to_json, value_to_json};
@@ -72,6 +72,17 @@ impl Expandable for proto::DpSum {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: false
+                    }),
+                    "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone(),
+                        analytic: true
+                    }),
+                    "discretegaussian" => proto::component::Variant::DiscreteGaussianMechanism(proto::DiscreteGaussianMechanism {
+                        privacy_usage: self.privacy_usage.clone()
+                    }),
+                    "snapping" => proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
                         privacy_usage: self.privacy_usage.clone()
                     }),
                     _ => panic!("Unexpected invalid token {:?}", mechanism.as_str()),
@@ -88,7 +99,7 @@ impl Expandable for proto::DpSum {
 impl Report for proto::DpSum {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -126,7 +137,7 @@ impl Report for proto::DpSum {
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
                 accuracy: None,
                 submission: component.submission,
-                node_id,
+                node_id: context.node_id,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
                     name: "".to_string(),
@@ -1,7 +1,7 @@
 use crate::errors::*;
 
 use crate::{proto, base};
-use crate::components::{Expandable, Report};
+use crate::components::{Expandable, Report, ReportContext};
 use crate::utilities::{prepend, array::get_ith_column};
 use crate::utilities::privacy::{spread_privacy_usage};
 
@@ -46,6 +46,14 @@ impl Expandable for proto::DpVariance {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: false
+                }),
+                "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    analytic: true
+                }),
+                "snapping" => proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 _ => panic!("Unexpected invalid token {:?}", self.mechanism.as_str()),
@@ -61,7 +69,7 @@ impl Expandable for proto::DpVariance {
 impl Report for proto::DpVariance {
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -98,7 +106,7 @@ impl Report for proto::DpVariance {
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
                 accuracy: None,
                 submission: component.submission,
-                node_id,
+                node_id: context.node_id,
                 postprocess: false,
                 algorithm_info: AlgorithmInfo {
                     name: "".to_string(),
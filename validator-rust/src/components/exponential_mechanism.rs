@@ -2,10 +2,10 @@ use crate::errors::*;
 
 use crate::{proto, base, Warnable};
 
-use crate::components::{Component, Expandable, Sensitivity, Mechanism};
+use crate::components::{Component, Expandable, Sensitivity, Mechanism, Accuracy};
 use crate::base::{Value, SensitivitySpace, ValueProperties, DataType, ArrayProperties, NodeProperties, IndexKey};
-use crate::utilities::{prepend, get_literal, get_argument};
-use crate::utilities::privacy::{privacy_usage_check};
+use crate::utilities::{prepend, get_literal};
+use crate::utilities::privacy::{privacy_usage_check, spread_privacy_usage, get_epsilon};
 use itertools::Itertools;
 use indexmap::map::IndexMap;
 use crate::utilities::inference::infer_property;
@@ -14,145 +14,307 @@ impl Component for proto::ExponentialMechanism {
     fn propagate_property(
         &self,
         privacy_definition: &Option<proto::PrivacyDefinition>,
-        public_arguments: IndexMap<base::IndexKey, &Value>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32,
     ) -> Result<Warnable<ValueProperties>> {
-        let privacy_definition = privacy_definition.as_ref()
-            .ok_or_else(|| "privacy_definition must be defined")?;
+        propagate_selection_property(privacy_definition, &self.privacy_usage, &properties)
+    }
+}
 
-        if privacy_definition.group_size == 0 {
-            return Err("group size must be greater than zero".into());
-        }
+impl Expandable for proto::ExponentialMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        expand_selection_component(privacy_definition, component, properties, component_id, maximum_id)
+    }
+}
+
+impl Mechanism for proto::ExponentialMechanism {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        get_selection_privacy_usage(
+            privacy_definition, release_usage.unwrap_or(&self.privacy_usage), properties)
+    }
+}
 
-        let utilities_property = properties.get::<IndexKey>(&"utilities".into())
-            .ok_or("utilities: missing")?.jagged()
-            .map_err(prepend("utilities:"))?.clone();
+/// Propagate properties through a mechanism that selects one public candidate per column, by utility.
+///
+/// Shared by the exponential, report-noisy-max and permute-and-flip mechanisms.
+pub fn propagate_selection_property(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    privacy_usage: &[proto::PrivacyUsage],
+    properties: &base::NodeProperties,
+) -> Result<Warnable<ValueProperties>> {
+    let privacy_definition = privacy_definition.as_ref()
+        .ok_or_else(|| "privacy_definition must be defined")?;
 
-        if utilities_property.data_type != DataType::Float {
-            return Err("utilities: data_type must be float".into());
-        }
+    if privacy_definition.group_size == 0 {
+        return Err("group size must be greater than zero".into());
+    }
 
-        let candidates = get_argument(&public_arguments, "candidates")?.ref_jagged()?;
+    let utilities_property = properties.get::<IndexKey>(&"utilities".into())
+        .ok_or("utilities: missing")?.jagged()
+        .map_err(prepend("utilities:"))?.clone();
 
-        let utilities_num_records = utilities_property.num_records()?;
-        let candidates_num_records = candidates.num_records();
+    if utilities_property.data_type != DataType::Float {
+        return Err("utilities: data_type must be float".into());
+    }
 
-        if utilities_num_records.len() != candidates_num_records.len() {
-            return Err("utilities and candidates must share the same number of columns".into());
-        }
-        if !utilities_num_records.iter().zip(candidates_num_records.iter()).all(|(l, r)| l == r) {
-            return Err("utilities and candidates must share the same number of rows in every column".into());
-        }
+    let candidates_property = properties.get::<IndexKey>(&"candidates".into())
+        .ok_or("candidates: missing")?.jagged()
+        .map_err(prepend("candidates:"))?;
+    if !candidates_property.releasable {
+        return Err("candidates: must be public".into());
+    }
 
-        let aggregator = utilities_property.aggregator.clone()
-            .ok_or_else(|| Error::from("aggregator: missing"))?;
-
-        // sensitivity must be computable
-        let sensitivity_values = aggregator.component.compute_sensitivity(
-            privacy_definition,
-            &aggregator.properties,
-            &SensitivitySpace::Exponential)?;
-
-        // make sure sensitivities are an f64 array
-        sensitivity_values.array()?.float()?;
-
-        let num_columns = utilities_property.num_columns()?;
-        let mut output_property = ArrayProperties {
-            num_records: Some(1),
-            num_columns: Some(num_columns),
-            nullity: false,
-            releasable: true,
-            c_stability: (0..num_columns).map(|_| 1.).collect(),
-            aggregator: None,
-            nature: None,
-            data_type: candidates.data_type(),
-            dataset_id: None,
-            is_not_empty: true,
-            // TODO: preserve dimensionality through exponential mechanism
-            //     All outputs become 2D, so 1D outputs are lost
-            dimensionality: Some(2),
-            group_id: vec![],
-            naturally_ordered: true
-        };
+    let utilities_num_records = utilities_property.num_records()?;
+    let candidates_num_records = candidates_property.num_records()?;
 
-        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
-            .fold1(|l, r| l? + r?)
-            .ok_or_else(|| "privacy_usage: must be defined")??;
+    if utilities_num_records.len() != candidates_num_records.len() {
+        return Err("utilities and candidates must share the same number of columns".into());
+    }
+    if !utilities_num_records.iter().zip(candidates_num_records.iter()).all(|(l, r)| l == r) {
+        return Err("utilities and candidates must share the same number of rows in every column".into());
+    }
 
-        let warnings = privacy_usage_check(
-            &privacy_usage,
-            output_property.num_records,
-            privacy_definition.strict_parameter_checks)?;
+    let aggregator = utilities_property.aggregator.clone()
+        .ok_or_else(|| Error::from("aggregator: missing"))?;
 
-        output_property.releasable = true;
+    // sensitivity must be computable
+    let sensitivity_values = aggregator.component.compute_sensitivity(
+        privacy_definition,
+        &aggregator.properties,
+        &SensitivitySpace::Exponential)?;
 
-        Ok(Warnable(output_property.into(), warnings))
-    }
+    // make sure sensitivities are an f64 array
+    sensitivity_values.array()?.float()?;
+
+    let num_columns = utilities_property.num_columns()?;
+    let mut output_property = ArrayProperties {
+        num_records: Some(1),
+        num_columns: Some(num_columns),
+        nullity: false,
+        releasable: true,
+        c_stability: (0..num_columns).map(|_| 1.).collect(),
+        aggregator: None,
+        nature: None,
+        data_type: candidates_property.data_type.clone(),
+        dataset_id: None,
+        is_not_empty: true,
+        // TODO: preserve dimensionality through exponential mechanism
+        //     All outputs become 2D, so 1D outputs are lost
+        dimensionality: Some(2),
+        group_id: vec![],
+        naturally_ordered: true,
+        randomized_response: None
+    };
+
+    let privacy_usage = privacy_usage.iter().cloned().map(Ok)
+        .fold1(|l, r| l? + r?)
+        .ok_or_else(|| "privacy_usage: must be defined")??;
+
+    let warnings = privacy_usage_check(
+        &privacy_usage,
+        output_property.num_records,
+        privacy_definition.strict_parameter_checks)?;
+
+    output_property.releasable = true;
+
+    Ok(Warnable(output_property.into(), warnings))
 }
 
-impl Expandable for proto::ExponentialMechanism {
-    fn expand_component(
-        &self,
-        privacy_definition: &Option<proto::PrivacyDefinition>,
-        component: &proto::Component,
-        _public_arguments: &IndexMap<IndexKey, &Value>,
-        properties: &base::NodeProperties,
-        component_id: u32,
-        mut maximum_id: u32,
-    ) -> Result<base::ComponentExpansion> {
+/// Expand a selection mechanism by attaching the sensitivity of its utilities.
+pub fn expand_selection_component(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    component: &proto::Component,
+    properties: &base::NodeProperties,
+    component_id: u32,
+    mut maximum_id: u32,
+) -> Result<base::ComponentExpansion> {
 
-        let mut expansion = base::ComponentExpansion::default();
+    let mut expansion = base::ComponentExpansion::default();
 
-        let privacy_definition = privacy_definition.as_ref()
-            .ok_or_else(|| "privacy definition must be defined")?;
+    let privacy_definition = privacy_definition.as_ref()
+        .ok_or_else(|| "privacy definition must be defined")?;
 
-        // always overwrite sensitivity. This is not something a user may configure
-        let utilities_properties = properties.get::<IndexKey>(&"utilities".into())
-            .ok_or("utilities: missing")?.jagged()
-            .map_err(prepend("utilities:"))?.clone();
+    // always overwrite sensitivity. This is not something a user may configure
+    let utilities_properties = properties.get::<IndexKey>(&"utilities".into())
+        .ok_or("utilities: missing")?.jagged()
+        .map_err(prepend("utilities:"))?.clone();
 
-        let aggregator = utilities_properties.aggregator
-            .ok_or_else(|| Error::from("aggregator: missing"))?;
+    let aggregator = utilities_properties.aggregator
+        .ok_or_else(|| Error::from("aggregator: missing"))?;
 
-        let sensitivity = aggregator.component.compute_sensitivity(
-            privacy_definition,
-            &aggregator.properties,
-            &SensitivitySpace::Exponential)?;
+    let sensitivity = aggregator.component.compute_sensitivity(
+        privacy_definition,
+        &aggregator.properties,
+        &SensitivitySpace::Exponential)?;
 
-        maximum_id += 1;
-        let id_sensitivity = maximum_id;
-        let (patch_node, release) = get_literal(sensitivity, component.submission)?;
-        expansion.computation_graph.insert(id_sensitivity, patch_node);
-        expansion.properties.insert(id_sensitivity, infer_property(&release.value, None)?);
-        expansion.releases.insert(id_sensitivity, release);
+    maximum_id += 1;
+    let id_sensitivity = maximum_id;
+    let (patch_node, release) = get_literal(sensitivity, component.submission)?;
+    expansion.computation_graph.insert(id_sensitivity, patch_node);
+    expansion.properties.insert(id_sensitivity, infer_property(&release.value, None)?);
+    expansion.releases.insert(id_sensitivity, release);
 
-        // noising
-        let mut noise_component = component.clone();
-        noise_component.insert_argument(&"sensitivity".into(), id_sensitivity);
+    // noising
+    let mut noise_component = component.clone();
+    noise_component.insert_argument(&"sensitivity".into(), id_sensitivity);
 
-        expansion.computation_graph.insert(component_id, noise_component);
+    expansion.computation_graph.insert(component_id, noise_component);
 
-        Ok(expansion)
-    }
+    Ok(expansion)
 }
 
-impl Mechanism for proto::ExponentialMechanism {
-    fn get_privacy_usage(
+/// The privacy usage of a selection mechanism, accounting for the c-stability of the scored data.
+pub fn get_selection_privacy_usage(
+    privacy_definition: &proto::PrivacyDefinition,
+    privacy_usage: &[proto::PrivacyUsage],
+    properties: &NodeProperties,
+) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+    let utilities_property = properties.get::<IndexKey>(&"utilities".into())
+        .ok_or("utilities: missing")?.jagged()
+        .map_err(prepend("utilities:"))?;
+
+    // the c-stability of the scored data carries through the utilities
+    let c_stability = match utilities_property.aggregator.as_ref()
+        .and_then(|aggregator| aggregator.properties.get::<IndexKey>(&"data".into())) {
+        Some(data_property) => data_property.array()?.c_stability.clone(),
+        None => vec![1.; utilities_property.num_columns()? as usize]
+    };
+
+    Some(privacy_usage.iter()
+        .zip(c_stability.iter())
+        .map(|(usage, c_stab)|
+            usage.effective_to_actual(1., *c_stab, privacy_definition.group_size))
+        .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+}
+
+/// The accuracy of the exponential mechanism, as the utility lost relative to the best candidate.
+///
+/// With probability 1 - alpha, the utility of the released candidate is within
+/// `2 * sensitivity * ln(|candidates| / alpha) / epsilon` of the largest utility.
+/// The utilities of quantiles are ranks, so the accuracy bounds how many more records
+/// lie between the release and the true quantile than between the best candidate and the true quantile.
+impl Accuracy for proto::ExponentialMechanism {
+    fn accuracy_to_privacy_usage(
         &self,
         privacy_definition: &proto::PrivacyDefinition,
-        release_usage: Option<&Vec<proto::PrivacyUsage>>,
-        properties: &NodeProperties,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
     ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
-        let data_property = properties.get::<IndexKey>(&"data".into())
-            .ok_or("data: missing")?.array()
-            .map_err(prepend("data:"))?;
+        let (sensitivities, num_candidates) = get_utility_parameters(privacy_definition, properties)?;
+        if sensitivities.len() != accuracies.values.len() {
+            return Err("accuracies: must contain one accuracy per column".into())
+        }
+
+        Ok(Some(sensitivities.into_iter().zip(num_candidates).zip(accuracies.values.iter())
+            .map(|((sensitivity, num_candidates), accuracy)| proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon: 2. * sensitivity * (num_candidates as f64 / accuracy.alpha).ln() / accuracy.value,
+                    delta: 0.,
+                }))
+            })
+            .collect()))
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let (sensitivities, num_candidates) = get_utility_parameters(privacy_definition, properties)?;
+
+        let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
+        let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
+
+        Ok(Some(sensitivities.into_iter().zip(num_candidates).zip(epsilons)
+            .map(|((sensitivity, num_candidates), epsilon)| proto::Accuracy {
+                value: 2. * sensitivity * (num_candidates as f64 / alpha).ln() / epsilon,
+                alpha,
+            })
+            .collect()))
+    }
+}
+
+/// The sensitivity of the utilities, and the number of candidates, in each column.
+fn get_utility_parameters(
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &NodeProperties
+) -> Result<(Vec<f64>, Vec<i64>)> {
+    let utilities_property = properties.get::<IndexKey>(&"utilities".into())
+        .ok_or("utilities: missing")?.jagged()
+        .map_err(prepend("utilities:"))?;
+
+    let aggregator = utilities_property.aggregator.as_ref()
+        .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+    let sensitivities = aggregator.component.compute_sensitivity(
+        privacy_definition,
+        &aggregator.properties,
+        &SensitivitySpace::Exponential)?.array()?.float()?;
+
+    Ok((sensitivities.iter().copied().collect(), utilities_property.num_records()?))
+}
+
+#[cfg(test)]
+mod test_exponential_mechanism {
+    use crate::proto;
+    use crate::base::{Value, Jagged, IndexKey};
+    use crate::utilities::inference::infer_property;
+    use ndarray::Array2;
+    use indexmap::map::IndexMap;
+
+    #[test]
+    fn test_accuracy_round_trip() {
+        let privacy_definition = proto::PrivacyDefinition {
+            neighboring: proto::privacy_definition::Neighboring::Substitute as i32,
+            group_size: 1,
+            ..Default::default()
+        };
+        let data: Value = Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into();
+        let candidates = Value::Jagged(Jagged::Float(vec![(0..11).map(|v| v as f64).collect()]));
+        let properties: IndexMap<IndexKey, _> = indexmap![
+            IndexKey::from("data") => infer_property(&data, None).unwrap(),
+            IndexKey::from("candidates") => infer_property(&candidates, None).unwrap()];
+
+        let component = proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                IndexKey::from("data") => 0, IndexKey::from("candidates") => 1])),
+            variant: Some(proto::component::Variant::DpMedian(proto::DpMedian {
+                mechanism: "exponential".to_string(),
+                interpolation: "midpoint".to_string(),
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                        epsilon: 1., delta: 0.
+                    }))
+                }],
+                implementation: "standard".to_string()
+            })),
+            omit: false,
+            submission: 0
+        };
 
+        let accuracies = crate::privacy_usage_to_accuracy(
+            component.clone(), privacy_definition.clone(), properties.clone(), 0.05).unwrap();
+        // ranks have unit sensitivity under substitution, and there are 11 candidates
+        assert!((accuracies.values[0].value - 2. * (11. / 0.05f64).ln()).abs() < 1e-10);
 
-        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
-            .zip(data_property.c_stability.iter())
-            .map(|(usage, c_stab)|
-                usage.effective_to_actual(1., *c_stab as f64, privacy_definition.group_size))
-            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+        let usages = crate::accuracy_to_privacy_usage(
+            component, privacy_definition, properties, accuracies).unwrap();
+        let epsilon = crate::utilities::privacy::get_epsilon(&usages.values[0]).unwrap();
+        assert!((epsilon - 1.).abs() < 1e-10);
     }
 }
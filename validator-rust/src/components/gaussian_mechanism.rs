@@ -9,7 +9,7 @@ use crate::{proto, base, Warnable};
 use crate::components::{Component, Expandable};
 use crate::base::{Value, SensitivitySpace, ValueProperties, DataType, NodeProperties, IndexKey};
 use crate::utilities::{prepend, expand_mechanism};
-use crate::utilities::privacy::{spread_privacy_usage, get_epsilon, get_delta, privacy_usage_check};
+use crate::utilities::privacy::{spread_privacy_usage, get_epsilon, get_delta, privacy_usage_check, gaussian_rdp_curve, analytic_gaussian_scale};
 use itertools::Itertools;
 use indexmap::map::IndexMap;
 
@@ -60,24 +60,34 @@ impl Component for proto::GaussianMechanism {
             data_property.num_records,
             privacy_definition.strict_parameter_checks)?;
 
-        let epsilon = get_epsilon(&privacy_usage)?;
-        if epsilon > 1.0 {
-            let message = Error::from(format!("Warning: A privacy parameter of epsilon = {} is in use. Privacy is only \
-                    guaranteed for the Gaussian mechanism as implemented in the rust runtime for epsilon \
-                    between 0 and 1.", epsilon));
+        // concentrated privacy usages calibrate the noise directly from rho
+        if let Some(proto::privacy_usage::Distance::Approximate(_)) = privacy_usage.distance {
+            let epsilon = get_epsilon(&privacy_usage)?;
+            // the analytic calibration is valid for all epsilon
+            if epsilon > 1.0 && !self.analytic {
+                let message = Error::from(format!("Warning: A privacy parameter of epsilon = {} is in use. Privacy is only \
+                        guaranteed for the Gaussian mechanism as implemented in the rust runtime for epsilon \
+                        between 0 and 1.", epsilon));
+
+                if privacy_definition.strict_parameter_checks {
+                    return Err(message)
+                }
+                warnings.push(message);
+            }
 
-            if privacy_definition.strict_parameter_checks {
-                return Err(message)
+            if get_delta(&privacy_usage)? == 0.0 {
+                return Err("delta: may not be zero".into())
             }
-            warnings.push(message);
-        }
 
-        if get_delta(&privacy_usage)? == 0.0 {
-            return Err("delta: may not be zero".into())
+            if self.analytic {
+                analytic_gaussian_scale(epsilon, get_delta(&privacy_usage)?, 1.)?;
+            }
         }
 
         data_property.releasable = true;
         data_property.aggregator = None;
+        // noise may move the release outside the bounds of the aggregate
+        data_property.nature = None;
 
         Ok(Warnable(data_property.into(), warnings))
     }
@@ -123,6 +133,27 @@ impl Mechanism for proto::GaussianMechanism {
                 usage.effective_to_actual(1., *c_stab as f64, privacy_definition.group_size))
             .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
     }
+
+    fn get_rdp_curves(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<Vec<f64>>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // the noise scale is determined by the effective usage,
+        //    and the sensitivity grows with the c-stability and group size
+        Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
+            .zip(data_property.c_stability.iter())
+            .map(|(usage, c_stab)| {
+                let rho = (gaussian_scale(usage, 1., self.analytic)?.powi(2) * 2.).recip();
+                Ok(gaussian_rdp_curve(rho * (*c_stab as f64 * privacy_definition.group_size as f64).powi(2)))
+            })
+            .collect::<Result<Vec<Vec<f64>>>>()).transpose()
+    }
 }
 
 
@@ -148,22 +179,38 @@ impl Accuracy for proto::GaussianMechanism {
         // sensitivity must be computable
         let sensitivities = sensitivity_value.array()?.float()?;
         let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
-        let delta = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
-        let iter = izip!(sensitivities.into_iter(), accuracies.values.iter(), delta.into_iter());
+        let iter = izip!(sensitivities.into_iter(), accuracies.values.iter(), usages.iter());
 
-        use proto::privacy_usage::{Distance, DistanceApproximate};
+        use proto::privacy_usage::{Distance, DistanceApproximate, DistanceConcentrated};
 
         Ok(Some(
-            iter.map(|(sensitivity, accuracy, delta)| {
-                let c: f64 = 2.0_f64 * (1.25_f64 / delta).ln();
-                let sigma: f64 = c.sqrt() * *sensitivity as f64 / accuracy.value;
-                proto::PrivacyUsage {
-                    distance: Some(Distance::Approximate(DistanceApproximate {
-                        epsilon: sigma * 2.0_f64.sqrt() * erf::erf_inv(1.0_f64 - accuracy.alpha),
-                        delta,
-                    }))
-                }
-            }).collect()))
+            iter.map(|(sensitivity, accuracy, usage)| Ok(proto::PrivacyUsage {
+                distance: Some(match usage.distance.as_ref().ok_or("distance must be defined")? {
+                    Distance::Approximate(DistanceApproximate { delta, .. }) if self.analytic => {
+                        // the standard deviation that achieves the accuracy
+                        let sigma: f64 = accuracy.value / (2.0_f64.sqrt() * erf::erf_inv(1.0_f64 - accuracy.alpha));
+                        Distance::Approximate(DistanceApproximate {
+                            epsilon: analytic_gaussian_epsilon(sigma, *delta, *sensitivity)?,
+                            delta: *delta,
+                        })
+                    }
+                    Distance::Approximate(DistanceApproximate { delta, .. }) => {
+                        let c: f64 = 2.0_f64 * (1.25_f64 / delta).ln();
+                        let sigma: f64 = c.sqrt() * *sensitivity as f64 / accuracy.value;
+                        Distance::Approximate(DistanceApproximate {
+                            epsilon: sigma * 2.0_f64.sqrt() * erf::erf_inv(1.0_f64 - accuracy.alpha),
+                            delta: *delta,
+                        })
+                    }
+                    Distance::Concentrated(_) => {
+                        // the standard deviation that achieves the accuracy
+                        let sigma: f64 = accuracy.value / (2.0_f64.sqrt() * erf::erf_inv(1.0_f64 - accuracy.alpha));
+                        Distance::Concentrated(DistanceConcentrated {
+                            rho: (*sensitivity as f64 / sigma).powi(2) / 2.
+                        })
+                    }
+                })
+            })).collect::<Result<Vec<proto::PrivacyUsage>>>()?))
     }
 
     fn privacy_usage_to_accuracy(
@@ -182,25 +229,71 @@ impl Accuracy for proto::GaussianMechanism {
         let sensitivities_value = aggregator.component.compute_sensitivity(
             &privacy_definition,
             &aggregator.properties,
-            &SensitivitySpace::KNorm(1))?;
+            &SensitivitySpace::KNorm(2))?;
 
         // sensitivity must be computable
         let sensitivities = sensitivities_value.array()?.float()?;
 
         let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
-        let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
-        let deltas = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
-        let iter = izip!(sensitivities.into_iter(), epsilons.into_iter(), deltas.into_iter());
+        let iter = izip!(sensitivities.into_iter(), usages.iter());
 
         Ok(Some(
-            iter.map(|(sensitivity, epsilon, delta)| {
-                let c: f64 = 2.0_f64 * (1.25_f64 / delta).ln();
-                let sigma: f64 = c.sqrt() * *sensitivity as f64 / epsilon;
+            iter.map(|(sensitivity, usage)| {
+                let sigma: f64 = gaussian_scale(usage, *sensitivity as f64, self.analytic)?;
 
-                proto::Accuracy {
+                Ok(proto::Accuracy {
                     value: sigma * 2.0_f64.sqrt() * erf::erf_inv(1.0_f64 - alpha),
                     alpha
-                }
-            }).collect()))
+                })
+            }).collect::<Result<Vec<proto::Accuracy>>>()?))
+    }
+}
+
+/// Standard deviation of the Gaussian noise calibrated to a privacy usage.
+///
+/// Approximate usages use the classic calibration sqrt(2 ln(1.25/delta)) * sensitivity / epsilon,
+///     or the analytic calibration if `analytic` is set,
+///     and concentrated usages use sensitivity / sqrt(2 rho).
+fn gaussian_scale(usage: &proto::PrivacyUsage, sensitivity: f64, analytic: bool) -> Result<f64> {
+    Ok(match usage.distance.as_ref().ok_or("distance must be defined")? {
+        proto::privacy_usage::Distance::Approximate(approximate) => if analytic {
+            analytic_gaussian_scale(approximate.epsilon, approximate.delta, sensitivity)?
+        } else {
+            (2.0_f64 * (1.25_f64 / approximate.delta).ln()).sqrt() * sensitivity / approximate.epsilon
+        },
+        proto::privacy_usage::Distance::Concentrated(concentrated) =>
+            sensitivity / (2.0_f64 * concentrated.rho).sqrt()
+    })
+}
+
+/// Smallest epsilon at which the analytic Gaussian mechanism adds noise with standard deviation at most `sigma`.
+fn analytic_gaussian_epsilon(sigma: f64, delta: f64, sensitivity: f64) -> Result<f64> {
+    if sigma.is_nan() || sigma <= 0. {
+        return Err("accuracy: must be positive".into())
+    }
+    // the scale decreases in epsilon, so bracket epsilon and then bisect
+    let (mut lower, mut upper) = (0.5, 1.);
+    while analytic_gaussian_scale(upper, delta, sensitivity)? > sigma { upper *= 2.; }
+    while analytic_gaussian_scale(lower, delta, sensitivity)? <= sigma { lower /= 2.; }
+    for _ in 0..100 {
+        let middle = (lower + upper) / 2.;
+        if analytic_gaussian_scale(middle, delta, sensitivity)? > sigma { lower = middle } else { upper = middle }
+    }
+    Ok(upper)
+}
+
+#[cfg(test)]
+mod test_gaussian_mechanism {
+    use crate::components::gaussian_mechanism::analytic_gaussian_epsilon;
+    use crate::utilities::privacy::analytic_gaussian_scale;
+
+    #[test]
+    fn test_analytic_accuracy_inverse() {
+        // Balle & Wang (2018) report sigma of roughly 3.73 at epsilon = 1, delta = 1e-5
+        let sigma = analytic_gaussian_scale(1., 1e-5, 1.).unwrap();
+        assert!((sigma - 3.73).abs() < 0.01);
+
+        let epsilon = analytic_gaussian_epsilon(analytic_gaussian_scale(0.7, 1e-6, 2.).unwrap(), 1e-6, 2.).unwrap();
+        assert!((epsilon - 0.7).abs() < 1e-6);
     }
 }
@@ -83,6 +83,9 @@ impl Component for proto::Index {
                 if !data_property.releasable {
                     data_property.assert_is_not_aggregated()?;
                 }
+                if data_property.dimensionality.map(|dimensionality| dimensionality != 2).unwrap_or(false) {
+                    return Err("data: columns may only be indexed on 2-dimensional arrays".into())
+                }
 
                 if let Some(indices) = public_arguments.remove::<IndexKey>(&"indices".into()) {
                     let indices = indices.clone().array()?.int()?;
@@ -113,6 +116,11 @@ impl Component for proto::Index {
             ValueProperties::Function(_) => Err("indexing is not suppported on functions".into())
         }?;
 
+        // a single jagged column, like the categories from key selection, is passed through without stacking
+        if let (Some(1), [property @ ValueProperties::Jagged(_)]) = (dimensionality, properties.as_slice()) {
+            return Ok(Warnable::new(property.clone()))
+        }
+
         stack_properties(&properties, dimensionality).map(Warnable::new)
     }
 }
@@ -169,3 +177,24 @@ pub fn to_name_vec<T: Clone>(columns: ArrayD<T>) -> Result<Vec<T>> {
         _ => Err("dimensionality of column names must be less than 2".into())
     }
 }
+
+#[cfg(test)]
+mod test_index {
+    use ndarray::{arr1, arr2};
+    use crate::base::{Value, IndexKey};
+    use crate::components::Component;
+    use crate::utilities::inference::infer_property;
+    use crate::proto;
+
+    #[test]
+    fn test_index_dimensionality() {
+        let indices: Value = arr1(&[0i64]).into_dyn().into();
+        let index = |data: Value| proto::Index {}.propagate_property(
+            &None,
+            indexmap![IndexKey::from("indices") => &indices],
+            indexmap!["data".into() => infer_property(&data, None).unwrap()], 0);
+
+        assert!(index(arr2(&[[1.], [2.]]).into_dyn().into()).is_ok());
+        assert!(index(arr1(&[1., 2.]).into_dyn().into()).is_err());
+    }
+}
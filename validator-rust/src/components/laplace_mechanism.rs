@@ -7,7 +7,7 @@ use crate::{proto, base, Warnable};
 use crate::components::{Component, Expandable};
 use crate::base::{Value, SensitivitySpace, ValueProperties, DataType, NodeProperties, IndexKey};
 use crate::utilities::{prepend, expand_mechanism};
-use crate::utilities::privacy::{spread_privacy_usage, get_epsilon, privacy_usage_check};
+use crate::utilities::privacy::{spread_privacy_usage, get_epsilon, privacy_usage_check, laplace_rdp_curve};
 use itertools::Itertools;
 use indexmap::map::IndexMap;
 
@@ -25,7 +25,7 @@ impl Component for proto::LaplaceMechanism {
             .ok_or_else(|| "privacy_definition must be defined")?;
 
         if privacy_definition.protect_floating_point {
-            return Err("Floating-point protections are enabled. The laplace mechanism is susceptible to floating-point attacks.".into())
+            return Err("Floating-point protections are enabled. The laplace mechanism is susceptible to floating-point attacks. Use the snapping mechanism instead.".into())
         }
 
         if privacy_definition.group_size == 0 {
@@ -62,6 +62,8 @@ impl Component for proto::LaplaceMechanism {
 
         data_property.releasable = true;
         data_property.aggregator = None;
+        // noise may move the release outside the bounds of the aggregate
+        data_property.nature = None;
 
         Ok(Warnable(data_property.into(), warnings))
     }
@@ -107,6 +109,19 @@ impl Mechanism for proto::LaplaceMechanism {
                 usage.effective_to_actual(1., *c_stab as f64, privacy_definition.group_size))
             .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
     }
+
+    fn get_rdp_curves(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<Vec<f64>>>> {
+        self.get_privacy_usage(privacy_definition, release_usage, properties)?
+            .map(|usages| usages.iter()
+                .map(|usage| Ok(laplace_rdp_curve(get_epsilon(usage)?)))
+                .collect::<Result<Vec<Vec<f64>>>>())
+            .transpose()
+    }
 }
 
 
@@ -4,6 +4,7 @@ use crate::{proto, base, Warnable};
 
 use crate::components::{Component, Named};
 use crate::base::{Value, ValueProperties, ArrayProperties, DataType, IndexKey, DataframeProperties};
+use crate::utilities::{get_argument, prepend};
 use indexmap::map::IndexMap;
 
 impl Component for proto::Materialize {
@@ -15,30 +16,80 @@ impl Component for proto::Materialize {
         node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
 
+        if self.file_format.to_lowercase() == "sqlite" && self.table.is_empty() {
+            return Err("table: must be set when the file format is sqlite".into())
+        }
+
+        let column_types = self.get_column_types(&public_arguments)?;
         let column_names = self.get_names(public_arguments, IndexMap::new(), None)?;
 
+        // csv columns are always read as strings
+        let column_types = match column_types {
+            Some(column_types) => {
+                if column_types.len() != column_names.len() {
+                    return Err("column_types: must have the same length as column_names".into())
+                }
+                column_types
+            }
+            None => column_names.iter().map(|_| DataType::Str).collect()
+        };
+
         Ok(ValueProperties::Dataframe(DataframeProperties {
-            children: column_names.into_iter()
-                .map(|name| (name, ValueProperties::Array(ArrayProperties {
+            children: column_names.into_iter().zip(column_types)
+                .map(|(name, data_type)| (name, ValueProperties::Array(ArrayProperties {
                     num_records: None,
                     num_columns: Some(1),
-                    nullity: true,
+                    // nulls are represented by NaN in floats and empty strings in strings
+                    nullity: data_type == DataType::Float || data_type == DataType::Str,
                     releasable: self.public,
                     c_stability: vec![1.],
                     aggregator: None,
                     nature: None,
-                    data_type: DataType::Str,
+                    data_type,
                     dataset_id: Some(node_id as i64),
                     // this is a library-wide assumption - that datasets initially have more than zero rows
                     is_not_empty: true,
                     dimensionality: Some(1),
                     group_id: vec![],
-                    naturally_ordered: true
+                    naturally_ordered: true,
+                    randomized_response: None
                 }))).collect(),
         }).into())
     }
 }
 
+impl proto::Materialize {
+    /// Retrieve the atomic type of each column, as specified for typed file formats.
+    ///
+    /// # Return
+    /// None if the file format is `csv`, where every column is read as a string.
+    pub fn get_column_types(&self, public_arguments: &IndexMap<base::IndexKey, &Value>) -> Result<Option<Vec<DataType>>> {
+        match self.file_format.to_lowercase().as_str() {
+            "" | "csv" => Ok(None),
+            "parquet" | "arrow" | "sqlite" => {
+                let column_types = get_argument(public_arguments, "column_types")
+                    .map_err(|_| Error::from("column_types: must be defined for parquet, arrow and sqlite file formats"))?
+                    .ref_array()?.ref_string().map_err(prepend("column_types:"))?;
+
+                if column_types.ndim() > 1 {
+                    return Err("column_types: dimensionality may not be greater than one".into())
+                }
+
+                column_types.iter()
+                    .map(|atomic_type| Ok(match atomic_type.to_lowercase().as_str() {
+                        "float" => DataType::Float,
+                        "int" => DataType::Int,
+                        "bool" => DataType::Bool,
+                        "string" => DataType::Str,
+                        _ => bail!("column_types: data type is not recognized. Must be one of \"float\", \"int\", \"bool\" or \"string\"")
+                    }))
+                    .collect::<Result<Vec<DataType>>>().map(Some)
+            }
+            _ => Err("file_format: must be one of \"csv\", \"parquet\", \"arrow\" or \"sqlite\"".into())
+        }
+    }
+}
+
 impl Named for proto::Materialize {
     fn get_names(
         &self,
@@ -18,9 +18,12 @@ mod count;
 mod covariance;
 mod column_bind;
 mod digitize;
+mod distinct_sketch;
+mod distinct_sketch_estimate;
 mod dp_count;
 mod dp_variance;
 mod dp_covariance;
+mod dp_linear_regression;
 mod dp_histogram;
 mod dp_maximum;
 mod dp_median;
@@ -39,13 +42,23 @@ mod map;
 mod materialize;
 pub mod partition;
 mod quantile;
+mod randomized_response_histogram;
 mod reshape;
 mod mean;
+mod above_threshold;
+mod dp_key_selection;
 mod exponential_mechanism;
+mod discrete_gaussian_mechanism;
 mod gaussian_mechanism;
 mod laplace_mechanism;
+mod permute_and_flip_mechanism;
+mod randomized_response;
+mod report_noisy_max_mechanism;
 mod simple_geometric_mechanism;
+mod smooth_quantile_mechanism;
+mod snapping_mechanism;
 mod resize;
+mod solve_linear_regression;
 mod sum;
 mod union;
 mod variance;
@@ -53,6 +66,7 @@ mod variance;
 use crate::base::{IndexKey, Value, NodeProperties, SensitivitySpace, ValueProperties};
 use crate::{proto, Warnable, base};
 use crate::utilities::json::{JSONRelease};
+use crate::utilities::privacy::privacy_usage_to_rdp_curve;
 use indexmap::map::IndexMap;
 
 /// Universal Component trait
@@ -141,6 +155,32 @@ pub trait Mechanism {
         release_usage: Option<&Vec<proto::PrivacyUsage>>,
         properties: &NodeProperties
     ) -> Result<Option<Vec<proto::PrivacyUsage>>>;
+
+    /// Extraction of the Rényi differential privacy curves of the component.
+    ///
+    /// Each curve is evaluated at the orders in [RDP_ORDERS](../utilities/privacy/constant.RDP_ORDERS.html).
+    /// By default, the curves are derived from the privacy usages, which must be pure or concentrated.
+    ///
+    /// # Arguments
+    /// * `self` - the protobuf object corresponding to the prost protobuf struct, containing an upper bound on privacy usage
+    /// * `privacy_definition` - the definition of privacy under which the sensitivity is to be computed
+    /// * `release_usage` - optionally, the privacy actually used by the mechanism (if it has already been released)
+    /// * `properties` - properties of the arguments to the component
+    ///
+    /// # Returns
+    /// One RDP curve for each privacy usage, after group_size and c_stability have been taken into account.
+    fn get_rdp_curves(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<Vec<f64>>>> {
+        self.get_privacy_usage(privacy_definition, release_usage, properties)?
+            .map(|usages| usages.iter()
+                .map(privacy_usage_to_rdp_curve)
+                .collect::<Result<Vec<Vec<f64>>>>())
+            .transpose()
+    }
 }
 
 /// Sensitivity component trait
@@ -189,6 +229,14 @@ pub trait Accuracy {
     ) -> Result<Option<Vec<proto::Accuracy>>>;
 }
 
+/// Context of the node being summarized in a report
+pub struct ReportContext<'a> {
+    /// the privacy definition the graph was validated under
+    pub privacy_definition: &'a proto::PrivacyDefinition,
+    /// the id of the node in the graph
+    pub node_id: u32,
+}
+
 /// Report component trait
 ///
 /// Reportable components correspond to a computation that a researcher may want a JSON summary for
@@ -196,7 +244,7 @@ pub trait Report {
     /// Summarize the relevant metadata around a computation in a readable, JSON-serializable format.
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -249,11 +297,12 @@ impl Component for proto::Component {
 
         propagate_property!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, ColumnBind, Count, Covariance, Digitize,
+            Cast, Clamp, ColumnBind, Count, Covariance, Digitize, DistinctSketch, DistinctSketchEstimate,
             Filter, Histogram, Impute, Index, Literal, Materialize, Mean,
-            Partition, Quantile, RawMoment, Reshape, Resize, Sum, Union, Variance,
+            Partition, Quantile, RandomizedResponseHistogram, RawMoment, Reshape, Resize, SolveLinearRegression,
+            Sum, Union, Variance,
 
-            ExponentialMechanism, GaussianMechanism, LaplaceMechanism, SimpleGeometricMechanism,
+            AboveThreshold, DiscreteGaussianMechanism, DpKeySelection, ExponentialMechanism, GaussianMechanism, LaplaceMechanism, PermuteAndFlipMechanism, RandomizedResponse, ReportNoisyMaxMechanism, SimpleGeometricMechanism, SmoothQuantileMechanism, SnappingMechanism,
 
             Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
             Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract
@@ -324,12 +373,12 @@ impl Expandable for proto::Component {
 
         expand_component!(
             // INSERT COMPONENT LIST
-            Clamp, Digitize, Histogram, Impute, Map, Maximum, Median, Minimum, Partition, Resize,
+            Clamp, Digitize, Histogram, Impute, Map, Maximum, Median, Minimum, Partition, RandomizedResponseHistogram, Resize,
 
-            DpCount, DpCovariance, DpHistogram, DpMaximum, DpMean, DpMedian,
+            DpCount, DpCovariance, DpHistogram, DpLinearRegression, DpMaximum, DpMean, DpMedian,
             DpMinimum, DpQuantile, DpRawMoment, DpSum, DpVariance,
 
-            ExponentialMechanism, GaussianMechanism, LaplaceMechanism, SimpleGeometricMechanism,
+            AboveThreshold, DiscreteGaussianMechanism, DpKeySelection, ExponentialMechanism, GaussianMechanism, LaplaceMechanism, PermuteAndFlipMechanism, RandomizedResponse, ReportNoisyMaxMechanism, SimpleGeometricMechanism, SmoothQuantileMechanism, SnappingMechanism,
 
             ToBool, ToFloat, ToInt, ToString
         );
@@ -365,7 +414,37 @@ impl Mechanism for proto::Component {
 
         get_privacy_usage!(
             // INSERT COMPONENT LIST
-            ExponentialMechanism, GaussianMechanism, LaplaceMechanism, SimpleGeometricMechanism
+            AboveThreshold, DiscreteGaussianMechanism, DpKeySelection, ExponentialMechanism, GaussianMechanism, LaplaceMechanism, PermuteAndFlipMechanism, RandomizedResponse, ReportNoisyMaxMechanism, SimpleGeometricMechanism, SmoothQuantileMechanism, SnappingMechanism
+        );
+
+        Ok(None)
+    }
+
+    fn get_rdp_curves(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<Vec<f64>>>> {
+        let variant = self.variant.as_ref()
+            .ok_or_else(|| "variant: must be defined")?;
+
+        macro_rules! get_rdp_curves {
+            ($( $variant:ident ),*) => {
+                {
+                    $(
+                       if let proto::component::Variant::$variant(x) = variant {
+                            return x.get_rdp_curves(privacy_definition, release_usage, properties)
+                                .chain_err(|| format!("node specification {:?}:", variant))
+                       }
+                    )*
+                }
+            }
+        }
+
+        get_rdp_curves!(
+            // INSERT COMPONENT LIST
+            AboveThreshold, DiscreteGaussianMechanism, DpKeySelection, ExponentialMechanism, GaussianMechanism, LaplaceMechanism, PermuteAndFlipMechanism, RandomizedResponse, ReportNoisyMaxMechanism, SimpleGeometricMechanism, SmoothQuantileMechanism, SnappingMechanism
         );
 
         Ok(None)
@@ -398,7 +477,7 @@ impl Sensitivity for proto::component::Variant {
 
         compute_sensitivity!(
             // INSERT COMPONENT LIST
-            Count, Covariance, Histogram, Mean, Quantile, RawMoment, Sum, Union, Variance
+            Count, Covariance, DistinctSketch, Histogram, Mean, Quantile, RawMoment, Sum, Union, Variance
         );
 
         Err(format!("sensitivity is not implemented for proto component {:?}", self).into())
@@ -432,9 +511,12 @@ impl Accuracy for proto::Component {
         }
 
         accuracy_to_privacy_usage!(
+             ExponentialMechanism,
              LaplaceMechanism,
              GaussianMechanism,
-             SimpleGeometricMechanism
+             RandomizedResponse,
+             SimpleGeometricMechanism,
+             SmoothQuantileMechanism
         );
 
         Ok(None)
@@ -466,9 +548,12 @@ impl Accuracy for proto::Component {
         }
 
         privacy_usage_to_accuracy!(
+            ExponentialMechanism,
             LaplaceMechanism,
             GaussianMechanism,
-            SimpleGeometricMechanism
+            RandomizedResponse,
+            SimpleGeometricMechanism,
+            SmoothQuantileMechanism
         );
 
         Ok(None)
@@ -481,7 +566,7 @@ impl Report for proto::Component {
     /// This utility delegates evaluation to the concrete implementation of each component variant.
     fn summarize(
         &self,
-        node_id: u32,
+        context: &ReportContext,
         component: &proto::Component,
         public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
@@ -496,7 +581,7 @@ impl Report for proto::Component {
                 {
                     $(
                        if let proto::component::Variant::$variant(x) = variant {
-                            return x.summarize(node_id, component, public_arguments,
+                            return x.summarize(context, component, public_arguments,
                                  properties, release, variable_names)
                                 .chain_err(|| format!("node specification: {:?}:", variant))
                        }
@@ -507,7 +592,7 @@ impl Report for proto::Component {
 
         summarize!(
             // INSERT COMPONENT LIST
-            DpCount, DpCovariance, DpHistogram, DpMaximum, DpMean, DpMinimum, DpQuantile,
+            DpCount, DpCovariance, DpHistogram, DpLinearRegression, DpMaximum, DpMean, DpMedian, DpMinimum, DpQuantile,
             DpRawMoment, DpSum, DpVariance
         );
 
@@ -14,7 +14,7 @@ impl Component for proto::Quantile {
     fn propagate_property(
         &self,
         _privacy_definition: &Option<proto::PrivacyDefinition>,
-        public_arguments: IndexMap<base::IndexKey, &Value>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
@@ -31,17 +31,28 @@ impl Component for proto::Quantile {
             return Err("data: atomic type must be numeric".into());
         }
 
-        Ok(match public_arguments.get::<IndexKey>(&"candidates".into()) {
-            Some(candidates) => {
-                let candidates = candidates.ref_jagged()?;
+        // candidates are read from their properties, so that utilities may be propagated before the candidates are released
+        let candidates_num_records = match properties.get::<IndexKey>(&"candidates".into()) {
+            Some(candidates_property) => {
+                let candidates_property = candidates_property.jagged()
+                    .map_err(prepend("candidates:"))?;
 
-                if data_property.data_type != candidates.data_type() {
+                if !candidates_property.releasable {
+                    return Err("candidates: must be public".into())
+                }
+                if data_property.data_type != candidates_property.data_type {
                     return Err("data_type of data must match data_type of candidates".into())
                 }
+                Some(candidates_property.num_records()?)
+            },
+            None => None
+        };
 
+        Ok(match candidates_num_records {
+            Some(candidates_num_records) => {
                 let num_columns = data_property.num_columns()?;
                 ValueProperties::Jagged(JaggedProperties {
-                    num_records: Some(candidates.num_records()),
+                    num_records: Some(candidates_num_records),
                     nullity: false,
                     aggregator: Some(AggregatorProperties {
                         component: proto::component::Variant::Quantile(self.clone()),
@@ -0,0 +1,315 @@
+use crate::errors::*;
+
+use crate::components::{Accuracy, Mechanism};
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, Jagged, ValueProperties, DataType, NodeProperties, IndexKey, Nature, NatureCategorical, RandomizedResponseProperties};
+use crate::utilities::prepend;
+use crate::utilities::privacy::{spread_privacy_usage, get_epsilon, privacy_usage_check, randomized_response_probabilities};
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::RandomizedResponse {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into())
+        }
+
+        // a report is released for every record, so the number of records is disclosed
+        if privacy_definition.neighboring != proto::privacy_definition::Neighboring::Substitute as i32 {
+            return Err("randomized response requires substitute neighboring".into())
+        }
+
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain exactly one column".into())
+        }
+
+        let encoding = self.encoding.to_lowercase();
+        let categories = match encoding.as_str() {
+            "binary" => {
+                if data_property.data_type != DataType::Bool {
+                    return Err("data: binary randomized response requires boolean data".into())
+                }
+                Jagged::Bool(vec![vec![false, true]])
+            },
+            "generalized" | "unary" => {
+                let categories_property = properties.get::<IndexKey>(&"categories".into())
+                    .ok_or("categories: missing")?.jagged()
+                    .map_err(prepend("categories:"))?;
+                if !categories_property.releasable {
+                    return Err("categories: must be public".into())
+                }
+                // when only properties are known, as when estimating accuracy, fall back to the categorical nature
+                let categories = match public_arguments.get::<IndexKey>(&"categories".into()) {
+                    Some(categories) => {
+                        let categories = categories.ref_jagged()?.clone();
+                        if categories.deduplicate()?.num_records() != categories.num_records() {
+                            return Err("categories: must be distinct".into())
+                        }
+                        categories
+                    },
+                    None => categories_property.nature.as_ref()
+                        .ok_or("categories: must be known")?.categorical()?.categories.clone()
+                };
+                if categories.num_columns() != 1 {
+                    return Err("categories: must contain exactly one column".into())
+                }
+                match (&categories, &data_property.data_type) {
+                    (Jagged::Bool(_), DataType::Bool) | (Jagged::Int(_), DataType::Int) | (Jagged::Str(_), DataType::Str) => (),
+                    (Jagged::Float(_), _) => return Err("categories: may not be floats".into()),
+                    _ => return Err("data and categories must be homogeneously typed".into())
+                }
+                categories
+            },
+            _ => return Err("encoding: must be one of [Binary, Generalized, Unary]".into())
+        };
+        let num_categories = categories.num_records()[0] as usize;
+
+        let privacy_usage = get_local_privacy_usage(&self.privacy_usage)?;
+        // the probabilities must be computable
+        randomized_response_probabilities(&encoding, get_epsilon(&privacy_usage)?, num_categories)?;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        // each report is privatized independently, so the reports are releasable row by row
+        data_property.releasable = true;
+        if encoding == "unary" {
+            data_property.data_type = DataType::Bool;
+            data_property.num_columns = Some(num_categories as i64);
+            data_property.dimensionality = Some(2);
+            data_property.c_stability = vec![data_property.c_stability[0]; num_categories];
+            data_property.nature = Some(Nature::Categorical(NatureCategorical {
+                categories: Jagged::Bool(vec![vec![false, true]; num_categories])
+            }));
+        } else {
+            data_property.nature = Some(Nature::Categorical(NatureCategorical { categories }));
+        }
+
+        // properties are propagated from the expanded component, so the usage is already effective
+        data_property.randomized_response = Some(RandomizedResponseProperties {
+            encoding: self.encoding.clone(),
+            num_categories: num_categories as i64,
+            privacy_usage
+        });
+
+        Ok(Warnable(data_property.into(), warnings))
+    }
+}
+
+impl Expandable for proto::RandomizedResponse {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        _maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy definition must be defined")?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // reduce the usage allowed to each report based on c-stability and group size
+        let privacy_usage = get_local_privacy_usage(&self.privacy_usage)?
+            .actual_to_effective(1., data_property.c_stability[0], privacy_definition.group_size)?;
+
+        let mut component = component.clone();
+        if let Some(proto::component::Variant::RandomizedResponse(variant)) = component.variant.as_mut() {
+            variant.privacy_usage = vec![privacy_usage];
+        }
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::RandomizedResponse {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .zip(data_property.c_stability.iter())
+            .map(|(usage, c_stab)|
+                usage.effective_to_actual(1., *c_stab, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+/// The accuracy of the category counts estimated by RandomizedResponseHistogram.
+///
+/// Each tally is a sum of independent bernoulli reports, so by Hoeffding's inequality
+///     the debiased count is within sqrt(n ln(2 / alpha) / 2) / (p - q) of the true count with probability 1 - alpha.
+impl Accuracy for proto::RandomizedResponse {
+    fn accuracy_to_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let num_records = data_property.num_records()? as f64;
+        let num_categories = get_num_categories(&self.encoding, properties)? as f64;
+
+        // the smallest epsilon that meets every requested accuracy
+        let epsilon = accuracies.values.iter()
+            .map(|accuracy| {
+                let gap = (num_records * (2. / accuracy.alpha).ln() / 2.).sqrt() / accuracy.value;
+                if gap >= 1. {
+                    return Err(format!("an accuracy of {} is not attainable with {} records", accuracy.value, num_records).into())
+                }
+                Ok(match self.encoding.to_lowercase().as_str() {
+                    "unary" => 2. * ((1. + gap) / (1. - gap)).ln(),
+                    _ => ((1. + gap * (num_categories - 1.)) / (1. - gap)).ln()
+                })
+            })
+            .collect::<Result<Vec<f64>>>()?.into_iter()
+            .fold(0., f64::max);
+
+        Ok(Some(vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon, delta: 0.
+            }))
+        }.effective_to_actual(1., data_property.c_stability[0], privacy_definition.group_size)?]))
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let num_records = data_property.num_records()? as f64;
+        let num_categories = get_num_categories(&self.encoding, properties)?;
+
+        // accuracies are computed on the expanded component, whose usage is already effective
+        let epsilon = get_epsilon(&get_local_privacy_usage(&self.privacy_usage)?)?;
+        let (p, q) = randomized_response_probabilities(&self.encoding, epsilon, num_categories)?;
+
+        let value = (num_records * (2. / alpha).ln() / 2.).sqrt() / (p - q);
+        Ok(Some((0..num_categories).map(|_| proto::Accuracy { value, alpha }).collect()))
+    }
+}
+
+/// Retrieve the privacy usage of a single report, which must be pure.
+pub fn get_local_privacy_usage(privacy_usage: &[proto::PrivacyUsage]) -> Result<proto::PrivacyUsage> {
+    let privacy_usage = spread_privacy_usage(privacy_usage, 1)?.remove(0);
+    match privacy_usage.distance.as_ref().ok_or("privacy_usage: distance must be defined")? {
+        proto::privacy_usage::Distance::Approximate(approximate) => if approximate.delta != 0. {
+            return Err("privacy_usage: delta must be zero for randomized response".into())
+        },
+        _ => return Err("privacy_usage: randomized response requires an approximate privacy usage".into())
+    }
+    Ok(privacy_usage)
+}
+
+/// Retrieve the number of categories a report may take.
+pub fn get_num_categories(encoding: &str, properties: &NodeProperties) -> Result<usize> {
+    if encoding.to_lowercase() == "binary" {
+        return Ok(2)
+    }
+    let num_records = properties.get::<IndexKey>(&"categories".into())
+        .ok_or("categories: missing")?.jagged()
+        .map_err(prepend("categories:"))?.num_records()?;
+    match num_records.as_slice() {
+        [num_categories] => Ok(*num_categories as usize),
+        _ => Err("categories: must contain exactly one column".into())
+    }
+}
+
+#[cfg(test)]
+mod test_randomized_response {
+    use crate::proto;
+    use crate::base::{Value, Jagged, IndexKey};
+    use crate::utilities::inference::infer_property;
+    use ndarray::Array1;
+    use indexmap::map::IndexMap;
+
+    #[test]
+    fn test_accuracy_round_trip() {
+        // protecting groups of two halves the usage of each report, so the estimates are less accurate
+        let individual = accuracy_round_trip(1);
+        let grouped = accuracy_round_trip(2);
+        individual.iter().zip(grouped.iter())
+            .for_each(|(individual, grouped)| assert!(individual < grouped, "{} >= {}", individual, grouped));
+    }
+
+    fn accuracy_round_trip(group_size: u32) -> Vec<f64> {
+        let privacy_definition = proto::PrivacyDefinition {
+            neighboring: proto::privacy_definition::Neighboring::Substitute as i32,
+            group_size,
+            ..Default::default()
+        };
+        let data: Value = Array1::from_elem(1000, 1).into_dyn().into();
+        let categories = Value::Jagged(Jagged::Int(vec![vec![0, 1, 2, 3]]));
+        let properties: IndexMap<IndexKey, _> = indexmap![
+            IndexKey::from("data") => infer_property(&data, None).unwrap(),
+            IndexKey::from("categories") => infer_property(&categories, None).unwrap()];
+
+        ["Generalized", "Unary"].iter().map(|encoding| {
+            let component = proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    IndexKey::from("data") => 0, IndexKey::from("categories") => 1])),
+                variant: Some(proto::component::Variant::RandomizedResponse(proto::RandomizedResponse {
+                    encoding: encoding.to_string(),
+                    privacy_usage: vec![proto::PrivacyUsage {
+                        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                            epsilon: 1., delta: 0.
+                        }))
+                    }]
+                })),
+                omit: false,
+                submission: 0
+            };
+
+            let accuracies = crate::privacy_usage_to_accuracy(
+                component.clone(), privacy_definition.clone(), properties.clone(), 0.05).unwrap();
+            assert_eq!(accuracies.values.len(), 4);
+            let value = accuracies.values[0].value;
+
+            let usages = crate::accuracy_to_privacy_usage(
+                component, privacy_definition.clone(), properties.clone(), accuracies).unwrap();
+            let epsilon = crate::utilities::privacy::get_epsilon(&usages.values[0]).unwrap();
+            assert!((epsilon - 1.).abs() < 1e-10, "{}, group size {}: epsilon {}", encoding, group_size, epsilon);
+            value
+        }).collect()
+    }
+}
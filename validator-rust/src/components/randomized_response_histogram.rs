@@ -0,0 +1,121 @@
+use crate::errors::*;
+
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, Jagged, ValueProperties, DataType, ArrayProperties, IndexKey, RandomizedResponseProperties};
+use crate::utilities::{prepend, get_argument};
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::RandomizedResponseHistogram {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            return Err("data: must be reports released by RandomizedResponse".into())
+        }
+
+        let num_columns = data_property.num_columns()?;
+        let num_categories = match self.encoding.to_lowercase().as_str() {
+            "binary" => {
+                if data_property.data_type != DataType::Bool || num_columns != 1 {
+                    return Err("data: binary reports must be a single boolean column".into())
+                }
+                2
+            },
+            encoding @ "generalized" | encoding @ "unary" => {
+                let categories = get_argument(&public_arguments, "categories")?.ref_jagged()?;
+                if categories.num_columns() != 1 {
+                    return Err("categories: must contain exactly one column".into())
+                }
+                let num_categories = categories.num_records()[0];
+
+                if encoding == "unary" {
+                    if data_property.data_type != DataType::Bool || num_columns != num_categories {
+                        return Err("data: unary reports must contain one boolean column per category".into())
+                    }
+                } else {
+                    if num_columns != 1 {
+                        return Err("data: generalized reports must be a single column".into())
+                    }
+                    match (categories, &data_property.data_type) {
+                        (Jagged::Bool(_), DataType::Bool) | (Jagged::Int(_), DataType::Int) | (Jagged::Str(_), DataType::Str) => (),
+                        _ => return Err("data and categories must be homogeneously typed".into())
+                    }
+                }
+                num_categories
+            },
+            _ => return Err("encoding: must be one of [Binary, Generalized, Unary]".into())
+        };
+
+        let randomized_response = get_randomized_response(&data_property)?;
+        if randomized_response.encoding.to_lowercase() != self.encoding.to_lowercase() {
+            return Err("encoding: must match the encoding passed to RandomizedResponse".into())
+        }
+        if randomized_response.num_categories != num_categories {
+            return Err("categories: must match the categories passed to RandomizedResponse".into())
+        }
+
+        Ok(Warnable::new(ArrayProperties {
+            num_records: Some(num_categories),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Float,
+            dataset_id: None,
+            is_not_empty: true,
+            dimensionality: Some(1),
+            group_id: vec![],
+            naturally_ordered: true,
+            randomized_response: None
+        }.into()))
+    }
+}
+
+impl Expandable for proto::RandomizedResponseHistogram {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        _maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+
+        let mut expansion = base::ComponentExpansion::default();
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // debias with the usage that the expanded RandomizedResponse spent on each report
+        let privacy_usage = get_randomized_response(data_property)?.privacy_usage.clone();
+
+        let mut component = component.clone();
+        if let Some(proto::component::Variant::RandomizedResponseHistogram(variant)) = component.variant.as_mut() {
+            variant.privacy_usage = vec![privacy_usage];
+        }
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+/// Retrieve the properties of the RandomizedResponse that released the reports.
+fn get_randomized_response(data_property: &ArrayProperties) -> Result<&RandomizedResponseProperties> {
+    data_property.randomized_response.as_ref()
+        .ok_or_else(|| "data: must be reports released by RandomizedResponse".into())
+}
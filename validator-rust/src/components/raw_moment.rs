@@ -3,7 +3,7 @@ use crate::errors::*;
 use crate::{proto, base, Warnable, Float};
 
 use crate::components::{Component, Sensitivity};
-use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, Nature, NatureContinuous, Vector1DNull};
 use crate::utilities::prepend;
 use ndarray::prelude::*;
 use std::convert::TryFrom;
@@ -12,7 +12,7 @@ use indexmap::map::IndexMap;
 impl Component for proto::RawMoment {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         node_id: u32
@@ -30,6 +30,10 @@ impl Component for proto::RawMoment {
         }
         data_property.assert_is_not_empty()?;
 
+        if privacy_definition.as_ref().map(|v| v.protect_overflow).unwrap_or(false) {
+            data_property.assert_accumulator_bounded(self.order).map_err(prepend("data:"))?;
+        }
+
         let num_columns = data_property.num_columns()?;
         // save a snapshot of the state when aggregating
         data_property.aggregator = Some(AggregatorProperties {
@@ -39,6 +43,26 @@ impl Component for proto::RawMoment {
                 vec![1, num_columns as usize],
                 (0..num_columns).map(|_| 1.).collect())?.into_dyn().into()
         });
+
+        // the mean of the k-th powers lies within the image of [lower, upper] under x^k
+        let order = self.order as i32;
+        data_property.nature = match (data_property.lower_float(), data_property.upper_float()) {
+            (Ok(lower), Ok(upper)) => {
+                let (lower, upper): (Vec<Option<Float>>, Vec<Option<Float>>) = lower.into_iter().zip(upper)
+                    .map(|(l, u)| if order % 2 == 1 {
+                        (Some(l.powi(order)), Some(u.powi(order)))
+                    } else {
+                        let inner = if l <= 0. && 0. <= u { 0. } else { l.abs().min(u.abs()) };
+                        (Some(inner.powi(order)), Some(l.abs().max(u.abs()).powi(order)))
+                    })
+                    .unzip();
+                Some(Nature::Continuous(NatureContinuous {
+                    lower: Vector1DNull::Float(lower),
+                    upper: Vector1DNull::Float(upper)
+                }))
+            }
+            _ => None
+        };
         data_property.num_records = Some(1);
         data_property.dataset_id = Some(node_id as i64);
         Ok(ValueProperties::Array(data_property).into())
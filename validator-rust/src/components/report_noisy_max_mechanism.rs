@@ -0,0 +1,93 @@
+use crate::errors::*;
+
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable, Mechanism};
+use crate::components::exponential_mechanism::{propagate_selection_property, expand_selection_component, get_selection_privacy_usage};
+use crate::base::{Value, ValueProperties, NodeProperties, IndexKey};
+use indexmap::map::IndexMap;
+
+impl Component for proto::ReportNoisyMaxMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        if let Some(privacy_definition) = privacy_definition {
+            // every noise distribution is sampled with floating-point arithmetic
+            if privacy_definition.protect_floating_point {
+                return Err("Floating-point protections are enabled. Report noisy max perturbs utilities with floating-point noise.".into())
+            }
+        }
+
+        if !["gumbel", "laplace", "exponential"].contains(&self.noise.to_lowercase().as_str()) {
+            return Err("noise: must be one of [Gumbel, Laplace, Exponential]".into());
+        }
+
+        propagate_selection_property(privacy_definition, &self.privacy_usage, &properties)
+    }
+}
+
+impl Expandable for proto::ReportNoisyMaxMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        expand_selection_component(privacy_definition, component, properties, component_id, maximum_id)
+    }
+}
+
+impl Mechanism for proto::ReportNoisyMaxMechanism {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        get_selection_privacy_usage(
+            privacy_definition, release_usage.unwrap_or(&self.privacy_usage), properties)
+    }
+}
+
+#[cfg(test)]
+mod test_report_noisy_max_mechanism {
+    use crate::base::{test_data, Value, Jagged};
+    use crate::components::clamp::test_clamp;
+    use crate::proto;
+
+    fn analysis_dp_median(mechanism: &str, protect_floating_point: bool) -> crate::errors::Result<()> {
+        let (mut analysis, clamped) = test_clamp::utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), Some(0.0.into()), Some(10.0.into()));
+        analysis.privacy_definition.protect_floating_point = protect_floating_point;
+
+        let candidates = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![(0..11).map(|v| v as f64).collect()])))
+            .value_public(true).build();
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1.,
+                delta: 0.,
+            }))
+        }];
+        let median = analysis.dp_median(clamped, privacy_usage)
+            .candidates(candidates)
+            .mechanism(mechanism.to_string())
+            .build();
+        analysis.properties(median).map(|_| ())
+    }
+
+    #[test]
+    fn test_floating_point_protection() {
+        for mechanism in &["ReportNoisyMax", "ReportNoisyMaxLaplace", "ReportNoisyMaxExponential"] {
+            assert!(format!("{:?}", analysis_dp_median(mechanism, true).unwrap_err()).contains("Floating-point protections are enabled"));
+            analysis_dp_median(mechanism, false).unwrap();
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use crate::errors::*;
+
+use crate::components::{Accuracy, Mechanism};
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, ValueProperties, DataType, NodeProperties, IndexKey};
+use crate::utilities::{prepend, get_literal};
+use crate::utilities::inference::infer_property;
+use crate::utilities::privacy::{spread_privacy_usage, get_epsilon, privacy_usage_check, cauchy_smooth_sensitivity_parameters};
+use ndarray::arr1;
+use itertools::Itertools;
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::SmoothQuantileMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. The smooth quantile mechanism samples cauchy noise with floating-point arithmetic.".into())
+        }
+
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into())
+        }
+
+        // the smooth sensitivity is computed over neighboring datasets of the same size
+        if privacy_definition.neighboring != proto::privacy_definition::Neighboring::Substitute as i32 {
+            return Err("the smooth quantile mechanism requires substitute neighboring".into())
+        }
+
+        if !(0. ..=1.).contains(&self.alpha) {
+            return Err("alpha: must be within [0, 1]".into())
+        }
+
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+        data_property.assert_is_not_empty()?;
+
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        // the smooth sensitivity is bounded by the range of the data
+        let lower = data_property.lower_float().map_err(prepend("data:"))?;
+        let upper = data_property.upper_float().map_err(prepend("data:"))?;
+        if lower.iter().zip(upper.iter()).any(|(l, u)| l > u) {
+            return Err("data: lower bound may not be greater than upper bound".into())
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?)
+            .ok_or("privacy_usage: must be defined")??;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        data_property.num_records = Some(1);
+        data_property.releasable = true;
+        data_property.data_type = DataType::Float;
+        data_property.nature = None;
+
+        Ok(Warnable(data_property.into(), warnings))
+    }
+}
+
+impl Expandable for proto::SmoothQuantileMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+
+        let mut expansion = base::ComponentExpansion::default();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy definition must be defined")?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let mut component = component.clone();
+
+        // always overwrite the bounds. These are not something a user may configure
+        for (name, bounds) in [("lower", data_property.lower_float()?), ("upper", data_property.upper_float()?)].iter() {
+            maximum_id += 1;
+            let (patch_node, release) = get_literal(arr1(bounds).into_dyn().into(), component.submission)?;
+            expansion.computation_graph.insert(maximum_id, patch_node);
+            expansion.properties.insert(maximum_id, infer_property(&release.value, None)?);
+            expansion.releases.insert(maximum_id, release);
+            component.insert_argument(&(*name).into(), maximum_id);
+        }
+
+        // reduce the usage of each column based on c-stability and group size
+        let privacy_usage = spread_privacy_usage(&self.privacy_usage, data_property.num_columns()? as usize)?.into_iter()
+            .zip(data_property.c_stability.iter())
+            .map(|(usage, c_stab)| usage.actual_to_effective(1., *c_stab, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+
+        if let Some(proto::component::Variant::SmoothQuantileMechanism(variant)) = component.variant.as_mut() {
+            variant.privacy_usage = privacy_usage;
+        }
+        expansion.computation_graph.insert(component_id, component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::SmoothQuantileMechanism {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .zip(data_property.c_stability.iter())
+            .map(|(usage, c_stab)|
+                usage.effective_to_actual(1., *c_stab, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+/// The accuracy of the smooth quantile mechanism.
+///
+/// The smooth sensitivity depends on the data, so the accuracy is derived from its upper bound, the range of the data.
+/// This is a worst case, and releases from well-concentrated data are typically much more accurate.
+impl Accuracy for proto::SmoothQuantileMechanism {
+    fn accuracy_to_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        accuracies: &proto::Accuracies,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let ranges = get_ranges(properties)?;
+        if ranges.len() != accuracies.values.len() {
+            return Err("accuracies: must contain one accuracy per column".into())
+        }
+
+        // the scale of the noise is proportional to epsilon
+        let noise_fraction = cauchy_smooth_sensitivity_parameters(1.).0;
+
+        Ok(Some(ranges.into_iter()
+            .zip(accuracies.values.iter())
+            .zip(data_property.c_stability.iter())
+            .map(|((range, accuracy), c_stab)| proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon: range * cauchy_quantile(accuracy.alpha) / (accuracy.value * noise_fraction),
+                    delta: 0.,
+                }))
+            }.effective_to_actual(1., *c_stab, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()?))
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        _privacy_definition: &proto::PrivacyDefinition,
+        properties: &base::NodeProperties,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let ranges = get_ranges(properties)?;
+        let usages = spread_privacy_usage(&self.privacy_usage, ranges.len())?;
+
+        Ok(Some(ranges.into_iter().zip(usages.iter())
+            .map(|(range, usage)| Ok(proto::Accuracy {
+                value: range * cauchy_quantile(alpha) / cauchy_smooth_sensitivity_parameters(get_epsilon(usage)?).0,
+                alpha,
+            }))
+            .collect::<Result<Vec<proto::Accuracy>>>()?))
+    }
+}
+
+/// The radius containing a standard cauchy draw with probability 1 - alpha.
+fn cauchy_quantile(alpha: f64) -> f64 {
+    (std::f64::consts::PI * (1. - alpha) / 2.).tan()
+}
+
+fn get_ranges(properties: &NodeProperties) -> Result<Vec<f64>> {
+    let data_property = properties.get::<IndexKey>(&"data".into())
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?;
+
+    Ok(data_property.lower_float()?.into_iter()
+        .zip(data_property.upper_float()?)
+        .map(|(lower, upper)| upper - lower)
+        .collect())
+}
+
+#[cfg(test)]
+mod test_smooth_quantile_mechanism {
+    use crate::proto;
+    use crate::base::{Value, IndexKey};
+    use crate::utilities::inference::infer_property;
+    use ndarray::Array2;
+    use indexmap::map::IndexMap;
+
+    #[test]
+    fn test_accuracy_round_trip() {
+        let privacy_definition = proto::PrivacyDefinition {
+            neighboring: proto::privacy_definition::Neighboring::Substitute as i32,
+            group_size: 1,
+            ..Default::default()
+        };
+        let data: Value = Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into();
+        let properties: IndexMap<IndexKey, _> = indexmap![
+            IndexKey::from("data") => infer_property(&data, None).unwrap()];
+
+        let component = proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![IndexKey::from("data") => 0])),
+            variant: Some(proto::component::Variant::SmoothQuantileMechanism(proto::SmoothQuantileMechanism {
+                alpha: 0.5,
+                privacy_usage: vec![proto::PrivacyUsage {
+                    distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                        epsilon: 1., delta: 0.
+                    }))
+                }]
+            })),
+            omit: false,
+            submission: 0
+        };
+
+        let accuracies = crate::privacy_usage_to_accuracy(
+            component.clone(), privacy_definition.clone(), properties.clone(), 0.05).unwrap();
+        // the range of the data is 9
+        assert!((accuracies.values[0].value - 9. * (std::f64::consts::PI * 0.475).tan() / 0.5).abs() < 1e-10);
+
+        let usages = crate::accuracy_to_privacy_usage(
+            component, privacy_definition, properties, accuracies).unwrap();
+        let epsilon = crate::utilities::privacy::get_epsilon(&usages.values[0]).unwrap();
+        assert!((epsilon - 1.).abs() < 1e-10);
+    }
+}
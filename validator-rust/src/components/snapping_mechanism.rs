@@ -0,0 +1,258 @@
+use crate::errors::*;
+
+use crate::components::{Sensitivity, Mechanism};
+use crate::{proto, base, Warnable};
+
+use crate::components::{Component, Expandable};
+use crate::base::{Value, SensitivitySpace, ValueProperties, DataType, NodeProperties, IndexKey, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::{prepend, expand_mechanism, get_literal};
+use crate::utilities::privacy::{get_epsilon, privacy_usage_check, laplace_rdp_curve};
+use itertools::Itertools;
+use indexmap::map::IndexMap;
+use crate::utilities::inference::infer_property;
+use ndarray::arr1;
+
+
+impl Component for proto::SnappingMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into())
+        }
+
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into());
+        }
+
+        // clamping bounds must be supplied, or known from the nature of the aggregate
+        let num_columns = data_property.num_columns()?;
+        let lower = match public_arguments.get::<IndexKey>(&"lower".into()) {
+            Some(&lower) => lower.ref_array()?.clone().vec_float(Some(num_columns))
+                .map_err(prepend("lower:"))?,
+            None => data_property.lower_float().map_err(prepend("data:"))?
+        };
+        let upper = match public_arguments.get::<IndexKey>(&"upper".into()) {
+            Some(&upper) => upper.ref_array()?.clone().vec_float(Some(num_columns))
+                .map_err(prepend("upper:"))?,
+            None => data_property.upper_float().map_err(prepend("data:"))?
+        };
+
+        let aggregator = data_property.aggregator.clone()
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        // sensitivity must be computable
+        aggregator.component.compute_sensitivity(
+            privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(1))?.array()?.float()?;
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or("privacy_usage: must be defined")??;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        data_property.releasable = true;
+        data_property.aggregator = None;
+        // released values are snapped to a grid within [-B, B], where B = max(|lower|, |upper|)
+        let bound = lower.iter().zip(upper.iter())
+            .map(|(lower, upper)| Some(lower.abs().max(upper.abs())))
+            .collect::<Vec<Option<f64>>>();
+        data_property.data_type = DataType::Float;
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Float(bound.iter().map(|v| v.map(|v| -v)).collect()),
+            upper: Vector1DNull::Float(bound),
+        }));
+
+        Ok(Warnable(data_property.into(), warnings))
+    }
+}
+
+
+impl Expandable for proto::SnappingMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let lower_id = if public_arguments.contains_key::<IndexKey>(&"lower".into()) {
+            None
+        } else {
+            maximum_id += 1;
+            Some(maximum_id)
+        };
+
+        let upper_id = if public_arguments.contains_key::<IndexKey>(&"upper".into()) {
+            None
+        } else {
+            maximum_id += 1;
+            Some(maximum_id)
+        };
+
+        let mut expansion = expand_mechanism(
+            &SensitivitySpace::KNorm(1),
+            privacy_definition,
+            self.privacy_usage.as_ref(),
+            component,
+            properties,
+            component_id,
+            maximum_id
+        )?;
+
+        if lower_id.is_some() || upper_id.is_some() {
+            let mut component = expansion.computation_graph.get(&component_id).unwrap().clone();
+
+            let data_property = properties.get::<IndexKey>(&"data".into())
+                .ok_or("data: missing")?.array()?.clone();
+
+            if let Some(lower_id) = lower_id {
+                let (patch_node, release) = get_literal(arr1(&data_property.lower_float()?).into_dyn().into(), component.submission)?;
+                expansion.computation_graph.insert(lower_id, patch_node);
+                expansion.properties.insert(lower_id, infer_property(&release.value, None)?);
+                expansion.releases.insert(lower_id, release);
+                component.insert_argument(&"lower".into(), lower_id);
+            }
+
+            if let Some(upper_id) = upper_id {
+                let (patch_node, release) = get_literal(arr1(&data_property.upper_float()?).into_dyn().into(), component.submission)?;
+                expansion.computation_graph.insert(upper_id, patch_node);
+                expansion.properties.insert(upper_id, infer_property(&release.value, None)?);
+                expansion.releases.insert(upper_id, release);
+                component.insert_argument(&"upper".into(), upper_id);
+            }
+            expansion.computation_graph.insert(component_id, component);
+        }
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::SnappingMechanism {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .zip(data_property.c_stability.iter())
+            .map(|(usage, c_stab)|
+                usage.effective_to_actual(1., *c_stab, privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+
+    fn get_rdp_curves(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<Vec<f64>>>> {
+        // the runtime inflates the noise scale to cover rounding, so each release is epsilon-DP
+        self.get_privacy_usage(privacy_definition, release_usage, properties)?
+            .map(|usages| usages.iter()
+                .map(|usage| Ok(laplace_rdp_curve(get_epsilon(usage)?)))
+                .collect::<Result<Vec<Vec<f64>>>>())
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test_snapping_mechanism {
+    use crate::base::test_data;
+    use crate::components::clamp::test_clamp;
+    use crate::proto;
+    use crate::bindings::Analysis;
+
+    fn analysis_resized(lower: f64, upper: f64, protect_floating_point: bool) -> (Analysis, u32) {
+        let (mut analysis, clamped) = test_clamp::utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), Some(lower.into()), Some(upper.into()));
+        analysis.privacy_definition.protect_floating_point = protect_floating_point;
+
+        let lower = analysis.literal().value(lower.into()).value_public(true).build();
+        let upper = analysis.literal().value(upper.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(10.into()).value_public(true).build();
+        let resized = analysis.resize(clamped)
+            .number_rows(number_rows).lower(lower).upper(upper)
+            .build();
+        (analysis, resized)
+    }
+
+    fn analysis_dp_mean(mechanism: &str, implementation: &str, protect_floating_point: bool) -> crate::errors::Result<()> {
+        let (mut analysis, resized) = analysis_resized(0., 10., protect_floating_point);
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1.,
+                delta: 0.,
+            }))
+        }];
+        let mean = analysis.dp_mean(resized, privacy_usage)
+            .mechanism(mechanism.to_string())
+            .implementation(implementation.to_string())
+            .build();
+        analysis.properties(mean).map(|_| ())
+    }
+
+    #[test]
+    fn test_floating_point_protection() {
+        assert!(analysis_dp_mean("Laplace", "resize", true).is_err());
+        analysis_dp_mean("Laplace", "resize", false).unwrap();
+        analysis_dp_mean("Snapping", "resize", true).unwrap();
+        analysis_dp_mean("Snapping", "plug-in", true).unwrap();
+    }
+
+    #[test]
+    fn test_release_bounds() {
+        let (mut analysis, resized) = analysis_resized(-2., 10., true);
+        let privacy_usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1.,
+                delta: 0.,
+            }))
+        }];
+        let mean = analysis.dp_mean(resized, privacy_usage)
+            .mechanism("Snapping".to_string())
+            .build();
+
+        // the release is clamped to [-B, B], where B is the largest magnitude of the bounds of the mean
+        let mean_property = analysis.properties(mean).unwrap().array().unwrap().clone();
+        assert_eq!(mean_property.lower_float().unwrap(), vec![-10.]);
+        assert_eq!(mean_property.upper_float().unwrap(), vec![10.]);
+    }
+
+    #[test]
+    fn test_aggregate_bounds() {
+        let (mut analysis, resized) = analysis_resized(-2., 10., true);
+        let variance = analysis.variance(resized).finite_sample_correction(true).build();
+        let moment = analysis.raw_moment(resized, 2).build();
+
+        let variance_property = analysis.properties(variance).unwrap().array().unwrap().clone();
+        assert_eq!(variance_property.lower_float().unwrap(), vec![0.]);
+        assert!((variance_property.upper_float().unwrap()[0] - 40.).abs() < 1e-10);
+
+        let moment_property = analysis.properties(moment).unwrap().array().unwrap().clone();
+        assert_eq!(moment_property.lower_float().unwrap(), vec![0.]);
+        assert_eq!(moment_property.upper_float().unwrap(), vec![100.]);
+    }
+}
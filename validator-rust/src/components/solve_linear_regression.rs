@@ -0,0 +1,67 @@
+use crate::errors::*;
+
+use crate::{proto, base, Warnable};
+
+use crate::components::Component;
+use crate::base::{IndexKey, Value, ValueProperties, DataType, ArrayProperties};
+use crate::utilities::prepend;
+use indexmap::map::IndexMap;
+
+
+impl Component for proto::SolveLinearRegression {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+
+        // number of elements in each argument
+        let get_num_elements = |name: &str| -> Result<i64> {
+            let property = properties.get::<IndexKey>(&name.into())
+                .ok_or_else(|| Error::from(format!("{}: missing", name)))?.array()
+                .map_err(prepend(&format!("{}:", name)))?;
+
+            if !property.releasable {
+                return Err(format!("{}: must be public", name).into())
+            }
+            if property.data_type != DataType::Float {
+                return Err(format!("{}: atomic type must be float", name).into())
+            }
+            Ok(property.num_records()? * property.num_columns()?)
+        };
+
+        let num_features = get_num_elements("data_mean")?;
+
+        if get_num_elements("covariance")? != num_features * num_features {
+            return Err("covariance: must be a square matrix with one row per feature".into())
+        }
+        if get_num_elements("cross_covariance")? != num_features {
+            return Err("cross_covariance: must contain one element per feature".into())
+        }
+        if get_num_elements("target_mean")? != 1 {
+            return Err("target_mean: must contain exactly one element".into())
+        }
+        if self.ridge < 0. {
+            return Err("ridge: must be non-negative".into())
+        }
+
+        Ok(Warnable::new(ArrayProperties {
+            num_records: Some(num_features + 1),
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            c_stability: vec![1.],
+            aggregator: None,
+            nature: None,
+            data_type: DataType::Float,
+            dataset_id: None,
+            is_not_empty: true,
+            dimensionality: Some(1),
+            group_id: vec![],
+            naturally_ordered: true,
+            randomized_response: None
+        }.into()))
+    }
+}
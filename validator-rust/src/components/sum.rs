@@ -12,7 +12,7 @@ use indexmap::map::IndexMap;
 impl Component for proto::Sum {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         node_id: u32
@@ -38,17 +38,22 @@ impl Component for proto::Sum {
         if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
             return Err("data: atomic type must be numeric".into())
         }
+
+        if privacy_definition.as_ref().map(|v| v.protect_overflow).unwrap_or(false) {
+            data_property.assert_accumulator_bounded(1).map_err(prepend("data:"))?;
+        }
+
         data_property.nature = data_property.num_records.and_then(|n| Some(Nature::Continuous(NatureContinuous {
             lower: match data_property.data_type {
                 DataType::Int => Vector1DNull::Int(data_property
-                    .lower_int().ok()?.iter().map(|l| Some(l * n)).collect()),
+                    .lower_int().ok()?.iter().map(|l| l.checked_mul(n)).collect()),
                 DataType::Float => Vector1DNull::Float(data_property
                     .lower_float().ok()?.iter().map(|l| Some(l * (n as Float))).collect()),
                 _ => unreachable!()
             },
             upper: match data_property.data_type {
                 DataType::Int => Vector1DNull::Int(data_property
-                    .upper_int().ok()?.iter().map(|u| Some(u * n)).collect()),
+                    .upper_int().ok()?.iter().map(|u| u.checked_mul(n)).collect()),
                 DataType::Float => Vector1DNull::Float(data_property
                     .upper_float().ok()?.iter().map(|u| Some(u * (n as Float))).collect()),
                 _ => unreachable!()
@@ -109,4 +114,33 @@ impl Sensitivity for proto::Sum {
             _ => Err("Sum sensitivity is only implemented for KNorm of 1".into())
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub mod test_sum {
+    use crate::base::test_data;
+    use crate::components::clamp::test_clamp;
+    use crate::Integer;
+
+    fn analysis_sum(lower: Integer, upper: Integer, protect_overflow: bool) -> crate::errors::Result<()> {
+        let (mut analysis, clamped) = test_clamp::utilities::analysis_i64_cont(
+            test_data::array1d_i64_10_uniform(), Some(lower.into()), Some(upper.into()));
+        analysis.privacy_definition.protect_overflow = protect_overflow;
+
+        let lower = analysis.literal().value(lower.into()).value_public(true).build();
+        let upper = analysis.literal().value(upper.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(10.into()).value_public(true).build();
+        let resized = analysis.resize(clamped)
+            .number_rows(number_rows).lower(lower).upper(upper)
+            .build();
+        let summed = analysis.sum(resized).build();
+        analysis.properties(summed).map(|_| ())
+    }
+
+    #[test]
+    fn test_overflow() {
+        let large = Integer::MAX / 5;
+        analysis_sum(0, 10, true).unwrap();
+        analysis_sum(-large, large, false).unwrap();
+        assert!(analysis_sum(-large, large, true).is_err());
+    }
+}
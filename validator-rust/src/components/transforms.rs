@@ -17,7 +17,7 @@ use std::ops::{Mul, Div};
 impl Component for proto::Abs {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32
@@ -28,12 +28,16 @@ impl Component for proto::Abs {
         if !data_property.releasable {
             data_property.assert_is_not_aggregated()?;
         }
+        if data_property.data_type != DataType::Int && data_property.data_type != DataType::Float {
+            return Err("data must be numeric".into())
+        }
 
         data_property.nature = propagate_unary_nature(
             &data_property,
             &UnaryOperators {
                 float: Some(Box::new(|v| Ok(v.abs()))),
-                int: Some(Box::new(|v| Ok(v.abs()))),
+                int: Some(Box::new(|v| v.checked_abs()
+                    .ok_or_else(|| Error::from("absolute value may result in overflow")))),
                 bool: None,
                 str: None,
             },
@@ -45,13 +49,19 @@ impl Component for proto::Abs {
                     _ => Ok((None, None))
                 })),
                 int: Some(Box::new(|bounds| match (bounds.lower, bounds.upper) {
-                    (Some(lower), Some(upper)) => Ok((
-                        Some(if lower > &0 { *lower } else { -*upper }),
-                        Some(if lower + upper > 0 { *upper } else { -*lower }))),
+                    (Some(lower), Some(upper)) => Ok(if lower >= &0 {
+                        (Some(*lower), Some(*upper))
+                    } else if upper <= &0 {
+                        (upper.checked_neg(), lower.checked_neg())
+                    } else {
+                        (Some(0), lower.checked_neg().map(|lower| lower.max(*upper)))
+                    }),
                     _ => Ok((None, None))
                 })),
             }, data_property.num_columns()?)?;
 
+        assert_overflow_protected(privacy_definition, &data_property)?;
+
         Ok(ValueProperties::Array(data_property).into())
     }
 }
@@ -59,7 +69,7 @@ impl Component for proto::Abs {
 impl Component for proto::Add {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32
@@ -90,7 +100,7 @@ impl Component for proto::Add {
             return Err("left and right arguments must share the same data types".into())
         }
 
-        Ok(ValueProperties::Array(ArrayProperties {
+        let output_property = ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
@@ -131,8 +141,12 @@ impl Component for proto::Add {
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
-            naturally_ordered: true
-        }).into())
+            naturally_ordered: true,
+            randomized_response: None
+        };
+
+        assert_overflow_protected(privacy_definition, &output_property)?;
+        Ok(ValueProperties::Array(output_property).into())
     }
 }
 
@@ -217,79 +231,43 @@ impl Component for proto::Divide {
             return Err("left and right arguments must share the same data types".into())
         }
 
-        let float_denominator_may_span_zero = match right_property.clone().nature {
-            Some(nature) => match nature {
-                Nature::Continuous(nature) => nature.lower.float()
-                    .map(|min| nature.upper.float()
-                        .map(|max| min.iter().zip(max.iter())
-                            .any(|(min, max)| min
-                                .map(|min| max
-                                    .map(|max| min < 0. && max > 0.)
-                                    // if max is not known
-                                    .unwrap_or(min > 0.))
-                                // if min is not known
-                                .unwrap_or_else(|| max.map(|max| max < 0.)
-                                    .unwrap_or(true))))
-                        // if max is not float
-                        .unwrap_or(false))
-                    // if min is not float
-                    .unwrap_or(false),
-                Nature::Categorical(nature) => nature.categories.float()
-                    .map(|categories| categories.iter()
-                        .any(|column| column.iter()
-                            .any(|category| category.is_nan() || category == &0.)))
-                    // if categories are not known, a category could be zero or NAN
-                    .unwrap_or(false)
+        // true if any member of the denominator could be zero or NAN
+        let denominator_may_be_zero = match &right_property.nature {
+            Some(Nature::Continuous(nature)) => match (&nature.lower, &nature.upper) {
+                (Vector1DNull::Float(lower), Vector1DNull::Float(upper)) => lower.iter().zip(upper.iter())
+                    .any(|(lower, upper)| lower.map(|lower| lower <= 0.).unwrap_or(true)
+                        && upper.map(|upper| upper >= 0.).unwrap_or(true)),
+                (Vector1DNull::Int(lower), Vector1DNull::Int(upper)) => lower.iter().zip(upper.iter())
+                    .any(|(lower, upper)| lower.map(|lower| lower <= 0).unwrap_or(true)
+                        && upper.map(|upper| upper >= 0).unwrap_or(true)),
+                _ => true
+            },
+            Some(Nature::Categorical(nature)) => match &nature.categories {
+                Jagged::Float(categories) => categories.iter()
+                    .any(|column| column.iter().any(|category| category.is_nan() || category == &0.)),
+                Jagged::Int(categories) => categories.iter()
+                    .any(|column| column.iter().any(|category| category == &0)),
+                _ => true
             },
             // if nature is not known, data could span zero
-            _ => true
+            None => true
         };
 
+        // integer division by zero cannot be represented as a null
+        if left_property.data_type == DataType::Int && denominator_may_be_zero {
+            return Err("potential division by zero".into())
+        }
+
         // minimize and maximize b / e when a <= b <= c and d <= e <= f
         fn optimize<T: PartialOrd + Div<Output=T> + Zero + Copy>(
             a: T, c: T, d: T, f: T
         ) -> Result<(Option<T>, Option<T>)> {
-
-            let zero = T::zero();
-            // maximize {b * d | a <= b <= c && d <= e <= f}
-            let max = match (a, c, d, f) {
-
-                // if either interval is a point
-                (a, c, d, f) if a == c || d == f =>
-                    Some(c / f),
-
-                // if both intervals are not points
-                (a, c, d, f) if a > zero && a < c && ((f == zero && d < zero) && (d < f && f < zero)) =>
-                    Some(a / d),
-                (a, c, d, f) if d > zero && d < f && c > zero && a < c =>
-                    Some(c / d),
-                (a, c, d, f) if (a < c || c > zero) && d < f && f < zero && (a <= zero || c <= zero) =>
-                    Some(a / f),
-                (a, c, d, f) if f > zero && a < c && c <= zero && (d == zero || (d >= zero && d < f)) =>
-                    Some(c / f),
-
-                _ => return Err("potential division by zero".into())
-            };
-
-            // minimize {b * d | a <= b <= c && d <= e <= f}
-            let min = match (a, c, d, f) {
-                // if either interval is a point
-                (a, c, d, f) if a == c || d == f =>
-                    Some(a / d),
-
-                // if both intervals are not points
-                (a, c, d, f) if zero < d && d < f && (a < zero || c <= zero) && (a < c && c > zero) =>
-                    Some(a / d),
-                (a, c, d, f) if a < c && c <= zero && ((f == zero && d < zero) || (d < f && f < zero)) =>
-                    Some(c / d),
-                (a, c, d, f) if (d == zero || (zero < d && d < f)) && zero < a && a < c && f > zero =>
-                    Some(a / f),
-                (a, c, d, f) if f < zero && d < f && c > zero && a < c =>
-                    Some(c / f),
-
-                _ => return Err("potential division by zero".into())
-            };
-            Ok((min, max))
+            if d <= T::zero() && f >= T::zero() {
+                return Err("potential division by zero".into())
+            }
+            // when the denominator does not span zero, division is monotone in each argument,
+            // so the extrema are among the corners. Integer division truncates monotonically
+            Ok(corner_bounds([a / d, a / f, c / d, c / f]))
         }
 
         fn optimize_wrapper<T: PartialOrd + Div<Output=T> + Zero + Copy>(
@@ -326,7 +304,7 @@ impl Component for proto::Divide {
         }
 
         Ok(ValueProperties::Array(ArrayProperties {
-            nullity: left_property.nullity || right_property.nullity || float_denominator_may_span_zero,
+            nullity: left_property.nullity || right_property.nullity || denominator_may_be_zero,
             releasable: left_property.releasable && right_property.releasable,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 float: Some(Box::new(|l: &Float, r: &Float| {
@@ -356,7 +334,8 @@ impl Component for proto::Divide {
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
-            naturally_ordered: true
+            naturally_ordered: true,
+            randomized_response: None
         }).into())
     }
 }
@@ -406,7 +385,8 @@ impl Component for proto::Equal {
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality.max(right_property.dimensionality),
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            naturally_ordered: true
+            naturally_ordered: true,
+            randomized_response: None
         }).into())
     }
 }
@@ -464,7 +444,8 @@ impl Component for proto::GreaterThan {
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            naturally_ordered: true
+            naturally_ordered: true,
+            randomized_response: None
         }).into())
     }
 }
@@ -522,7 +503,8 @@ impl Component for proto::LessThan {
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            naturally_ordered: true
+            naturally_ordered: true,
+            randomized_response: None
         }).into())
     }
 }
@@ -666,7 +648,7 @@ impl Component for proto::Modulo {
 impl Component for proto::Multiply {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32
@@ -694,55 +676,8 @@ impl Component for proto::Multiply {
         fn optimize<T: PartialOrd + Mul<Output=T> + Zero + Copy>(
             a: T, c: T, d: T, f: T
         ) -> Result<(Option<T>, Option<T>)> {
-
-            let zero = T::zero();
-            // maximize {b * d | a <= b <= c && d <= e <= f}
-            let max = match (a, c, d, f) {
-
-                // if either interval is a point
-                (a, c, d, f) if a == c || d == f =>
-                    Some(c * f),
-
-                // if both intervals are not points
-                (a, c, d, f) if (d < zero && ((c > zero && ((f == zero && a < zero) || (a * d > c * f && f > zero && d + f >= zero))) || (a < c && f >= zero && c <= zero)))
-                    || (a < c && c <= zero && ((d < f && f < zero) || (f > zero && d + f < zero)))
-                    || (c > zero && ((d < f && f < zero && a <= zero) || (f > zero && d + f < zero && a * d <= c * f))) =>
-                    Some(a * d),
-                (a, c, d, f) if zero <= d && d < f && c <= zero && a < c =>
-                    Some(c * d),
-                (a, c, d, f) if f < zero && d < f && zero < a && a < c =>
-                    Some(a * f),
-                (a, c, d, f) if c > zero && f > zero && a < c
-                    && ((a * d >= c * f && d + f >= zero && d < zero) || (d < f && d >= zero) || (c * f < a * d && d + f < zero)) =>
-                    Some(c * f),
-
-                // Prior cases should cover all
-                _ => None
-            };
-
-            // minimize {b * d | a <= b <= c && d <= e <= f}
-            let min = match (a, c, d, f) {
-                // if either interval is a point
-                (a, c, d, f) if a == c || d == f =>
-                    Some(a * d),
-
-                // if both intervals are not points
-                (a, c, d, f) if d > zero && d < f && a > zero && a < c =>
-                    Some(a * d),
-                (a, c, d, f) if c > zero && a < c && ((f > zero && a * f > c * d && d < zero)
-                    || (d < f && f <= zero)) =>
-                    Some(c * d),
-                (a, c, d, f) if f > zero && ((c > zero && ((a < zero && (d == zero || (d >= zero && d < f)
-                    || (d <= zero && a * f <= c * d))) || (d < zero && a * f <= c * d)))
-                    || (a < c && c <= zero && (d < f || d <= zero))) =>
-                    Some(a * f),
-                (a, c, d, f) if f <= zero && d < f && c <= zero && a < c =>
-                    Some(c * f),
-
-                // Prior cases should cover all
-                _ => None
-            };
-            Ok((min, max))
+            // multiplication is monotone in each argument when the other is fixed, so the extrema are among the corners
+            Ok(corner_bounds([a * d, a * f, c * d, c * f]))
         }
 
         fn optimize_wrapper<T: PartialOrd + Mul<Output=T> + Zero + Copy>(
@@ -767,7 +702,21 @@ impl Component for proto::Multiply {
             optimize(a, c, d, f)
         }
 
-        Ok(ValueProperties::Array(ArrayProperties {
+        /// integer bounds are only derived if no product of the interval endpoints can overflow
+        fn optimize_wrapper_int(
+            bounds: BinaryBounds<Integer>
+        ) -> Result<(Option<Integer>, Option<Integer>)> {
+            let (a, c, d, f) = match (bounds.left_lower, bounds.left_upper, bounds.right_lower, bounds.right_upper) {
+                (Some(a), Some(c), Some(d), Some(f)) => (*a, *c, *d, *f),
+                _ => return Ok((None, None))
+            };
+            let overflows = [a.checked_mul(d), a.checked_mul(f), c.checked_mul(d), c.checked_mul(f)]
+                .iter().any(Option::is_none);
+            if overflows { return Ok((None, None)) }
+            optimize(a, c, d, f)
+        }
+
+        let output_property = ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
@@ -784,7 +733,7 @@ impl Component for proto::Multiply {
                 bool: None,
             }, &OptimizeBinaryOperators {
                 float: Some(&optimize_wrapper),
-                int: Some(&optimize_wrapper),
+                int: Some(&optimize_wrapper_int),
             }, num_columns)?,
             c_stability: broadcast(&left_property.c_stability, num_columns)?.iter()
                 .zip(broadcast(&right_property.c_stability, num_columns)?)
@@ -798,8 +747,12 @@ impl Component for proto::Multiply {
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
-            naturally_ordered: true
-        }).into())
+            naturally_ordered: true,
+            randomized_response: None
+        };
+
+        assert_overflow_protected(privacy_definition, &output_property)?;
+        Ok(ValueProperties::Array(output_property).into())
     }
 }
 
@@ -837,7 +790,7 @@ impl Component for proto::Negate {
 impl Component for proto::Negative {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32
@@ -849,12 +802,16 @@ impl Component for proto::Negative {
         if !data_property.releasable {
             data_property.assert_is_not_aggregated()?;
         }
+        if data_property.data_type != DataType::Int && data_property.data_type != DataType::Float {
+            return Err("data must be numeric".into())
+        }
 
         data_property.nature = propagate_unary_nature(
             &data_property,
             &UnaryOperators {
                 float: Some(Box::new(|v| Ok(-*v))),
-                int: Some(Box::new(|v| Ok(-*v))),
+                int: Some(Box::new(|v| v.checked_neg()
+                    .ok_or_else(|| Error::from("negation may result in overflow")))),
                 bool: None,
                 str: None,
             },
@@ -862,9 +819,11 @@ impl Component for proto::Negative {
                 float: Some(Box::new(|bounds|
                     Ok((bounds.upper.map(|v| -v), bounds.lower.map(|v| -v))))),
                 int: Some(Box::new(|bounds|
-                    Ok((bounds.upper.map(|v| -v), bounds.lower.map(|v| -v))))),
+                    Ok((bounds.upper.and_then(|v| v.checked_neg()), bounds.lower.and_then(|v| v.checked_neg()))))),
             }, data_property.num_columns()?)?;
 
+        assert_overflow_protected(privacy_definition, &data_property)?;
+
         Ok(ValueProperties::Array(data_property).into())
     }
 }
@@ -927,7 +886,7 @@ impl Component for proto::Or {
 impl Component for proto::Power {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32
@@ -949,6 +908,14 @@ impl Component for proto::Power {
         match (data_property.data_type.clone(), radical_property.data_type.clone()) {
             (DataType::Float, DataType::Float) => {
 
+                // fractional powers of negative numbers are NAN, and negative powers of zero are infinite
+                let lower_at_least = |property: &ArrayProperties, bound: Float, inclusive: bool| property.lower_float()
+                    .map(|lower| lower.iter().all(|v| *v > bound || (inclusive && *v == bound)))
+                    .unwrap_or(false);
+                let nullity = data_property.nullity || radical_property.nullity
+                    || !(lower_at_least(&data_property, 0., false)
+                    || (lower_at_least(&data_property, 0., true) && lower_at_least(&radical_property, 0., true)));
+
                 data_property.nature = propagate_binary_nature(
                     &data_property, &radical_property,
                     &BinaryOperators {
@@ -962,6 +929,7 @@ impl Component for proto::Power {
                         float: Some(&|_bounds| Ok((None, None))),
                         int: None
                     }, data_property.num_columns()?)?;
+                data_property.nullity = nullity;
             },
             (DataType::Int, DataType::Int) => {
                 if !radical_property.lower_int()?.iter().all(|min| min >= &0) {
@@ -977,11 +945,21 @@ impl Component for proto::Power {
                         bool: None,
                         str: None,
                     },
-                    // TODO: derive bounds and throw error if potential overflow
+                    // the magnitude of the output is at most the magnitude of the data to the largest radical
                     &OptimizeBinaryOperators {
                         float: None,
-                        int: Some(&|_bounds| Ok((None, None))),
+                        int: Some(&|bounds| Ok(match (bounds.left_lower, bounds.left_upper, bounds.right_upper) {
+                            (Some(lower), Some(upper), Some(radical)) => match lower.checked_abs()
+                                .map(|lower| lower.max(upper.abs()))
+                                .and_then(|magnitude| magnitude.max(1).checked_pow(*radical as u32)) {
+                                Some(magnitude) => (Some(if *lower >= 0 { 0 } else { -magnitude }), Some(magnitude)),
+                                None => (None, None)
+                            },
+                            _ => (None, None)
+                        })),
                     }, data_property.num_columns()?)?;
+
+                assert_overflow_protected(privacy_definition, &data_property)?;
             },
             _ => return Err("arguments for power must be numeric and homogeneously typed".into())
         }
@@ -1022,6 +1000,9 @@ impl Component for proto::RowMax {
             return Err("left and right arguments must share the same data types".into())
         }
 
+        // the float maximum and minimum ignore NAN, so a null member takes the member of the other argument
+        let may_take_either = left_property.nullity || right_property.nullity;
+
         Ok(ValueProperties::Array(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
@@ -1033,10 +1014,10 @@ impl Component for proto::RowMax {
                 str: Some(Box::new(|l: &String, r: &String| Ok(format!("{}{}", l, r)))),
                 bool: None,
             }, &OptimizeBinaryOperators {
-                float: Some(&|bounds| Ok((
+                float: Some(&move |bounds| Ok((
                     // min
                     match (bounds.left_lower, bounds.right_lower) {
-                        (Some(left_lower), Some(right_lower)) => Some(left_lower.max(*right_lower)),
+                        (Some(left_lower), Some(right_lower)) => Some(if may_take_either { left_lower.min(*right_lower) } else { left_lower.max(*right_lower) }),
                         _ => None
                     },
                     // max
@@ -1070,7 +1051,8 @@ impl Component for proto::RowMax {
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
-            naturally_ordered: true
+            naturally_ordered: true,
+            randomized_response: None
         }).into())
     }
 }
@@ -1102,6 +1084,9 @@ impl Component for proto::RowMin {
             return Err("left and right arguments must share the same data types".into())
         }
 
+        // the float maximum and minimum ignore NAN, so a null member takes the member of the other argument
+        let may_take_either = left_property.nullity || right_property.nullity;
+
         Ok(ValueProperties::Array(ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
@@ -1113,7 +1098,7 @@ impl Component for proto::RowMin {
                 str: Some(Box::new(|l: &String, r: &String| Ok(format!("{}{}", l, r)))),
                 bool: None,
             }, &OptimizeBinaryOperators {
-                float: Some(&|bounds| Ok((
+                float: Some(&move |bounds| Ok((
                     // min
                     match (bounds.left_lower, bounds.right_lower) {
                         (Some(left_lower), Some(right_lower)) => Some(left_lower.min(*right_lower)),
@@ -1121,7 +1106,7 @@ impl Component for proto::RowMin {
                     },
                     // max
                     match (bounds.left_upper, bounds.right_upper) {
-                        (Some(left_upper), Some(right_upper)) => Some(left_upper.min(*right_upper)),
+                        (Some(left_upper), Some(right_upper)) => Some(if may_take_either { left_upper.max(*right_upper) } else { left_upper.min(*right_upper) }),
                         _ => None
                     }
                 ))),
@@ -1150,7 +1135,8 @@ impl Component for proto::RowMin {
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
-            naturally_ordered: true
+            naturally_ordered: true,
+            randomized_response: None
         }).into())
     }
 }
@@ -1158,7 +1144,7 @@ impl Component for proto::RowMin {
 impl Component for proto::Subtract {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         _node_id: u32
@@ -1182,7 +1168,7 @@ impl Component for proto::Subtract {
             return Err("left and right arguments must share the same data types".into())
         }
 
-        Ok(ValueProperties::Array(ArrayProperties {
+        let output_property = ArrayProperties {
             nullity: left_property.nullity || right_property.nullity,
             releasable: left_property.releasable && right_property.releasable,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
@@ -1194,19 +1180,19 @@ impl Component for proto::Subtract {
                 bool: None,
             }, &OptimizeBinaryOperators {
                 float: Some(&|bounds| Ok((
-                    bounds.left_lower.and_then(|lmin| bounds.right_lower.and_then(|rmin|
-                        Some(lmin - rmin))),
-                    bounds.left_upper.and_then(|lmax| bounds.right_upper.and_then(|rmax|
-                        Some(lmax - rmax))),
+                    bounds.left_lower.and_then(|lmin| bounds.right_upper.and_then(|rmax|
+                        Some(lmin - rmax))),
+                    bounds.left_upper.and_then(|lmax| bounds.right_lower.and_then(|rmin|
+                        Some(lmax - rmin))),
                 ))),
                 int: Some(&|bounds| Ok((
-                    match (bounds.left_lower, bounds.right_lower) {
-                        (Some(lmin), Some(rmin)) => Some(lmin.checked_sub(rmin)
+                    match (bounds.left_lower, bounds.right_upper) {
+                        (Some(lmin), Some(rmax)) => Some(lmin.checked_sub(rmax)
                             .ok_or_else(|| Error::from("subtraction may result in underflow or overflow"))?),
                         _ => None
                     },
-                    match (bounds.left_upper, bounds.right_upper) {
-                        (Some(lmax), Some(rmax)) => Some(lmax.checked_sub(rmax)
+                    match (bounds.left_upper, bounds.right_lower) {
+                        (Some(lmax), Some(rmin)) => Some(lmax.checked_sub(rmin)
                             .ok_or_else(|| Error::from("subtraction may result in underflow or overflow"))?),
                         _ => None
                     })))
@@ -1223,8 +1209,12 @@ impl Component for proto::Subtract {
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
                 .max(right_property.dimensionality),
-            naturally_ordered: true
-        }).into())
+            naturally_ordered: true,
+            randomized_response: None
+        };
+
+        assert_overflow_protected(privacy_definition, &output_property)?;
+        Ok(ValueProperties::Array(output_property).into())
     }
 }
 
@@ -1266,6 +1256,13 @@ pub struct OptimizeBinaryOperators<'a> {
     pub int: BinaryOptimizer<'a, Integer>,
 }
 
+/// The minimum and maximum of a binary operator over two intervals, from its evaluations at the corners.
+fn corner_bounds<T: PartialOrd + Copy>(corners: [T; 4]) -> (Option<T>, Option<T>) {
+    let min = corners.iter().fold(corners[0], |min, v| if *v < min { *v } else { min });
+    let max = corners.iter().fold(corners[0], |max, v| if *v > max { *v } else { max });
+    (Some(min), Some(max))
+}
+
 pub fn propagate_binary_shape(left_property: &ArrayProperties, right_property: &ArrayProperties) -> Result<(i64, Option<i64>)> {
     if !left_property.releasable && !right_property.releasable && left_property.group_id != right_property.group_id {
         return Err("data from separate partitions may not be mixed".into())
@@ -1300,10 +1297,32 @@ pub fn propagate_binary_shape(left_property: &ArrayProperties, right_property: &
         return Err("number of rows must be the same for left and right arguments".into());
     }
 
-    // either left, right or both are broadcastable, so take the largest
-    let output_num_records = vec![l, r].iter().filter_map(|v| *v).max().unwrap();
+    // a broadcastable side takes the number of records of the other side, which may be unknown
+    let output_num_records = if left_is_row_broadcastable { r } else { l };
 
-    Ok((output_num_columns, Some(output_num_records)))
+    Ok((output_num_columns, output_num_records))
+}
+
+/// When protecting against overflow, the bounds on integer outputs must be known.
+///
+/// Bounds are propagated with checked arithmetic, so known bounds prove that the runtime cannot overflow.
+fn assert_overflow_protected(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    property: &ArrayProperties
+) -> Result<()> {
+    if !privacy_definition.as_ref().map(|v| v.protect_overflow).unwrap_or(false)
+        || property.data_type != DataType::Int {
+        return Ok(())
+    }
+
+    match &property.nature {
+        Some(Nature::Categorical(_)) => Ok(()),
+        Some(Nature::Continuous(_)) => property.lower_int()
+            .and_then(|_| property.upper_int())
+            .map(|_| ())
+            .map_err(|_| "integer arithmetic may overflow, because not all bounds are known".into()),
+        None => Err("integer arithmetic may overflow, because the bounds are not known".into())
+    }
 }
 
 pub fn propagate_unary_nature(
@@ -1567,3 +1586,109 @@ fn broadcast<T: Clone>(data: &[T], length: i64) -> Result<Vec<T>> {
 
     Ok((0..length).map(|_| data[0].clone()).collect())
 }
+
+#[cfg(test)]
+mod test_transforms {
+    use crate::proto;
+    use crate::base::{Value, ValueProperties};
+    use crate::components::Component;
+    use crate::components::transforms::propagate_binary_shape;
+    use crate::utilities::inference::infer_property;
+    use indexmap::map::IndexMap;
+    use ndarray::arr2;
+
+    fn float_property(lower: f64, upper: f64) -> ValueProperties {
+        infer_property(&arr2(&[[lower], [upper]]).into_dyn().into(), None).unwrap()
+    }
+
+    fn propagate_bounds(component: impl Component, left: ValueProperties, right: ValueProperties) -> (f64, f64) {
+        let property = component.propagate_property(
+            &None, IndexMap::new(), indexmap!["left".into() => left, "right".into() => right], 0).unwrap().0;
+        let property = property.array().unwrap();
+        (property.lower_float().unwrap()[0], property.upper_float().unwrap()[0])
+    }
+
+    #[test]
+    fn test_subtract_bounds() {
+        // the difference is smallest when the right argument is largest
+        assert_eq!(propagate_bounds(proto::Subtract {}, float_property(0., 10.), float_property(0., 5.)), (-5., 10.));
+    }
+    #[test]
+    fn test_broadcast_unknown_num_records() {
+        let mut data = float_property(0., 10.).array().unwrap().clone();
+        data.releasable = false;
+        data.num_records = None;
+        let scalar = infer_property(&arr2(&[[2.]]).into_dyn().into(), None).unwrap().array().unwrap().clone();
+
+        // broadcasting a public scalar preserves the unknown number of records
+        assert_eq!(propagate_binary_shape(&data, &scalar).unwrap(), (1, None));
+        assert_eq!(propagate_binary_shape(&scalar, &data).unwrap(), (1, None));
+    }
+    #[test]
+    fn test_negative_point_bounds() {
+        // multiplying or dividing by a negative point reverses the order of the bounds
+        assert_eq!(propagate_bounds(proto::Multiply {}, float_property(1., 4.), float_property(-2., -2.)), (-8., -2.));
+        assert_eq!(propagate_bounds(proto::Divide {}, float_property(1., 4.), float_property(-2., -2.)), (-2., -0.5));
+    }
+
+    #[test]
+    fn test_corner_bounds() {
+        // the extrema of products of intervals spanning zero are at opposite corners
+        assert_eq!(propagate_bounds(proto::Multiply {}, float_property(-3., 1.), float_property(-2., 5.)), (-15., 6.));
+        assert_eq!(propagate_bounds(proto::Divide {}, float_property(-3., 1.), float_property(2., 4.)), (-1.5, 0.5));
+    }
+    #[test]
+    fn test_divide_by_zero() {
+        let unbounded = |value: Value| {
+            let mut property = infer_property(&value, None).unwrap().array().unwrap().clone();
+            property.nature = None;
+            ValueProperties::from(property)
+        };
+        let divide = |left: ValueProperties, right: ValueProperties| proto::Divide {}.propagate_property(
+            &None, IndexMap::new(), indexmap!["left".into() => left, "right".into() => right], 0);
+
+        // a float denominator bounded at zero may produce nulls
+        let property = divide(unbounded(arr2(&[[1.], [4.]]).into_dyn().into()), float_property(0., 5.)).unwrap().0;
+        assert!(property.array().unwrap().nullity);
+
+        // integer division by zero cannot be represented as a null
+        let denominator = infer_property(&arr2(&[[0], [5]]).into_dyn().into(), None).unwrap();
+        assert!(divide(unbounded(arr2(&[[1], [4]]).into_dyn().into()), denominator).is_err());
+    }
+    #[test]
+    fn test_power_nullity() {
+        let power = |data: ValueProperties, radical: ValueProperties| proto::Power {}.propagate_property(
+            &None, IndexMap::new(), indexmap!["data".into() => data, "radical".into() => radical], 0)
+            .unwrap().0.array().unwrap().nullity;
+
+        // fractional powers of negative numbers are NAN
+        assert!(power(float_property(-1., 2.), float_property(0.5, 0.5)));
+        // negative powers of zero are infinite
+        assert!(power(float_property(0., 2.), float_property(-1., -1.)));
+        assert!(!power(float_property(0., 2.), float_property(0.5, 0.5)));
+        assert!(!power(float_property(1., 2.), float_property(-1., -1.)));
+    }
+    #[test]
+    fn test_row_extrema_nullity() {
+        let nullable = |lower: f64, upper: f64| {
+            let mut property = float_property(lower, upper).array().unwrap().clone();
+            property.nullity = true;
+            ValueProperties::from(property)
+        };
+
+        // a null member takes the member of the other argument
+        assert_eq!(propagate_bounds(proto::RowMax {}, nullable(5., 10.), float_property(0., 1.)), (0., 10.));
+        assert_eq!(propagate_bounds(proto::RowMin {}, nullable(0., 1.), float_property(5., 10.)), (0., 10.));
+    }
+    #[test]
+    fn test_unary_numeric() {
+        let mut data = infer_property(&arr2(&[[true], [false]]).into_dyn().into(), None).unwrap().array().unwrap().clone();
+        data.nature = None;
+        let data = ValueProperties::from(data);
+        let propagate = |component: &dyn Component| component.propagate_property(
+            &None, IndexMap::new(), indexmap!["data".into() => data.clone()], 0);
+
+        assert!(propagate(&proto::Abs {}).is_err());
+        assert!(propagate(&proto::Negative {}).is_err());
+    }
+}
@@ -71,7 +71,8 @@ impl Component for proto::Union {
                 group_id: get_group_id_path(array_props.iter()
                     .map(|prop| prop.group_id.clone())
                     .collect())?,
-                naturally_ordered: false
+                naturally_ordered: false,
+                randomized_response: None
             })
         } else {
             ValueProperties::Partitions(PartitionsProperties { children: properties })
@@ -3,7 +3,7 @@ use crate::errors::*;
 use crate::{proto, base, Warnable, Float};
 
 use crate::components::{Component, Sensitivity};
-use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, IndexKey};
+use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, IndexKey, Nature, NatureContinuous, Vector1DNull};
 use crate::utilities::prepend;
 use ndarray::prelude::*;
 use indexmap::map::IndexMap;
@@ -40,8 +40,19 @@ impl Component for proto::Variance {
             return Err("data: atomic type must be float".into())
         }
 
+        // the variance of data within [lower, upper] is at most (upper - lower)^2 / 4
+        let correction = if self.finite_sample_correction {
+            data_property.num_records.filter(|n| *n > 1).map(|n| n as Float / (n - 1) as Float)
+        } else { Some(1.) };
+        data_property.nature = match (data_property.lower_float(), data_property.upper_float(), correction) {
+            (Ok(lower), Ok(upper), Some(correction)) => Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Float(lower.iter().map(|_| Some(0.)).collect()),
+                upper: Vector1DNull::Float(lower.iter().zip(upper.iter())
+                    .map(|(l, u)| Some((u - l).powi(2) / 4. * correction)).collect())
+            })),
+            _ => None
+        };
         data_property.num_records = Some(1);
-        data_property.nature = None;
         data_property.dataset_id = Some(node_id as i64);
 
         Ok(ValueProperties::Array(data_property).into())
@@ -103,25 +103,26 @@ pub fn validate_analysis(
 
 /// Compute overall privacy usage of an analysis.
 ///
-/// The privacy usage is sum of the privacy usages for each node.
+/// The privacy usages for each node are composed with the theorem selected in the privacy definition.
 /// The Release's actual privacy usage, if defined, takes priority over the maximum allowable privacy usage defined in the Analysis.
+/// The composition theorem that produced the total is returned alongside the privacy usage.
 pub fn compute_privacy_usage(
     privacy_definition: proto::PrivacyDefinition,
     mut computation_graph: HashMap<u32, proto::Component>,
     mut release: base::Release
-) -> Result<proto::PrivacyUsage> {
+) -> Result<(proto::PrivacyUsage, proto::privacy_definition::Composition)> {
 
     let properties = utilities::propagate_properties(
         &Some(privacy_definition.clone()),
         &mut computation_graph,
         &mut release, None, false)?.0;
 
-    let privacy_usage = compute_graph_privacy_usage(
+    let (privacy_usage, composition) = compute_graph_privacy_usage(
         &computation_graph, &privacy_definition, &properties, &release)?;
 
     utilities::privacy::privacy_usage_check(&privacy_usage, None, false)?;
 
-    Ok(privacy_usage)
+    Ok((privacy_usage, composition))
 }
 
 
@@ -132,8 +133,11 @@ pub fn generate_report(
     mut release: base::Release
 ) -> Result<String> {
 
+    // expansion replaces the submitted components, which are the components that summarize their releases
+    let submitted_graph = computation_graph.clone();
+
     let graph_properties = utilities::propagate_properties(
-        &Some(privacy_definition),
+        &Some(privacy_definition.clone()),
         &mut computation_graph,
         &mut release, None, false)?.0;
 
@@ -168,7 +172,7 @@ pub fn generate_report(
 
     });
 
-    let release_schemas = computation_graph.iter()
+    let release_schemas = submitted_graph.iter()
         .map(|(node_id, component)| {
             let public_arguments = utilities::get_public_arguments(&component, &release)?;
             let input_properties = utilities::get_input_properties(&component, &graph_properties)?;
@@ -179,7 +183,7 @@ pub fn generate_report(
                 None => return Ok(None)
             };
             component.summarize(
-                *node_id,
+                &ReportContext { privacy_definition: &privacy_definition, node_id: *node_id },
                 &component,
                 public_arguments,
                 input_properties,
@@ -333,6 +337,11 @@ pub fn expand_component(
     ).chain_err(|| format!("at node_id {:?}", component_id))?;
 
     if result.traversal.is_empty() {
+        // propagate from the expanded component, as propagate_properties does,
+        //     because mechanisms overwrite their privacy usage when expanded
+        let component = result.computation_graph.get(&component_id)
+            .cloned().unwrap_or(component);
+
         let Warnable(propagated_property, propagation_warnings) = component
             .propagate_property(&privacy_definition, public_values, properties, component_id)
             .chain_err(|| format!("at node_id {:?}", component_id))?;
@@ -386,3 +395,32 @@ pub fn get_properties(
     properties.retain(|node_id, _| keep_ids.contains(node_id));
     Ok((properties, warnings))
 }
+
+#[cfg(test)]
+mod test_report {
+    use crate::bindings::Analysis;
+    use crate::base::ReleaseNode;
+    use crate::proto;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_report_submitted_component() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal().value(arr2(&[[1.], [2.], [3.]]).into_dyn().into()).value_public(true).build();
+        let lower = analysis.literal().value(0.into()).value_public(true).build();
+        let count = analysis.dp_count(data, lower, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).mechanism("Laplace".to_string()).build();
+
+        let mut release = analysis.release.clone();
+        release.insert(count, ReleaseNode::new(3.into()));
+
+        // the DP component is summarized, even though it is replaced by its expansion
+        let report = crate::generate_report(analysis.privacy_definition, analysis.components, release).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(report[0]["statistic"], "DPCount");
+        assert_eq!(report[0]["nodeID"], count);
+    }
+}
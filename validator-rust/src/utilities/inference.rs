@@ -249,7 +249,8 @@ pub fn infer_property(
                 group_id: prior_prop_arr
                     .map(|v| v.group_id.clone())
                     .unwrap_or_else(Vec::new),
-                naturally_ordered: true
+                naturally_ordered: true,
+                randomized_response: prior_prop_arr.and_then(|p| p.randomized_response.clone())
             }.into()
         },
         Value::Dataframe(dataframe) => match prior_property {
@@ -94,6 +94,8 @@ pub fn arraynd_to_json<T: Serialize + Clone>(array: &ArrayD<T>) -> Result<serde_
 pub fn privacy_usage_to_json(privacy_usage: &proto::PrivacyUsage) -> serde_json::Value {
     match privacy_usage.distance.clone().unwrap() {
         proto::privacy_usage::Distance::Approximate(distance) =>
-            serde_json::json!({"name": "approximate", "epsilon": distance.epsilon, "delta": distance.delta})
+            serde_json::json!({"name": "approximate", "epsilon": distance.epsilon, "delta": distance.delta}),
+        proto::privacy_usage::Distance::Concentrated(distance) =>
+            serde_json::json!({"name": "concentrated", "rho": distance.rho})
     }
 }
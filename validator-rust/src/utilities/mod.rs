@@ -191,12 +191,14 @@ pub fn propagate_properties(
                 properties.get(&node_id))?, vec![]))
         } else {
             // if node has not been evaluated, propagate properties over it
-            computation_graph.get(&node_id).unwrap()
-                .propagate_property(
-                    privacy_definition,
-                    get_public_arguments(component, &release)?,
-                    input_properties,
-                    node_id)
+            check_elapsed_time_protection(privacy_definition, component, &input_properties)
+                .and_then(|_| check_memory_utilization_protection(privacy_definition, component, &input_properties))
+                .and_then(|_| computation_graph.get(&node_id).unwrap()
+                    .propagate_property(
+                        privacy_definition,
+                        get_public_arguments(component, &release)?,
+                        input_properties,
+                        node_id))
                 .chain_err(|| format!("at node_id {:?}", node_id))
         };
 
@@ -221,6 +223,102 @@ pub fn propagate_properties(
     Ok((properties, warnings))
 }
 
+/// Reject components whose elapsed execution time cannot be made independent of the private data.
+///
+/// When `protect_elapsed_time` is enabled, the runtime pads the evaluation of every node
+/// to a bound derived from the number of records of its private arguments.
+/// This requires the number of records of every private argument to be public,
+/// and rules out components whose output size or amount of work depends on the data itself.
+pub fn check_elapsed_time_protection(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    component: &proto::Component,
+    properties: &NodeProperties,
+) -> Result<()> {
+    let privacy_definition = match privacy_definition {
+        Some(privacy_definition) if privacy_definition.protect_elapsed_time => privacy_definition,
+        _ => return Ok(())
+    };
+
+    if privacy_definition.elapsed_time_nanos_per_record == 0 {
+        bail!("elapsed_time_nanos_per_record must be positive when protect_elapsed_time is enabled")
+    }
+
+    match component.variant.as_ref().ok_or("component variant must be defined")? {
+        proto::component::Variant::Materialize(_) =>
+            bail!("the time to parse a data source depends on its contents. When protecting elapsed time, supply the data directly"),
+        proto::component::Variant::Filter(_) =>
+            bail!("the number of records retained by a filter depends on the data, so the elapsed time cannot be protected"),
+        proto::component::Variant::Partition(_) =>
+            bail!("the size of each partition depends on the data, so the elapsed time cannot be protected"),
+        proto::component::Variant::Count(count) if count.distinct =>
+            bail!("the time to count distinct values depends on the data, so the elapsed time cannot be protected"),
+        proto::component::Variant::Quantile(_) =>
+            bail!("the time to sort data depends on the data, so the elapsed time cannot be protected"),
+        _ => ()
+    };
+
+    check_public_num_records(properties)
+}
+
+/// Reject components whose memory utilization cannot be made independent of the private data.
+///
+/// When `protect_memory_utilization` is enabled, the runtime allocates buffers sized by the number of records
+/// of the private arguments, rather than by the data. Every component other than `Resize`
+/// must therefore receive private arguments with a public number of records,
+/// and data sources are only loaded when a public bound on their number of records is set.
+pub fn check_memory_utilization_protection(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    component: &proto::Component,
+    properties: &NodeProperties,
+) -> Result<()> {
+    if !privacy_definition.as_ref().map(|v| v.protect_memory_utilization).unwrap_or(false) {
+        return Ok(())
+    }
+
+    match component.variant.as_ref().ok_or("component variant must be defined")? {
+        proto::component::Variant::Materialize(materialize) => if materialize.max_records == 0 {
+            bail!("the memory used to load a data source depends on its size. When protecting memory utilization, set max_records or supply the data directly")
+        } else {
+            // the arguments of a data source are public
+            return Ok(())
+        },
+        // resizing makes the number of records public
+        proto::component::Variant::Resize(_) => return Ok(()),
+        _ => ()
+    };
+
+    check_public_num_records(properties)
+}
+
+/// Reject private arguments whose number of records is not public.
+fn check_public_num_records(properties: &NodeProperties) -> Result<()> {
+    properties.iter()
+        .filter(|(_, property)| !property.is_public())
+        .try_for_each(|(name, property)| get_public_num_records(property)
+            .map(|_| ())
+            .map_err(prepend(&format!("{}:", name.to_string()))))
+}
+
+/// Retrieve the public number of records of a value, summed over partitions and jagged columns.
+///
+/// Side-channel protections derive their padding and buffer sizes from this count.
+/// Fails if the number of records is not public.
+pub fn get_public_num_records(property: &ValueProperties) -> Result<i64> {
+    let num_records = match property {
+        ValueProperties::Array(array) => array.num_records,
+        ValueProperties::Jagged(jagged) => jagged.num_records.as_ref()
+            .map(|num_records| num_records.iter().sum()),
+        ValueProperties::Dataframe(dataframe) => dataframe.children.values()
+            .map(get_public_num_records)
+            .collect::<Result<Vec<i64>>>()?.into_iter().max(),
+        ValueProperties::Partitions(partitions) => Some(partitions.children.values()
+            .map(get_public_num_records)
+            .collect::<Result<Vec<i64>>>()?.into_iter().sum()),
+        ValueProperties::Function(_) => Some(0)
+    };
+    num_records.ok_or_else(|| "the number of records must be public to protect side-channels. Consider resizing the data".into())
+}
+
 /// Given a computation graph, return an ordering of nodes that ensures all dependencies of any node have been visited
 ///
 /// The traversal also fails upon detecting cyclic dependencies,
@@ -556,6 +654,8 @@ pub fn expand_mechanism(
         proto::component::Variant::GaussianMechanism(variant) => variant.privacy_usage = effective_usages,
         proto::component::Variant::ExponentialMechanism(variant) => variant.privacy_usage = effective_usages,
         proto::component::Variant::SimpleGeometricMechanism(variant) => variant.privacy_usage = effective_usages,
+        proto::component::Variant::SnappingMechanism(variant) => variant.privacy_usage = effective_usages,
+        proto::component::Variant::DiscreteGaussianMechanism(variant) => variant.privacy_usage = effective_usages,
         _ => ()
     };
     expansion.computation_graph.insert(component_id, noise_component);
@@ -595,6 +695,44 @@ pub fn deduplicate<T: Eq + Hash + Clone>(values: Vec<T>) -> Vec<T> {
 mod test_utilities {
     use crate::utilities;
 
+    #[test]
+    fn test_memory_utilization_protection() {
+        use crate::{proto, bindings::Analysis, base::NodeProperties};
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(ndarray::arr1(&[1, 2, 3]).into_dyn().into())
+            .value_public(true).build();
+        let mut data_property = analysis.properties(data).unwrap().array().unwrap().clone();
+        data_property.releasable = false;
+
+        let privacy_definition = Some(proto::PrivacyDefinition {
+            protect_memory_utilization: true,
+            ..Default::default()
+        });
+        let get_component = |variant| proto::Component {
+            variant: Some(variant), ..Default::default()
+        };
+        let count = get_component(proto::component::Variant::Count(proto::Count { distinct: true }));
+        let resize = get_component(proto::component::Variant::Resize(proto::Resize {}));
+        let materialize = get_component(proto::component::Variant::Materialize(proto::Materialize::default()));
+
+        let mut properties = NodeProperties::new();
+        properties.insert("data".into(), data_property.clone().into());
+        utilities::check_memory_utilization_protection(&privacy_definition, &count, &properties).unwrap();
+        assert!(utilities::check_memory_utilization_protection(&privacy_definition, &materialize, &NodeProperties::new()).is_err());
+        let bounded_materialize = get_component(proto::component::Variant::Materialize(proto::Materialize {
+            max_records: 10, ..Default::default()
+        }));
+        utilities::check_memory_utilization_protection(&privacy_definition, &bounded_materialize, &NodeProperties::new()).unwrap();
+
+        // the number of records is private, for example after filtering
+        data_property.num_records = None;
+        properties.insert("data".into(), data_property.into());
+        assert!(utilities::check_memory_utilization_protection(&privacy_definition, &count, &properties).is_err());
+        utilities::check_memory_utilization_protection(&privacy_definition, &resize, &properties).unwrap();
+    }
+
     #[test]
     fn test_deduplicate() {
         let values = vec![2, 0, 1, 0];
@@ -2,27 +2,144 @@ use std::collections::{HashMap, HashSet};
 use crate::errors::*;
 use itertools::Itertools;
 use std::cmp::Ordering;
+use statrs::function::erf;
 use crate::{proto, Float};
-use crate::base::{ValueProperties, Release, GroupId, IndexKey};
+use crate::base::{ValueProperties, Release, GroupId, IndexKey, NodeProperties};
 use crate::components::Mechanism;
+use crate::proto::privacy_definition::Composition;
 use crate::utilities::{get_input_properties, get_common_value, get_dependents};
 
 type BatchIdentifier = (u32, u32);
 type PartitionIds = Vec<u32>;
 
+/// Compose a batch of privacy usages with the composition theorem selected in the privacy definition.
+///
+/// The advanced and optimal homogeneous theorems spend an additional `composition_slack_delta`,
+///     so basic composition is used instead whenever it gives a smaller epsilon.
+/// Returns the composed privacy usage, and the theorem that produced it.
 fn compute_batch_privacy_usage(
-    privacy_usages: Vec<&proto::PrivacyUsage>
-) -> Result<proto::PrivacyUsage> {
-    // TODO: insert advanced composition here
-    //    This is just linear composition
-    privacy_usages.into_iter().cloned().map(Ok)
-        .fold1(|l, r| l? + r?)
-        .unwrap_or_else(|| Ok(proto::PrivacyUsage {
-            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-                epsilon: 0.,
-                delta: 0.,
-            }))
+    privacy_usages: Vec<&proto::PrivacyUsage>,
+    privacy_definition: &proto::PrivacyDefinition,
+) -> Result<(proto::PrivacyUsage, Composition)> {
+    let composition = Composition::from_i32(privacy_definition.composition)
+        .ok_or_else(|| Error::from("composition must be one of \"Basic\", \"Advanced\", \"OptimalHomogeneous\" or \"Renyi\""))?;
+
+    // concentrated usages compose exactly by summing rho
+    if privacy_usages.iter().any(|usage| matches!(usage.distance, Some(proto::privacy_usage::Distance::Concentrated(_)))) {
+        let usage = privacy_usages.into_iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?)
+            .ok_or("privacy usages must be defined")??;
+        return Ok((usage, Composition::Basic))
+    }
+
+    let usages = privacy_usages.into_iter()
+        .map(|usage| Ok((get_epsilon(usage)?, get_delta(usage)?)))
+        .collect::<Result<Vec<(f64, f64)>>>()?.into_iter()
+        // releases that do not consume privacy do not count towards the number of compositions
+        .filter(|(epsilon, delta)| *epsilon != 0. || *delta != 0.)
+        .collect::<Vec<(f64, f64)>>();
+
+    let basic = compose_basic(&usages);
+    if composition == Composition::Basic || usages.len() < 2 {
+        return Ok((approximate_usage(basic), Composition::Basic))
+    }
+
+    let slack_delta = privacy_definition.composition_slack_delta;
+    if !(slack_delta > 0. && slack_delta < 1.) {
+        bail!("composition_slack_delta: must be within (0, 1) when using {:?} composition", composition)
+    }
+
+    let (composed, composition) = match composition {
+        Composition::Basic => (basic, Composition::Basic),
+        Composition::Renyi => bail!("privacy usages may not be composed under Rényi composition"),
+        Composition::Advanced => (compose_advanced(&usages, slack_delta), Composition::Advanced),
+        // heterogeneous usages are not covered by the optimal homogeneous theorem
+        Composition::OptimalHomogeneous => match compose_optimal_homogeneous(&usages, slack_delta) {
+            Some(composed) => (composed, Composition::OptimalHomogeneous),
+            None => (compose_advanced(&usages, slack_delta), Composition::Advanced)
+        }
+    };
+
+    Ok(if composed.0 < basic.0 {
+        (approximate_usage(composed), composition)
+    } else {
+        (approximate_usage(basic), Composition::Basic)
+    })
+}
+
+fn approximate_usage((epsilon, delta): (f64, f64)) -> proto::PrivacyUsage {
+    proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon, delta
         }))
+    }
+}
+
+/// Linear composition: the epsilons and deltas are summed.
+fn compose_basic(usages: &[(f64, f64)]) -> (f64, f64) {
+    usages.iter().fold((0., 0.), |(eps_l, del_l), (eps_r, del_r)|
+        (eps_l + eps_r, del_l + del_r))
+}
+
+/// Advanced composition theorem, generalized to heterogeneous usages.
+///
+/// The k-fold adaptive composition of (ε_i, δ_i)-DP releases is (ε', Σδ_i + δ')-DP, where
+///     ε' = sqrt(2 ln(1/δ') Σε_i^2) + Σε_i (e^ε_i - 1)
+///
+/// Dwork, Rothblum, Vadhan. "Boosting and Differential Privacy" (2010)
+fn compose_advanced(usages: &[(f64, f64)], slack_delta: f64) -> (f64, f64) {
+    let sum_squared_epsilon = usages.iter().map(|(epsilon, _)| epsilon.powi(2)).sum::<f64>();
+    let epsilon = (2. * (1. / slack_delta).ln() * sum_squared_epsilon).sqrt()
+        + usages.iter().map(|(epsilon, _)| epsilon * epsilon.exp_m1()).sum::<f64>();
+    let delta = usages.iter().map(|(_, delta)| delta).sum::<f64>() + slack_delta;
+    (epsilon, delta)
+}
+
+/// Optimal composition theorem for k releases that are each (ε, δ)-DP.
+///
+/// For each i in 0..=floor(k/2), the composition is ((k - 2i)ε, 1 - (1 - δ)^k (1 - δ_i))-DP, where
+///     δ_i = Σ_{l=0}^{i-1} C(k, l) (e^{(k-l)ε} - e^{(k-2i+l)ε}) / (1 + e^ε)^k
+/// The smallest epsilon whose δ_i does not exceed the slack delta is chosen.
+/// Returns None if the usages are not homogeneous.
+///
+/// Kairouz, Oh, Viswanath. "The Composition Theorem for Differential Privacy" (2015)
+fn compose_optimal_homogeneous(usages: &[(f64, f64)], slack_delta: f64) -> Option<(f64, f64)> {
+    let (epsilon, delta) = *usages.first()?;
+    if !usages.iter().all(|usage| usage == &(epsilon, delta)) {
+        return None
+    }
+    let k = usages.len();
+
+    // all terms are computed in log space, as e^{kε} overflows quickly
+    // ln(1 + e^ε)
+    let ln_normalizer = epsilon + (-epsilon).exp().ln_1p();
+    let ln_term = |ln_binomial: f64, l: usize, i: usize| ln_binomial
+        + (k - l) as f64 * epsilon - k as f64 * ln_normalizer
+        + (-(-2. * (i - l) as f64 * epsilon).exp()).ln_1p();
+
+    // i = 0 is basic composition of the epsilons
+    let mut optimal_i = 0;
+    let mut optimal_delta_i = 0.;
+
+    for i in 1..=k / 2 {
+        let mut ln_binomial = 0.;
+        let delta_i = (0..i).map(|l| {
+            let term = ln_term(ln_binomial, l, i).exp();
+            // ln C(k, l + 1) = ln C(k, l) + ln(k - l) - ln(l + 1)
+            ln_binomial += ((k - l) as f64).ln() - ((l + 1) as f64).ln();
+            term
+        }).sum::<f64>();
+
+        // δ_i grows as the epsilon shrinks
+        if delta_i > slack_delta { break }
+        optimal_i = i;
+        optimal_delta_i = delta_i;
+    }
+
+    Some((
+        (k - 2 * optimal_i) as f64 * epsilon,
+        1. - (1. - delta).powi(k as i32) * (1. - optimal_delta_i)
+    ))
 }
 
 /// Use a computation graph to partition privacy usages into batches.
@@ -30,10 +147,10 @@ fn compute_batch_privacy_usage(
 /// This algorithm takes into account dynamic graph submissions that require multiple batches to compute.
 /// This algorithm traverses up to and stops at partition ids.
 ///     The partition ids are returned as a second argument.
-fn batch_partition<'a>(
+fn batch_partition<'a, T>(
     graph: &HashMap<u32, proto::Component>,
-    privacy_usages: &'a HashMap<u32, Vec<proto::PrivacyUsage>>,
-) -> Result<(HashMap<BatchIdentifier, Vec<&'a proto::PrivacyUsage>>, PartitionIds)> {
+    privacy_usages: &'a HashMap<u32, Vec<T>>,
+) -> Result<(HashMap<BatchIdentifier, Vec<&'a T>>, PartitionIds)> {
 
     // contains the subgraph for each submission id
     let mut submissions = HashMap::<u32, HashMap<u32, proto::Component>>::new();
@@ -49,7 +166,7 @@ fn batch_partition<'a>(
 
     // each batch is identified by the (submission_id, dependency_id),
     //    where the dependency_id is the maximum number of releases prior to a node id in the batch
-    let mut batches = HashMap::<BatchIdentifier, Vec<&proto::PrivacyUsage>>::new();
+    let mut batches = HashMap::<BatchIdentifier, Vec<&T>>::new();
 
     // node ids of partitions - these will require special treatment, and are not yet counted
     let mut partition_ids = Vec::new();
@@ -161,7 +278,7 @@ fn batch_partition<'a>(
                 batches.insert((submission_id, dependency_id), batch_values.iter()
                     .map(|node_id| privacy_usages.get(node_id))
                     .flatten().flatten()
-                    .collect::<Vec<&'a proto::PrivacyUsage>>());
+                    .collect::<Vec<&'a T>>());
             });
             Ok::<_, Error>(())
         })?;
@@ -172,25 +289,165 @@ fn batch_partition<'a>(
 /// Compute the privacy usage of a graph,
 ///     based on the privacy definition
 ///     and actual usages reported by any computed values.
+///
+/// Also returns the composition theorem used to compose the releases that are not in parallel.
+/// Concentrated privacy usages are composed in rho,
+///     and the total is converted to an approximate privacy usage if the privacy definition sets a `concentrated_conversion_delta`.
+/// Under Rényi composition, RDP curves are composed and converted to an approximate privacy usage at the end,
+///     with the `composition_slack_delta` as delta.
 pub fn compute_graph_privacy_usage(
     graph: &HashMap<u32, proto::Component>,
     privacy_definition: &proto::PrivacyDefinition,
     properties: &HashMap<u32, ValueProperties>,
     release: &Release,
-) -> Result<proto::PrivacyUsage> {
+) -> Result<(proto::PrivacyUsage, Composition)> {
+
+    if privacy_definition.composition == Composition::Renyi as i32 {
+        let (curve, composition) = compose_graph_loss(
+            &RenyiAccountant { privacy_definition }, graph, properties, release)?;
+        return Ok((rdp_curve_to_approximate(&curve, privacy_definition.composition_slack_delta)?, composition))
+    }
+
+    let (usage, composition) = compose_graph_loss(
+        &UsageAccountant { privacy_definition }, graph, properties, release)?;
+
+    let conversion_delta = privacy_definition.concentrated_conversion_delta;
+    Ok(match usage.distance {
+        Some(proto::privacy_usage::Distance::Concentrated(_)) if conversion_delta > 0. =>
+            (concentrated_to_approximate(&usage, conversion_delta)?, composition),
+        _ => (usage, composition)
+    })
+}
+
+/// Tracks the privacy loss of releases as they are composed through a computation graph.
+trait Accountant {
+    type Loss;
+
+    /// Privacy loss of each release made by a node, if the node is a mechanism.
+    fn get_node_losses(
+        &self,
+        component: &proto::Component,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<Self::Loss>>>;
+
+    fn zero(&self) -> Self::Loss;
+
+    /// Sequential composition of a batch of losses.
+    fn compose(&self, losses: Vec<&Self::Loss>) -> Result<(Self::Loss, Composition)>;
+
+    fn add(&self, left: Self::Loss, right: Self::Loss) -> Result<Self::Loss>;
+
+    /// Parallel composition of two losses.
+    fn max(&self, left: Self::Loss, right: Self::Loss) -> Result<Self::Loss>;
+}
+
+/// Composes privacy usages with the composition theorem selected in the privacy definition.
+struct UsageAccountant<'a> {
+    privacy_definition: &'a proto::PrivacyDefinition
+}
+
+impl Accountant for UsageAccountant<'_> {
+    type Loss = proto::PrivacyUsage;
+
+    fn get_node_losses(
+        &self,
+        component: &proto::Component,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        component.get_privacy_usage(self.privacy_definition, release_usage, properties)
+    }
+
+    fn zero(&self) -> proto::PrivacyUsage {
+        approximate_usage((0., 0.))
+    }
+
+    fn compose(&self, losses: Vec<&proto::PrivacyUsage>) -> Result<(proto::PrivacyUsage, Composition)> {
+        compute_batch_privacy_usage(losses, self.privacy_definition)
+    }
+
+    fn add(&self, left: proto::PrivacyUsage, right: proto::PrivacyUsage) -> Result<proto::PrivacyUsage> {
+        left + right
+    }
+
+    fn max(&self, left: proto::PrivacyUsage, right: proto::PrivacyUsage) -> Result<proto::PrivacyUsage> {
+        use proto::privacy_usage::{Distance, DistanceApproximate, DistanceConcentrated};
+        Ok(proto::PrivacyUsage {
+            distance: Some(match (left.distance.clone(), right.distance.clone()) {
+                (Some(Distance::Approximate(l)), Some(Distance::Approximate(r))) =>
+                    Distance::Approximate(DistanceApproximate {
+                        epsilon: l.epsilon.max(r.epsilon),
+                        delta: l.delta.max(r.delta),
+                    }),
+                // approximate usages are converted to concentrated usages
+                (Some(_), Some(_)) => Distance::Concentrated(DistanceConcentrated {
+                    rho: get_rho(&(left + zero_concentrated_usage())?)?
+                        .max(get_rho(&(right + zero_concentrated_usage())?)?)
+                }),
+                _ => return Err("distance must be defined".into())
+            })
+        })
+    }
+}
+
+/// Composes Rényi differential privacy curves, evaluated at each of the RDP_ORDERS.
+struct RenyiAccountant<'a> {
+    privacy_definition: &'a proto::PrivacyDefinition
+}
+
+impl Accountant for RenyiAccountant<'_> {
+    type Loss = Vec<f64>;
+
+    fn get_node_losses(
+        &self,
+        component: &proto::Component,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties,
+    ) -> Result<Option<Vec<Vec<f64>>>> {
+        component.get_rdp_curves(self.privacy_definition, release_usage, properties)
+    }
+
+    fn zero(&self) -> Vec<f64> {
+        vec![0.; RDP_ORDERS.len()]
+    }
+
+    // RDP curves compose by summing the divergence at each order
+    fn compose(&self, losses: Vec<&Vec<f64>>) -> Result<(Vec<f64>, Composition)> {
+        Ok((losses.into_iter().fold(self.zero(), |total, curve| total.iter()
+            .zip(curve.iter()).map(|(l, r)| l + r).collect()), Composition::Renyi))
+    }
+
+    fn add(&self, left: Vec<f64>, right: Vec<f64>) -> Result<Vec<f64>> {
+        Ok(self.compose(vec![&left, &right])?.0)
+    }
+
+    fn max(&self, left: Vec<f64>, right: Vec<f64>) -> Result<Vec<f64>> {
+        Ok(left.iter().zip(right.iter()).map(|(l, r)| l.max(*r)).collect())
+    }
+}
+
+/// Compose the privacy loss of a graph with an accountant,
+///     applying parallel composition to the dependents of partitions.
+fn compose_graph_loss<A: Accountant>(
+    accountant: &A,
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &Release,
+) -> Result<(A::Loss, Composition)> {
 
     // compute the privacy usage for every node in the graph
     //    include updated privacy usages for nodes that have already been released and may have actually consumed a different amount
     let release_privacy_usages = graph.iter()
-        .map(|(node_id, component)| Ok((*node_id, component.get_privacy_usage(
-            &privacy_definition,
+        .map(|(node_id, component)| Ok((*node_id, accountant.get_node_losses(
+            component,
             release.get(node_id)
                 .and_then(|v| v.privacy_usages.as_ref()),
             &get_input_properties(component, &properties)?)?))
         )
-        .collect::<Result<Vec<(u32, Option<Vec<proto::PrivacyUsage>>)>>>()?
+        .collect::<Result<Vec<(u32, Option<Vec<A::Loss>>)>>>()?
         .into_iter().filter_map(|(node_id, usages)| Some((node_id, usages?)))
-        .collect::<HashMap<u32, Vec<proto::PrivacyUsage>>>();
+        .collect::<HashMap<u32, Vec<A::Loss>>>();
 
     // for any node id in the submission, list all nodes that use it
     let dependent_edges = get_dependents(graph);
@@ -200,13 +457,6 @@ pub fn compute_graph_privacy_usage(
     //     also return the node ids of partitions, as parallel composition needs to be applied to its dependents
     let (batches, partition_ids) = batch_partition(graph, &release_privacy_usages)?;
 
-    let zero_usage = || proto::PrivacyUsage {
-        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-            epsilon: 0.0,
-            delta: 0.0,
-        }))
-    };
-
     // get all node ids that are indexed by a specific category
     let get_category_indexes = |
         category: IndexKey, partition_id: u32,
@@ -284,43 +534,23 @@ pub fn compute_graph_privacy_usage(
     };
 
     // return the max of the left and right privacy usages
-    let max_usage = |l: Result<proto::PrivacyUsage>, r: Result<proto::PrivacyUsage>| -> Result<proto::PrivacyUsage> {
-        let proto::privacy_usage::DistanceApproximate {
-            epsilon: eps_l, delta: del_l
-        } = match l?.distance {
-            Some(proto::privacy_usage::Distance::Approximate(x)) => Ok(x),
-            _ => Err("expected approximate privacy")
-        }?;
-        let proto::privacy_usage::DistanceApproximate {
-            epsilon: eps_r, delta: del_r
-        } = match r?.distance {
-            Some(proto::privacy_usage::Distance::Approximate(x)) => Ok(x),
-            _ => Err("expected approximate privacy")
-        }?;
-
-        Ok(proto::PrivacyUsage {
-            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-                epsilon: eps_l.max(eps_r),
-                delta: del_l.max(del_r),
-            }))
-        })
-    };
+    let max_usage = |l: Result<A::Loss>, r: Result<A::Loss>| accountant.max(l?, r?);
 
     // compute privacy usage of a subset of the graph,
     //     where the subset is indicated by a collection of node ids
     let compute_all_partitions_usage = |
         partition_ids: Vec<u32>
-    | -> Result<proto::PrivacyUsage> {
+    | -> Result<A::Loss> {
         partition_ids.iter()
-            .map(|partition_id| compute_graph_privacy_usage(
-                &get_downstream_graph(None, *partition_id)?,
-                privacy_definition, properties, release))
+            .map(|partition_id| compose_graph_loss(
+                accountant, &get_downstream_graph(None, *partition_id)?,
+                properties, release).map(|(usage, _)| usage))
             .fold1(max_usage)
-            .unwrap_or_else(|| Ok(zero_usage()))
+            .unwrap_or_else(|| Ok(accountant.zero()))
     };
 
     // compute the overall privacy usage
-    let partitions_usage: proto::PrivacyUsage = partition_ids.into_iter()
+    let partitions_usage: A::Loss = partition_ids.into_iter()
         // for each partition component...
         .map(|partition_node_id| {
             let partition_properties = properties.get(&partition_node_id)
@@ -337,25 +567,22 @@ pub fn compute_graph_privacy_usage(
 
                     let (batches, partition_ids) = batch_partition(
                         &unioned_downstream_graph, &release_privacy_usages)?;
-                    let batch_usages = batches.into_iter()
-                        .map(|(_, batch)| compute_batch_privacy_usage(batch))
-                        .fold1(|l, r| l? + r?)
-                        .unwrap_or_else(|| Ok(zero_usage()))?;
+                    let (batch_usages, _) = accountant.compose(
+                        batches.into_iter().flat_map(|(_, batch)| batch).collect())?;
 
-                    batch_usages + compute_all_partitions_usage(partition_ids)?
+                    accountant.add(batch_usages, compute_all_partitions_usage(partition_ids)?)
                 })
                 .fold1(max_usage)
-                .unwrap_or_else(|| Ok(zero_usage()))
+                .unwrap_or_else(|| Ok(accountant.zero()))
         })
-        .fold1(|l, r| l? + r?)
-        .unwrap_or_else(|| Ok(zero_usage()))?;
+        .fold1(|l, r| accountant.add(l?, r?))
+        .unwrap_or_else(|| Ok(accountant.zero()))?;
 
-    let batch_usages = batches.into_iter()
-        .map(|(_, batch)| compute_batch_privacy_usage(batch))
-        .fold1(|l, r| l? + r?)
-        .unwrap_or_else(|| Ok(zero_usage()))?;
+    // the composition theorems hold under adaptive composition, so all batches are composed together
+    let (batch_usages, composition) = accountant.compose(
+        batches.into_iter().flat_map(|(_, batch)| batch).collect())?;
 
-    batch_usages + partitions_usage
+    Ok((accountant.add(batch_usages, partitions_usage)?, composition))
 }
 
 // pub fn privacy_usage_reducer(
@@ -423,6 +650,15 @@ pub fn privacy_usage_check(
                 }
             }
         }
+        proto::privacy_usage::Distance::Concentrated(usage) => {
+            if usage.rho <= 0.0 {
+                return Err("rho: privacy parameter rho must be greater than 0".into());
+            }
+
+            if usage.rho > 0.5 {
+                warnings.push(format!("Warning: A large privacy parameter of rho = {} is in use", usage.rho.to_string()).into())
+            }
+        }
     };
 
     Ok(warnings)
@@ -432,7 +668,7 @@ pub fn get_epsilon(usage: &proto::PrivacyUsage) -> Result<f64> {
     match usage.distance.clone()
         .ok_or_else(|| Error::from("distance must be defined on a PrivacyUsage"))? {
         proto::privacy_usage::Distance::Approximate(distance) => Ok(distance.epsilon),
-//        _ => Err("epsilon is not defined".into())
+        proto::privacy_usage::Distance::Concentrated(_) => Err("epsilon is not defined on a concentrated privacy usage".into())
     }
 }
 
@@ -440,10 +676,239 @@ pub fn get_delta(usage: &proto::PrivacyUsage) -> Result<f64> {
     match usage.distance.clone()
         .ok_or_else(|| Error::from("distance must be defined on a PrivacyUsage"))? {
         proto::privacy_usage::Distance::Approximate(distance) => Ok(distance.delta),
-        // _ => Err("delta is not defined".into())
+        proto::privacy_usage::Distance::Concentrated(_) => Err("delta is not defined on a concentrated privacy usage".into())
+    }
+}
+
+pub fn get_rho(usage: &proto::PrivacyUsage) -> Result<f64> {
+    match usage.distance.clone()
+        .ok_or_else(|| Error::from("distance must be defined on a PrivacyUsage"))? {
+        proto::privacy_usage::Distance::Concentrated(distance) => Ok(distance.rho),
+        proto::privacy_usage::Distance::Approximate(_) => Err("rho is not defined on an approximate privacy usage".into())
     }
 }
 
+pub fn zero_concentrated_usage() -> proto::PrivacyUsage {
+    proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+            rho: 0.
+        }))
+    }
+}
+
+/// Convert a rho-zCDP privacy usage to an (epsilon, delta) privacy usage,
+///     where epsilon = rho + 2 sqrt(rho ln(1/delta)).
+///
+/// Bun, Steinke. "Concentrated Differential Privacy: Simplifications, Extensions, and Lower Bounds" (2016), Proposition 1.3
+pub fn concentrated_to_approximate(usage: &proto::PrivacyUsage, delta: f64) -> Result<proto::PrivacyUsage> {
+    if !(delta > 0. && delta < 1.) {
+        return Err("delta: must be within (0, 1) to convert a concentrated privacy usage".into())
+    }
+    let rho = get_rho(usage)?;
+    Ok(proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon: rho + 2. * (rho * (1. / delta).ln()).sqrt(),
+            delta,
+        }))
+    })
+}
+
+/// Convert a pure epsilon-DP privacy usage to the rho-zCDP usage it implies, where rho = epsilon^2 / 2.
+///
+/// This is the conversion used to compose pure and concentrated usages.
+///     Approximate usages with a nonzero delta do not imply any concentrated usage.
+///
+/// Bun, Steinke. "Concentrated Differential Privacy: Simplifications, Extensions, and Lower Bounds" (2016), Proposition 1.4
+pub fn pure_to_concentrated(usage: &proto::PrivacyUsage) -> Result<proto::PrivacyUsage> {
+    if get_delta(usage)? != 0. {
+        return Err("approximate privacy usages with nonzero delta may not be combined with concentrated privacy usages".into())
+    }
+    Ok(proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+            rho: get_epsilon(usage)?.powi(2) / 2.
+        }))
+    })
+}
+
+/// Find the largest rho-zCDP usage that implies an (epsilon, delta) privacy usage,
+///     where rho = (sqrt(ln(1/delta) + epsilon) - sqrt(ln(1/delta)))^2.
+///
+/// This is the inverse of `concentrated_to_approximate`, and is used to calibrate mechanisms to an approximate usage.
+///     It is not a composition rule: an (epsilon, delta) usage does not imply this rho.
+pub fn approximate_to_concentrated_for_delta(usage: &proto::PrivacyUsage) -> Result<proto::PrivacyUsage> {
+    let (epsilon, delta) = (get_epsilon(usage)?, get_delta(usage)?);
+    if !(delta > 0. && delta < 1.) {
+        return Err("delta: must be within (0, 1) to convert an approximate privacy usage".into())
+    }
+    let log_delta = (1. / delta).ln();
+    Ok(proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+            rho: ((log_delta + epsilon).sqrt() - log_delta.sqrt()).powi(2)
+        }))
+    })
+}
+
+/// Standard deviation of the analytic Gaussian mechanism.
+///
+/// Finds the smallest scale at which Gaussian noise satisfies (epsilon, delta)-DP,
+///     by bisection on the exact privacy profile of the Gaussian mechanism.
+///     Unlike the classic calibration, this is valid for all epsilon > 0.
+///
+/// Balle, Wang. "Improving the Gaussian Mechanism for Differential Privacy: Analytical Calibration and Optimal Denoising" (2018), Algorithm 1
+pub fn analytic_gaussian_scale(epsilon: f64, delta: f64, sensitivity: f64) -> Result<f64> {
+    let is_valid = epsilon > 0. && delta > 0. && delta < 1. && sensitivity >= 0.;
+    if !is_valid {
+        return Err(format!("epsilon ({}) must be positive, delta ({}) must be within (0, 1) and sensitivity ({}) must be nonnegative", epsilon, delta, sensitivity).into())
+    }
+
+    // standard normal cdf
+    let phi = |x: f64| 0.5 * erf::erfc(-x / 2f64.sqrt());
+    // e^epsilon * Phi(-x), computed in log space to delay overflow
+    let scaled_phi = |x: f64| (epsilon + phi(-x).ln()).exp();
+
+    // boundary of a predicate that holds at zero and fails for large values
+    let boundary = |predicate: &dyn Fn(f64) -> bool| {
+        let mut upper = 1.;
+        while predicate(upper) { upper *= 2.; }
+        let mut lower = 0.;
+        (0..100).for_each(|_| {
+            let middle = (lower + upper) / 2.;
+            if predicate(middle) { lower = middle } else { upper = middle }
+        });
+        (lower, upper)
+    };
+
+    let delta_zero = phi(0.) - scaled_phi((2. * epsilon).sqrt());
+
+    // each branch rounds toward the larger scale
+    let alpha = if delta >= delta_zero {
+        let b_plus = |v: f64| phi((epsilon * v).sqrt()) - scaled_phi((epsilon * (v + 2.)).sqrt());
+        let (v, _) = boundary(&|v| b_plus(v) <= delta);
+        (1. + v / 2.).sqrt() - (v / 2.).sqrt()
+    } else {
+        let b_minus = |u: f64| phi(-(epsilon * u).sqrt()) - scaled_phi((epsilon * (u + 2.)).sqrt());
+        let (_, u) = boundary(&|u| b_minus(u) > delta);
+        (1. + u / 2.).sqrt() + (u / 2.).sqrt()
+    };
+
+    Ok(alpha * sensitivity / (2. * epsilon).sqrt())
+}
+
+/// Parameters of the smooth sensitivity framework with cauchy noise.
+///
+/// Cauchy noise scaled by S / s, where S is a t-smooth upper bound on the local sensitivity, satisfies (s + 2t)-DP.
+/// Half of epsilon is spent on the scale of the noise, and the remainder bounds the growth of the smooth sensitivity.
+///
+/// Bun, Steinke. "Average-Case Averages: Private Algorithms for Smooth Sensitivity and Mean Estimation" (2019)
+///
+/// # Returns
+/// (s, t), where s scales the noise and t is the smoothing parameter of the sensitivity
+pub fn cauchy_smooth_sensitivity_parameters(epsilon: f64) -> (f64, f64) {
+    (epsilon / 2., epsilon / 4.)
+}
+
+/// Probabilities that a randomized response report indicates a category,
+///     when the record is or is not in the category.
+///
+/// Binary and generalized reports are truthful with probability e^epsilon / (e^epsilon + k - 1),
+///     and otherwise one of the other k - 1 categories is chosen uniformly.
+/// Unary reports flip each of the k bits independently, spending epsilon / 2 on each of the two bits that differ between records.
+///
+/// Wang, Blocki, Li, Jha. "Locally Differentially Private Protocols for Frequency Estimation" (2017)
+///
+/// # Returns
+/// (p, q), where p is the probability of indicating the true category and q the probability of indicating any other category
+pub fn randomized_response_probabilities(encoding: &str, epsilon: f64, num_categories: usize) -> Result<(f64, f64)> {
+    if epsilon < 0. {
+        return Err("epsilon: must be non-negative".into())
+    }
+    if num_categories < 2 {
+        return Err("categories: there must be at least two categories".into())
+    }
+    let k = num_categories as f64;
+    Ok(match encoding.to_lowercase().as_str() {
+        "binary" | "generalized" => (epsilon.exp() / (epsilon.exp() + k - 1.), 1. / (epsilon.exp() + k - 1.)),
+        "unary" => ((epsilon / 2.).exp() / ((epsilon / 2.).exp() + 1.), 1. / ((epsilon / 2.).exp() + 1.)),
+        _ => return Err("encoding: must be one of [Binary, Generalized, Unary]".into())
+    })
+}
+
+/// Orders at which Rényi differential privacy curves are tracked.
+pub const RDP_ORDERS: [f64; 17] = [
+    1.25, 1.5, 1.75, 2., 2.5, 3., 4., 5., 6., 8., 12., 16., 24., 32., 64., 128., 256.];
+
+/// Evaluate an RDP curve at each of the RDP_ORDERS.
+fn rdp_curve(divergence: impl Fn(f64) -> f64) -> Vec<f64> {
+    RDP_ORDERS.iter().map(|alpha| divergence(*alpha)).collect()
+}
+
+/// ln(e^a + e^b), without overflow
+fn ln_add_exp(a: f64, b: f64) -> f64 {
+    a.max(b) + (-(a - b).abs()).exp().ln_1p()
+}
+
+/// RDP curve of the Laplace mechanism with privacy parameter epsilon.
+///
+/// Mironov. "Rényi Differential Privacy" (2017), Proposition 6
+pub fn laplace_rdp_curve(epsilon: f64) -> Vec<f64> {
+    rdp_curve(|alpha| ln_add_exp(
+        (alpha / (2. * alpha - 1.)).ln() + (alpha - 1.) * epsilon,
+        ((alpha - 1.) / (2. * alpha - 1.)).ln() - alpha * epsilon) / (alpha - 1.))
+}
+
+/// RDP curve of the Gaussian mechanism, where rho = sensitivity^2 / (2 sigma^2).
+///
+/// Mironov. "Rényi Differential Privacy" (2017), Proposition 7
+pub fn gaussian_rdp_curve(rho: f64) -> Vec<f64> {
+    rdp_curve(|alpha| alpha * rho)
+}
+
+/// RDP curve of the geometric mechanism with privacy parameter epsilon.
+///
+/// This is the Rényi divergence between two-sided geometric distributions shifted by one unit.
+/// Shifts by larger integer sensitivities with the same overall epsilon have smaller divergences,
+///     approaching the Laplace curve.
+pub fn geometric_rdp_curve(epsilon: f64) -> Vec<f64> {
+    rdp_curve(|alpha| (ln_add_exp((alpha - 1.) * epsilon, -alpha * epsilon)
+        - (-epsilon).exp().ln_1p()) / (alpha - 1.))
+}
+
+/// RDP curve implied by a privacy usage.
+///
+/// Pure epsilon-DP implies (alpha, min(epsilon, alpha epsilon^2 / 2))-RDP, and rho-zCDP implies (alpha, alpha rho)-RDP.
+/// Approximate usages with a nonzero delta have no RDP curve.
+pub fn privacy_usage_to_rdp_curve(usage: &proto::PrivacyUsage) -> Result<Vec<f64>> {
+    Ok(match usage.distance.as_ref().ok_or("distance must be defined")? {
+        proto::privacy_usage::Distance::Approximate(approximate) => {
+            if approximate.delta != 0. {
+                return Err("approximate privacy usages with nonzero delta do not have an RDP curve".into())
+            }
+            let epsilon = approximate.epsilon;
+            rdp_curve(|alpha| epsilon.min(alpha * epsilon.powi(2) / 2.))
+        },
+        proto::privacy_usage::Distance::Concentrated(concentrated) =>
+            gaussian_rdp_curve(concentrated.rho)
+    })
+}
+
+/// Convert an RDP curve to an approximate privacy usage,
+///     where epsilon = min_alpha RDP(alpha) + ln(1/delta) / (alpha - 1).
+///
+/// Mironov. "Rényi Differential Privacy" (2017), Proposition 3
+pub fn rdp_curve_to_approximate(curve: &[f64], delta: f64) -> Result<proto::PrivacyUsage> {
+    if !(delta > 0. && delta < 1.) {
+        return Err("composition_slack_delta: must be within (0, 1) to convert an RDP curve".into())
+    }
+    if curve.iter().all(|divergence| *divergence == 0.) {
+        return Ok(approximate_usage((0., 0.)))
+    }
+    let epsilon = RDP_ORDERS.iter().zip(curve.iter())
+        .map(|(alpha, divergence)| divergence + (1. / delta).ln() / (alpha - 1.))
+        .fold(f64::INFINITY, f64::min);
+
+    Ok(approximate_usage((epsilon, delta)))
+}
+
 pub fn spread_privacy_usage(usages: &[proto::PrivacyUsage], length: usize) -> Result<Vec<proto::PrivacyUsage>> {
     if usages.len() == length {
         return Ok(usages.to_owned());
@@ -464,6 +929,12 @@ pub fn spread_privacy_usage(usages: &[proto::PrivacyUsage], length: usize) -> Re
                     epsilon: approx.epsilon / (length as f64),
                     delta: approx.delta / (length as f64),
                 }))
+            }).collect(),
+        proto::privacy_usage::Distance::Concentrated(conc) => (0..length)
+            .map(|_| proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                    rho: conc.rho / (length as f64),
+                }))
             }).collect()
     })
 }
@@ -521,4 +992,90 @@ pub fn get_c_stability_multiplier(arguments: Vec<Vec<GroupId>>) -> Result<Float>
         *counts.entry(group_id.index).or_insert(0) += 1);
 
     Ok(*counts.values().max().unwrap() as Float)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test_privacy {
+    use crate::proto;
+    use crate::utilities::privacy::{compose_basic, compose_advanced, compose_optimal_homogeneous, get_rho, concentrated_to_approximate,
+        approximate_to_concentrated_for_delta, analytic_gaussian_scale, laplace_rdp_curve, geometric_rdp_curve, gaussian_rdp_curve, rdp_curve_to_approximate, get_epsilon};
+
+    #[test]
+    fn test_composition() {
+        let usages = vec![(0.01, 1e-8); 1000];
+        let basic = compose_basic(&usages);
+        let advanced = compose_advanced(&usages, 1e-6);
+        let optimal = compose_optimal_homogeneous(&usages, 1e-6).unwrap();
+
+        assert!((basic.0 - 10.).abs() < 1e-8);
+        assert!(advanced.0 < basic.0);
+        assert!(optimal.0 <= advanced.0);
+        assert!(optimal.1 <= advanced.1);
+    }
+
+    #[test]
+    fn test_optimal_heterogeneous() {
+        assert!(compose_optimal_homogeneous(&[(0.1, 0.), (0.2, 0.)], 1e-6).is_none());
+    }
+
+    #[test]
+    fn test_concentrated() {
+        use proto::privacy_usage::{Distance, DistanceApproximate, DistanceConcentrated};
+        let concentrated = proto::PrivacyUsage {
+            distance: Some(Distance::Concentrated(DistanceConcentrated { rho: 0.1 }))
+        };
+        let pure = proto::PrivacyUsage {
+            distance: Some(Distance::Approximate(DistanceApproximate { epsilon: 0.2, delta: 0. }))
+        };
+        let approximate = proto::PrivacyUsage {
+            distance: Some(Distance::Approximate(DistanceApproximate { epsilon: 0.2, delta: 1e-6 }))
+        };
+
+        let total = (concentrated.clone() + pure).unwrap();
+        assert!((get_rho(&total).unwrap() - 0.12).abs() < 1e-12);
+        assert!((concentrated.clone() + approximate).is_err());
+
+        let converted = concentrated_to_approximate(&total, 1e-6).unwrap();
+        assert!(matches!(converted.distance, Some(Distance::Approximate(_))));
+
+        // converting back recovers rho
+        let recovered = approximate_to_concentrated_for_delta(&converted).unwrap();
+        assert!((get_rho(&recovered).unwrap() - 0.12).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rdp_curves() {
+        let epsilon = 0.5;
+        let laplace = laplace_rdp_curve(epsilon);
+        let geometric = geometric_rdp_curve(epsilon);
+        laplace.iter().zip(geometric.iter()).for_each(|(laplace, geometric)| {
+            assert!(*laplace <= epsilon && *geometric <= epsilon + 1e-12);
+            assert!(laplace <= geometric);
+        });
+
+        // one hundred gaussian releases, each with rho = 0.001
+        let total = gaussian_rdp_curve(0.1);
+        let usage = rdp_curve_to_approximate(&total, 1e-6).unwrap();
+        let basic = 100. * concentrated_to_approximate(&proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Concentrated(proto::privacy_usage::DistanceConcentrated {
+                rho: 0.001
+            }))
+        }, 1e-8).map(|usage| get_epsilon(&usage).unwrap()).unwrap();
+        assert!(get_epsilon(&usage).unwrap() < basic);
+    }
+
+    #[test]
+    fn test_analytic_gaussian() {
+        let classic = |epsilon: f64, delta: f64| (2. * (1.25 / delta).ln()).sqrt() / epsilon;
+
+        // the analytic calibration adds less noise than the classic calibration wherever the latter is valid
+        [0.1, 0.5, 1.].iter().for_each(|epsilon| {
+            let analytic = analytic_gaussian_scale(*epsilon, 1e-6, 1.).unwrap();
+            assert!(analytic < classic(*epsilon, 1e-6));
+        });
+
+        // and remains defined for large epsilon
+        let large = analytic_gaussian_scale(10., 1e-6, 1.).unwrap();
+        assert!(large > 0. && large < analytic_gaussian_scale(1., 1e-6, 1.).unwrap());
+        assert!(analytic_gaussian_scale(1., 0., 1.).is_err());
+    }
+}
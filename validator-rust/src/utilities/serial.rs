@@ -2,7 +2,7 @@
 
 use crate::{proto, Integer, Float};
 use std::collections::HashMap;
-use crate::base::{Release, Nature, Jagged, Vector1D, Value, Array, Vector1DNull, NatureCategorical, NatureContinuous, AggregatorProperties, ValueProperties, JaggedProperties, DataType, ArrayProperties, ReleaseNode, GroupId, IndexKey, ComponentExpansion, DataframeProperties, PartitionsProperties};
+use crate::base::{Release, Nature, Jagged, Vector1D, Value, Array, Vector1DNull, NatureCategorical, NatureContinuous, AggregatorProperties, ValueProperties, JaggedProperties, DataType, ArrayProperties, RandomizedResponseProperties, ReleaseNode, GroupId, IndexKey, ComponentExpansion, DataframeProperties, PartitionsProperties};
 use indexmap::IndexMap;
 use error_chain::ChainedError;
 
@@ -266,7 +266,12 @@ pub fn parse_array_properties(value: proto::ArrayProperties) -> ArrayProperties
         is_not_empty: value.is_not_empty,
         dimensionality: value.dimensionality.and_then(parse_i64_null),
         group_id: value.group_id.into_iter().map(parse_group_id).collect(),
-        naturally_ordered: value.naturally_ordered
+        naturally_ordered: value.naturally_ordered,
+        randomized_response: value.randomized_response.map(|randomized_response| RandomizedResponseProperties {
+            encoding: randomized_response.encoding,
+            num_categories: randomized_response.num_categories,
+            privacy_usage: randomized_response.privacy_usage.unwrap()
+        })
     }
 }
 
@@ -533,7 +538,8 @@ pub fn serialize_array_properties(value: ArrayProperties) -> proto::ArrayPropert
         num_records, num_columns, nullity, releasable,
         c_stability, aggregator, nature,
         data_type, dataset_id, is_not_empty,
-        dimensionality, group_id, naturally_ordered
+        dimensionality, group_id, naturally_ordered,
+        randomized_response
     } = value;
 
     proto::ArrayProperties {
@@ -567,7 +573,12 @@ pub fn serialize_array_properties(value: ArrayProperties) -> proto::ArrayPropert
         is_not_empty,
         dimensionality: Some(serialize_i64_null(dimensionality)),
         group_id: group_id.into_iter().map(serialize_group_id).collect(),
-        naturally_ordered
+        naturally_ordered,
+        randomized_response: randomized_response.map(|randomized_response| proto::RandomizedResponseProperties {
+            encoding: randomized_response.encoding,
+            num_categories: randomized_response.num_categories,
+            privacy_usage: Some(randomized_response.privacy_usage)
+        })
     }
 }
 